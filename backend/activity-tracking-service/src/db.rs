@@ -1,9 +1,10 @@
-use mongodb::{Client, Database, IndexModel};
+use mongodb::{Client, Database, IndexModel, options::IndexOptions};
 use bson::doc;
 use tracing::info;
 
 use crate::config::Config;
 
+#[derive(Clone)]
 pub struct MongoDB {
     pub db: Database,
 }
@@ -58,9 +59,15 @@ impl MongoDB {
             .keys(doc! { "activity_type": 1 })
             .build();
 
+        // Index for recorded_by (who logged the activity)
+        let activity_recorded_by_index = IndexModel::builder()
+            .keys(doc! { "recorded_by": 1, "recorded_at": -1 })
+            .build();
+
         activities.create_indexes(vec![
             activity_rodent_index,
             activity_type_index,
+            activity_recorded_by_index,
         ], None).await?;
 
         // Feeding records collection indexes
@@ -76,11 +83,46 @@ impl MongoDB {
             .keys(doc! { "food_type": 1 })
             .build();
 
+        // Index for recorded_by (who logged the feeding)
+        let feeding_recorded_by_index = IndexModel::builder()
+            .keys(doc! { "recorded_by": 1, "meal_time": -1 })
+            .build();
+
         feeding_records.create_indexes(vec![
             feeding_rodent_index,
             food_type_index,
+            feeding_recorded_by_index,
         ], None).await?;
 
+        // Inactive rodents collection index (one entry per rodent)
+        let inactive_rodents = self.db.collection::<bson::Document>("inactive_rodents");
+
+        let inactive_rodent_index = IndexModel::builder()
+            .keys(doc! { "rodent_id": 1 })
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        inactive_rodents.create_index(inactive_rodent_index, None).await?;
+
+        // Idempotency keys expire on their own once past `expires_at`
+        let idempotency_keys = self.db.collection::<bson::Document>("idempotency_keys");
+
+        let idempotency_expiry_index = IndexModel::builder()
+            .keys(doc! { "expires_at": 1 })
+            .options(IndexOptions::builder().expire_after(std::time::Duration::from_secs(0)).build())
+            .build();
+
+        idempotency_keys.create_index(idempotency_expiry_index, None).await?;
+
+        // Outbox of unpublished events, relayed oldest first
+        let outbox = self.db.collection::<bson::Document>("outbox");
+
+        let outbox_created_index = IndexModel::builder()
+            .keys(doc! { "created_at": 1 })
+            .build();
+
+        outbox.create_index(outbox_created_index, None).await?;
+
         info!("MongoDB indexes created successfully for Activity Tracking Service");
 
         Ok(())
@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -23,6 +23,9 @@ pub enum AppError {
     #[error("Invalid rodent ID format")]
     InvalidRodentId,
 
+    #[error("Invalid date range")]
+    InvalidDateRange,
+
     #[error("Invalid token")]
     InvalidToken,
 
@@ -46,6 +49,43 @@ pub enum AppError {
 
     #[error("Rodent not found in registry")]
     RodentNotFound,
+
+    #[error("Rodent is {0} and no longer accepts new records")]
+    RodentInactive(String),
+
+    #[error("Request body too large")]
+    PayloadTooLarge,
+
+    #[error("A request with this Idempotency-Key is still being processed")]
+    IdempotencyKeyInUse,
+
+    #[error("This Idempotency-Key was already used with a different request")]
+    IdempotencyKeyReused,
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::DailyRecordNotFound => "DAILY_RECORD_NOT_FOUND",
+            AppError::ActivityNotFound => "ACTIVITY_NOT_FOUND",
+            AppError::FeedingRecordNotFound => "FEEDING_RECORD_NOT_FOUND",
+            AppError::InvalidId => "INVALID_ID",
+            AppError::InvalidRodentId => "INVALID_RODENT_ID",
+            AppError::InvalidDateRange => "INVALID_DATE_RANGE",
+            AppError::InvalidToken => "INVALID_TOKEN",
+            AppError::TokenExpired => "TOKEN_EXPIRED",
+            AppError::AccessDenied(_) => "ACCESS_DENIED",
+            AppError::ValidationError(_) => "VALIDATION_ERROR",
+            AppError::DatabaseError(_) => "DATABASE_ERROR",
+            AppError::InternalError => "INTERNAL_ERROR",
+            AppError::ServiceUnavailable(_) => "SERVICE_UNAVAILABLE",
+            AppError::RodentNotFound => "RODENT_NOT_FOUND",
+            AppError::RodentInactive(_) => "RODENT_INACTIVE",
+            AppError::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+            AppError::IdempotencyKeyInUse => "IDEMPOTENCY_KEY_IN_USE",
+            AppError::IdempotencyKeyReused => "IDEMPOTENCY_KEY_REUSED",
+        }
+    }
 }
 
 impl IntoResponse for AppError {
@@ -56,6 +96,7 @@ impl IntoResponse for AppError {
             AppError::FeedingRecordNotFound => (StatusCode::NOT_FOUND, self.to_string()),
             AppError::InvalidId => (StatusCode::BAD_REQUEST, self.to_string()),
             AppError::InvalidRodentId => (StatusCode::BAD_REQUEST, self.to_string()),
+            AppError::InvalidDateRange => (StatusCode::BAD_REQUEST, self.to_string()),
             AppError::InvalidToken => (StatusCode::UNAUTHORIZED, self.to_string()),
             AppError::TokenExpired => (StatusCode::UNAUTHORIZED, self.to_string()),
             AppError::AccessDenied(_) => (StatusCode::FORBIDDEN, self.to_string()),
@@ -70,17 +111,36 @@ impl IntoResponse for AppError {
             }
             AppError::ServiceUnavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
             AppError::RodentNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::RodentInactive(_) => (StatusCode::CONFLICT, self.to_string()),
+            AppError::PayloadTooLarge => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
+            AppError::IdempotencyKeyInUse => (StatusCode::CONFLICT, self.to_string()),
+            AppError::IdempotencyKeyReused => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
         };
 
         let body = Json(json!({
             "success": false,
-            "error": error_message
+            "error": error_message,
+            "code": self.code()
         }));
 
         (status, body).into_response()
     }
 }
 
+/// Replace axum's plain-text 413 (from `DefaultBodyLimit`) with the JSON error envelope
+pub async fn json_payload_too_large(response: Response) -> Response {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE && !is_json {
+        return AppError::PayloadTooLarge.into_response();
+    }
+
+    response
+}
+
 impl From<mongodb::error::Error> for AppError {
     fn from(err: mongodb::error::Error) -> Self {
         tracing::error!("MongoDB error: {:?}", err);
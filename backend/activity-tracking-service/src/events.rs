@@ -7,11 +7,27 @@ pub const EXCHANGE_NAME: &str = "rodent_care_events";
 pub const DAILY_METRICS_ROUTING_KEY: &str = "activity.daily_metrics";
 pub const FEEDING_ROUTING_KEY: &str = "activity.feeding";
 
+// Queue and routing keys this service consumes
+pub const ACTIVITY_QUEUE: &str = "activity_tracking_events";
+pub const RODENT_STATUS_CHANGED_ROUTING_KEY: &str = "registry.rodent_status_changed";
+// Consumed events with a schema version this service doesn't understand are parked here
+pub const ACTIVITY_DEAD_LETTER_QUEUE: &str = "activity_tracking_events.dead_letter";
+
+/// Version of the event payload layout; bump when a payload changes shape
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// Events published before versioning was introduced have the version 1 layout
+fn legacy_schema_version() -> u32 {
+    1
+}
+
 /// Event published when daily metrics are recorded for a rodent
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DailyMetricsRecordedEvent {
     pub event_type: String,
     pub event_id: String,
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub timestamp: DateTime<Utc>,
     pub payload: DailyMetricsPayload,
 }
@@ -35,6 +51,7 @@ impl DailyMetricsRecordedEvent {
         Self {
             event_type: "DailyMetricsRecorded".to_string(),
             event_id: uuid::Uuid::new_v4().to_string(),
+            schema_version: EVENT_SCHEMA_VERSION,
             timestamp: Utc::now(),
             payload,
         }
@@ -46,6 +63,8 @@ impl DailyMetricsRecordedEvent {
 pub struct FeedingRecordedEvent {
     pub event_type: String,
     pub event_id: String,
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub timestamp: DateTime<Utc>,
     pub payload: FeedingPayload,
 }
@@ -67,8 +86,39 @@ impl FeedingRecordedEvent {
         Self {
             event_type: "FeedingRecorded".to_string(),
             event_id: uuid::Uuid::new_v4().to_string(),
+            schema_version: EVENT_SCHEMA_VERSION,
             timestamp: Utc::now(),
             payload,
         }
     }
 }
+
+// ============== Consumed Registry Events ==============
+
+/// Just the schema version of an incoming event, read before parsing the rest
+#[derive(Debug, Deserialize)]
+pub struct EventVersion {
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
+}
+
+/// Event consumed when a rodent's status changes in the registry
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RodentStatusChangedEvent {
+    pub event_type: String,
+    pub event_id: String,
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
+    pub timestamp: DateTime<Utc>,
+    pub payload: RodentStatusChangedPayload,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RodentStatusChangedPayload {
+    pub rodent_id: String,
+    pub rodent_name: String,
+    pub old_status: String,
+    pub new_status: String,
+    pub changed_by: String,
+    pub changed_by_name: String,
+}
@@ -1,21 +1,28 @@
 use axum::{
     extract::{Path, Query, State},
     http::{header, HeaderMap},
+    response::Response,
     Json,
 };
 use bson::{doc, oid::ObjectId, Document};
-use chrono::{Utc, Datelike, TimeZone};
+use chrono::{DateTime, Utc, Datelike, TimeZone};
 use futures::TryStreamExt;
-use jsonwebtoken::{decode, DecodingKey, Validation};
-use mongodb::options::FindOptions;
+use jsonwebtoken::{decode, DecodingKey};
+use mongodb::{
+    options::{FindOneOptions, FindOptions},
+    Collection,
+};
 use std::sync::Arc;
 use validator::Validate;
 
 use crate::{
+    config::Config,
     error::AppError,
     events::{DailyMetricsPayload, DailyMetricsRecordedEvent, FeedingPayload, FeedingRecordedEvent},
-    middleware::{can_track_activities, can_view},
+    idempotency::{self, IdempotencyKey},
+    middleware::{can_purge_data, can_reassign_data, can_track_activities, can_view},
     models::*,
+    timing::QueryTimer,
     AppState,
 };
 
@@ -36,7 +43,7 @@ fn extract_auth_info(state: &AppState, headers: &HeaderMap) -> Result<AuthInfo,
     let claims = decode::<Claims>(
         token,
         &DecodingKey::from_secret(state.config.jwt_secret.as_bytes()),
-        &Validation::default(),
+        &state.config.jwt_validation(),
     )
     .map_err(|e| {
         tracing::debug!("Token validation failed: {:?}", e);
@@ -50,13 +57,332 @@ fn extract_auth_info(state: &AppState, headers: &HeaderMap) -> Result<AuthInfo,
     Ok(AuthInfo::from(claims))
 }
 
+/// Over-limit page sizes fail unless clamping is configured
+fn page_limit(config: &Config, requested: Option<u32>, default: u32) -> Result<u32, AppError> {
+    let limit = requested.unwrap_or(default.min(config.max_page_limit));
+    if limit <= config.max_page_limit {
+        return Ok(limit);
+    }
+    if config.clamp_page_limit {
+        return Ok(config.max_page_limit);
+    }
+    Err(AppError::ValidationError(format!(
+        "limit must be at most {}",
+        config.max_page_limit
+    )))
+}
+
+/// `from` after `to` would match nothing, so it is an error rather than an empty page
+fn validate_date_range(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Result<(), AppError> {
+    match (from, to) {
+        (Some(from), Some(to)) if from > to => Err(AppError::InvalidDateRange),
+        _ => Ok(()),
+    }
+}
+
+/// Assistants must be distinct, well-formed user ids other than the recorder
+fn validate_assistants(assistants: &[ActivityAssistant], recorded_by: &str) -> Result<(), AppError> {
+    let mut seen = std::collections::HashSet::new();
+    for assistant in assistants {
+        if uuid::Uuid::parse_str(&assistant.user_id).is_err() {
+            return Err(AppError::ValidationError(format!(
+                "assisted_by: '{}' is not a valid user id",
+                assistant.user_id
+            )));
+        }
+        if assistant.name.trim().is_empty() {
+            return Err(AppError::ValidationError("assisted_by: name is required".to_string()));
+        }
+        if assistant.user_id == recorded_by {
+            return Err(AppError::ValidationError(
+                "assisted_by: the recorder cannot also be an assistant".to_string(),
+            ));
+        }
+        if !seen.insert(assistant.user_id.as_str()) {
+            return Err(AppError::ValidationError(format!(
+                "assisted_by: user {} is listed more than once",
+                assistant.user_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Reject new records for rodents the registry reported as deceased or adopted
+async fn ensure_rodent_active(state: &AppState, rodent_oid: &ObjectId) -> Result<(), AppError> {
+    let inactive = state
+        .db
+        .db
+        .collection::<InactiveRodent>("inactive_rodents")
+        .find_one(doc! { "rodent_id": rodent_oid, "status": { "$in": INACTIVE_STATUSES.to_vec() } }, None)
+        .await?;
+
+    match inactive {
+        Some(entry) => Err(AppError::RodentInactive(entry.status)),
+        None => Ok(()),
+    }
+}
+
+/// Volunteers only see rodents assigned to them. The registry owns assignments,
+/// so ask its existence check with the caller's token; a hidden rodent is a 404.
+async fn ensure_rodent_visible(
+    state: &AppState,
+    headers: &HeaderMap,
+    auth_info: &AuthInfo,
+    rodent_id: &str,
+) -> Result<(), AppError> {
+    if auth_info.role != "volunteer" {
+        return Ok(());
+    }
+
+    let url = format!("{}/api/rodents/exists", state.config.rodent_service_url);
+    let mut request = state.http_client.post(&url).json(&serde_json::json!({ "ids": [rodent_id] }));
+    if let Some(auth) = headers.get(header::AUTHORIZATION).and_then(|value| value.to_str().ok()) {
+        request = request.header(reqwest::header::AUTHORIZATION, auth);
+    }
+
+    let unavailable = || AppError::ServiceUnavailable("Rodent Registry unavailable".to_string());
+    let response = request.send().await.map_err(|e| {
+        tracing::error!("Failed to reach Rodent Registry for rodent {}: {}", rodent_id, e);
+        unavailable()
+    })?;
+    if !response.status().is_success() {
+        tracing::warn!("Rodent Registry returned {} for rodent {}", response.status(), rodent_id);
+        return Err(unavailable());
+    }
+
+    let body: serde_json::Value = response.json().await.map_err(|e| {
+        tracing::error!("Invalid response from Rodent Registry: {}", e);
+        unavailable()
+    })?;
+    if body["exists"][rodent_id].as_bool() == Some(true) {
+        Ok(())
+    } else {
+        Err(AppError::RodentNotFound)
+    }
+}
+
+/// Sum a numeric field over every document matching `filter`, not just the current page
+async fn sum_field(
+    state: &AppState,
+    collection: &str,
+    filter: Document,
+    field: &str,
+) -> Result<f64, AppError> {
+    let pipeline = vec![
+        doc! { "$match": filter },
+        doc! { "$group": { "_id": null, "total": { "$sum": format!("${}", field) } } },
+    ];
+
+    let mut cursor = state
+        .db
+        .db
+        .collection::<Document>(collection)
+        .aggregate(pipeline, None)
+        .await?;
+
+    let total = match cursor.try_next().await? {
+        Some(doc) => match doc.get("total") {
+            Some(bson::Bson::Int32(v)) => *v as f64,
+            Some(bson::Bson::Int64(v)) => *v as f64,
+            Some(bson::Bson::Double(v)) => *v,
+            _ => 0.0,
+        },
+        None => 0.0,
+    };
+
+    Ok(total)
+}
+
+// ============== Type Taxonomy ==============
+
+/// Canonical activity and food type values with display labels for client dropdowns
+pub async fn list_types() -> Json<TypeTaxonomyResponse> {
+    Json(TypeTaxonomyResponse {
+        success: true,
+        activity_types: ActivityType::ALL
+            .iter()
+            .map(|t| TypeOption { value: t.as_str(), label: t.label() })
+            .collect(),
+        food_types: FoodType::ALL
+            .iter()
+            .map(|t| TypeOption { value: t.as_str(), label: t.label() })
+            .collect(),
+    })
+}
+
+/// How long facet values are served from memory before being recomputed
+const FACETS_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Sorted distinct string values of `field` across a collection
+async fn distinct_strings(state: &AppState, collection: &str, field: &str) -> Result<Vec<String>, AppError> {
+    let mut values: Vec<String> = state
+        .db
+        .db
+        .collection::<Document>(collection)
+        .distinct(field, None, None)
+        .await?
+        .into_iter()
+        .filter_map(|value| value.as_str().map(str::to_string))
+        .collect();
+    values.sort();
+    Ok(values)
+}
+
+/// Distinct activity and food types logged so far
+pub async fn get_activity_facets(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ActivityFacetsResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_view(&auth_info)?;
+
+    let cached = state
+        .facets_cache
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|(loaded_at, _)| loaded_at.elapsed() < FACETS_CACHE_TTL)
+        .map(|(_, facets)| facets.clone());
+
+    let facets = match cached {
+        Some(facets) => facets,
+        None => {
+            let facets = ActivityFacets {
+                activity_types: distinct_strings(&state, "activities", "activity_type").await?,
+                food_types: distinct_strings(&state, "feeding_records", "food_type").await?,
+            };
+            *state.facets_cache.lock().unwrap() = Some((std::time::Instant::now(), facets.clone()));
+            facets
+        }
+    };
+
+    Ok(Json(ActivityFacetsResponse { success: true, facets }))
+}
+
+// ============== Data Purge ==============
+
+/// Delete all tracking data of the given rodents (admin only). Used by the
+/// registry when purging sample rodents.
+pub async fn purge_rodent_data(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<PurgeRodentDataRequest>,
+) -> Result<Json<PurgeRodentDataResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_purge_data(&auth_info)?;
+
+    let rodent_ids = payload
+        .rodent_ids
+        .iter()
+        .map(|id| ObjectId::parse_str(id).map_err(|_| AppError::InvalidRodentId))
+        .collect::<Result<Vec<_>, _>>()?;
+    let filter = doc! { "rodent_id": { "$in": &rodent_ids } };
+
+    let daily_records = state
+        .db
+        .db
+        .collection::<DailyRecord>("daily_records")
+        .delete_many(filter.clone(), None)
+        .await?;
+    let activities = state
+        .db
+        .db
+        .collection::<Activity>("activities")
+        .delete_many(filter.clone(), None)
+        .await?;
+    let feeding_records = state
+        .db
+        .db
+        .collection::<FeedingRecord>("feeding_records")
+        .delete_many(filter, None)
+        .await?;
+
+    tracing::info!(
+        "Tracking data of {} rodents purged by user {}",
+        rodent_ids.len(),
+        auth_info.username
+    );
+
+    Ok(Json(PurgeRodentDataResponse {
+        success: true,
+        daily_records_deleted: daily_records.deleted_count,
+        activities_deleted: activities.deleted_count,
+        feeding_records_deleted: feeding_records.deleted_count,
+    }))
+}
+
+/// Point all tracking data of one rodent at another (admin only). The
+/// activity-side complement to merging or re-identifying rodents in the registry.
+pub async fn reassign_rodent_data(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<ReassignRodentDataRequest>,
+) -> Result<Json<ReassignRodentDataResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_reassign_data(&auth_info)?;
+
+    let from_id = ObjectId::parse_str(&payload.from_rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+    let to_id = ObjectId::parse_str(&payload.to_rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+    if from_id == to_id {
+        return Err(AppError::ValidationError(
+            "from_rodent_id and to_rodent_id must be different rodents".to_string(),
+        ));
+    }
+
+    // updated_at lets analytics notice that the moved records changed
+    let filter = doc! { "rodent_id": from_id };
+    let update = doc! { "$set": { "rodent_id": to_id, "updated_at": bson::DateTime::from_chrono(Utc::now()) } };
+
+    let daily_records = state
+        .db
+        .db
+        .collection::<DailyRecord>("daily_records")
+        .update_many(filter.clone(), update.clone(), None)
+        .await?;
+    let activities = state
+        .db
+        .db
+        .collection::<Activity>("activities")
+        .update_many(filter.clone(), update.clone(), None)
+        .await?;
+    let feeding_records = state
+        .db
+        .db
+        .collection::<FeedingRecord>("feeding_records")
+        .update_many(filter, update, None)
+        .await?;
+
+    tracing::info!(
+        "Tracking data of rodent {} reassigned to {} by user {} ({} daily records, {} activities, {} feeding records)",
+        payload.from_rodent_id,
+        payload.to_rodent_id,
+        auth_info.username,
+        daily_records.modified_count,
+        activities.modified_count,
+        feeding_records.modified_count
+    );
+
+    Ok(Json(ReassignRodentDataResponse {
+        success: true,
+        daily_records_moved: daily_records.modified_count,
+        activities_moved: activities.modified_count,
+        feeding_records_moved: feeding_records.modified_count,
+    }))
+}
+
 // ============== Health Check ==============
 
-pub async fn health_check() -> Json<serde_json::Value> {
+/// Service status, with the number of events waiting in the outbox (null if
+/// it couldn't be counted) as a measure of event lag
+pub async fn health_check(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let outbox_backlog = state.publisher.outbox_backlog().await.ok();
+
     Json(serde_json::json!({
         "success": true,
         "service": "activity-tracking-service",
-        "status": "healthy"
+        "status": "healthy",
+        "outbox_backlog": outbox_backlog
     }))
 }
 
@@ -69,9 +395,12 @@ pub async fn list_daily_records(
     Query(params): Query<DailyRecordQueryParams>,
 ) -> Result<Json<DailyRecordListResponse>, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "list_daily_records");
     can_view(&auth_info)?;
 
     let rodent_oid = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+    ensure_rodent_visible(&state, &headers, &auth_info, &rodent_id).await?;
+    validate_date_range(params.from_date, params.to_date)?;
 
     let collection = state.db.db.collection::<DailyRecord>("daily_records");
 
@@ -91,7 +420,7 @@ pub async fn list_daily_records(
     }
 
     let page = params.page.unwrap_or(1).max(1);
-    let limit = params.limit.unwrap_or(30).min(100);
+    let limit = page_limit(&state.config, params.limit, 30)?;
 
     let total = collection.count_documents(filter.clone(), None).await?;
 
@@ -109,9 +438,7 @@ pub async fn list_daily_records(
     Ok(Json(DailyRecordListResponse {
         success: true,
         daily_records,
-        total,
-        page,
-        limit,
+        pagination: Pagination::new(total, page, limit),
     }))
 }
 
@@ -124,6 +451,7 @@ pub async fn get_daily_record(
     can_view(&auth_info)?;
 
     let rodent_oid = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+    ensure_rodent_visible(&state, &headers, &auth_info, &rodent_id).await?;
     let record_oid = ObjectId::parse_str(&record_id).map_err(|_| AppError::InvalidId)?;
 
     let collection = state.db.db.collection::<DailyRecord>("daily_records");
@@ -144,63 +472,69 @@ pub async fn create_daily_record(
     Path(rodent_id): Path<String>,
     headers: HeaderMap,
     Json(payload): Json<CreateDailyRecordRequest>,
-) -> Result<Json<SingleDailyRecordResponse>, AppError> {
+) -> Result<Response, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
     can_track_activities(&auth_info)?;
 
     payload.validate()?;
 
-    let rodent_oid = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
-
-    let now = Utc::now();
-    let date = payload.date
-        .map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc())
-        .unwrap_or(now);
-
-    let record = DailyRecord {
-        id: None,
-        rodent_id: rodent_oid,
-        date,
-        weight_grams: payload.weight_grams,
-        temperature_celsius: payload.temperature_celsius,
-        energy_level: payload.energy_level,
-        mood_level: payload.mood_level,
-        behavior_notes: payload.behavior_notes,
-        health_observations: payload.health_observations,
-        created_by: auth_info.user_id.clone(),
-        created_by_name: auth_info.username.clone(),
-        created_at: now,
-        updated_at: now,
-    };
-
-    let collection = state.db.db.collection::<DailyRecord>("daily_records");
-    let result = collection.insert_one(&record, None).await?;
-
-    let inserted_id = result.inserted_id.as_object_id().ok_or(AppError::InternalError)?;
-    let mut created_record = record;
-    created_record.id = Some(inserted_id);
-
-    // Publish DailyMetricsRecorded event
-    let event = DailyMetricsRecordedEvent::new(DailyMetricsPayload {
-        record_id: inserted_id.to_hex(),
-        rodent_id: rodent_id.clone(),
-        date,
-        weight_grams: created_record.weight_grams,
-        temperature_celsius: created_record.temperature_celsius,
-        energy_level: created_record.energy_level,
-        mood_level: created_record.mood_level,
-        has_health_observations: created_record.health_observations.is_some(),
-        recorded_by: auth_info.user_id.clone(),
-        recorded_by_name: auth_info.username.clone(),
-    });
-    if let Err(e) = state.publisher.publish_daily_metrics(&event).await {
-        tracing::warn!("Failed to publish DailyMetricsRecorded event: {}", e);
-    }
+    let key = IdempotencyKey::from_headers(&headers, &auth_info.user_id, "create_daily_record", &(&rodent_id, &payload))?;
+
+    idempotency::run_once(&state, key, async {
+        let rodent_oid = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+        ensure_rodent_active(&state, &rodent_oid).await?;
+
+        let now = Utc::now();
+        let date = payload.date
+            .map(|d| state.config.start_of_day(d))
+            .unwrap_or(now);
+
+        let record = DailyRecord {
+            id: None,
+            rodent_id: rodent_oid,
+            date,
+            weight_grams: payload.weight_grams,
+            temperature_celsius: payload.temperature_celsius,
+            energy_level: payload.energy_level,
+            mood_level: payload.mood_level,
+            behavior_notes: payload.behavior_notes,
+            health_observations: payload.health_observations,
+            created_by: auth_info.user_id.clone(),
+            created_by_name: auth_info.username.clone(),
+            created_at: now,
+            updated_at: now,
+        };
+
+        let collection = state.db.db.collection::<DailyRecord>("daily_records");
+        let result = collection.insert_one(&record, None).await?;
+
+        let inserted_id = result.inserted_id.as_object_id().ok_or(AppError::InternalError)?;
+        let mut created_record = record;
+        created_record.id = Some(inserted_id);
+
+        // Publish DailyMetricsRecorded event
+        let event = DailyMetricsRecordedEvent::new(DailyMetricsPayload {
+            record_id: inserted_id.to_hex(),
+            rodent_id: rodent_id.clone(),
+            date,
+            weight_grams: created_record.weight_grams,
+            temperature_celsius: created_record.temperature_celsius,
+            energy_level: created_record.energy_level,
+            mood_level: created_record.mood_level,
+            has_health_observations: created_record.health_observations.is_some(),
+            recorded_by: auth_info.user_id.clone(),
+            recorded_by_name: auth_info.username.clone(),
+        });
+        if let Err(e) = state.publisher.publish_daily_metrics(&event).await {
+            tracing::warn!("Failed to publish DailyMetricsRecorded event: {}", e);
+        }
 
-    Ok(Json(SingleDailyRecordResponse {
-        success: true,
-        daily_record: created_record.into(),
-    }))
+        Ok(SingleDailyRecordResponse {
+            success: true,
+            daily_record: created_record.into(),
+        })
+    })
+    .await
 }
 
 pub async fn update_daily_record(
@@ -296,9 +630,12 @@ pub async fn list_activities(
     Query(params): Query<ActivityQueryParams>,
 ) -> Result<Json<ActivityListResponse>, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "list_activities");
     can_view(&auth_info)?;
 
     let rodent_oid = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+    ensure_rodent_visible(&state, &headers, &auth_info, &rodent_id).await?;
+    validate_date_range(params.from_date, params.to_date)?;
 
     let collection = state.db.db.collection::<Activity>("activities");
 
@@ -307,6 +644,9 @@ pub async fn list_activities(
     if let Some(activity_type) = params.activity_type {
         filter.insert("activity_type", activity_type.as_str());
     }
+    if let Some(recorded_by) = params.recorded_by {
+        filter.insert("recorded_by", recorded_by);
+    }
     if let Some(from_date) = params.from_date {
         filter.insert("recorded_at", doc! { "$gte": bson::DateTime::from_chrono(from_date) });
     }
@@ -321,9 +661,10 @@ pub async fn list_activities(
     }
 
     let page = params.page.unwrap_or(1).max(1);
-    let limit = params.limit.unwrap_or(30).min(100);
+    let limit = page_limit(&state.config, params.limit, 30)?;
 
     let total = collection.count_documents(filter.clone(), None).await?;
+    let total_minutes = sum_field(&state, "activities", filter.clone(), "duration_minutes").await? as i64;
 
     let find_options = FindOptions::builder()
         .skip(((page - 1) * limit) as u64)
@@ -339,9 +680,8 @@ pub async fn list_activities(
     Ok(Json(ActivityListResponse {
         success: true,
         activities,
-        total,
-        page,
-        limit,
+        total_minutes,
+        pagination: Pagination::new(total, page, limit),
     }))
 }
 
@@ -350,38 +690,126 @@ pub async fn create_activity(
     Path(rodent_id): Path<String>,
     headers: HeaderMap,
     Json(payload): Json<CreateActivityRequest>,
-) -> Result<Json<SingleActivityResponse>, AppError> {
+) -> Result<Response, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
     can_track_activities(&auth_info)?;
 
     payload.validate()?;
+    validate_assistants(&payload.assisted_by, &auth_info.user_id)?;
+
+    let key = IdempotencyKey::from_headers(&headers, &auth_info.user_id, "create_activity", &(&rodent_id, &payload))?;
+
+    idempotency::run_once(&state, key, async {
+        let rodent_oid = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+        ensure_rodent_active(&state, &rodent_oid).await?;
+
+        let now = Utc::now();
+
+        let activity = Activity {
+            id: None,
+            rodent_id: rodent_oid,
+            activity_type: payload.activity_type,
+            duration_minutes: payload.duration_minutes,
+            notes: payload.notes,
+            recorded_at: payload.recorded_at.unwrap_or(now),
+            recorded_by: auth_info.user_id.clone(),
+            recorded_by_name: auth_info.username.clone(),
+            assisted_by: payload.assisted_by,
+            created_at: now,
+        };
+
+        let collection = state.db.db.collection::<Activity>("activities");
+        let result = collection.insert_one(&activity, None).await?;
+
+        let inserted_id = result.inserted_id.as_object_id().ok_or(AppError::InternalError)?;
+        let mut created_activity = activity;
+        created_activity.id = Some(inserted_id);
+
+        Ok(SingleActivityResponse {
+            success: true,
+            activity: created_activity.into(),
+        })
+    })
+    .await
+}
 
-    let rodent_oid = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
-
-    let now = Utc::now();
+/// Record one shared session (e.g. group play) as an activity for each rodent
+pub async fn create_group_activity(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateGroupActivityRequest>,
+) -> Result<Json<GroupActivityResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_track_activities(&auth_info)?;
 
-    let activity = Activity {
-        id: None,
-        rodent_id: rodent_oid,
-        activity_type: payload.activity_type,
-        duration_minutes: payload.duration_minutes,
-        notes: payload.notes,
-        recorded_at: payload.recorded_at.unwrap_or(now),
-        recorded_by: auth_info.user_id.clone(),
-        recorded_by_name: auth_info.username.clone(),
-        created_at: now,
-    };
+    payload.validate()?;
+    validate_assistants(&payload.assisted_by, &auth_info.user_id)?;
+
+    // Keep request order, dropping repeats
+    let mut rodent_oids: Vec<ObjectId> = Vec::with_capacity(payload.rodent_ids.len());
+    for id in &payload.rodent_ids {
+        let oid = ObjectId::parse_str(id).map_err(|_| AppError::InvalidRodentId)?;
+        if !rodent_oids.contains(&oid) {
+            rodent_oids.push(oid);
+        }
+    }
 
-    let collection = state.db.db.collection::<Activity>("activities");
-    let result = collection.insert_one(&activity, None).await?;
+    // Reject the whole group if any member is no longer active
+    let inactive = state
+        .db
+        .db
+        .collection::<InactiveRodent>("inactive_rodents")
+        .find_one(
+            doc! { "rodent_id": { "$in": &rodent_oids }, "status": { "$in": INACTIVE_STATUSES.to_vec() } },
+            None,
+        )
+        .await?;
+    if let Some(entry) = inactive {
+        return Err(AppError::RodentInactive(entry.status));
+    }
 
-    let inserted_id = result.inserted_id.as_object_id().ok_or(AppError::InternalError)?;
-    let mut created_activity = activity;
-    created_activity.id = Some(inserted_id);
+    let now = Utc::now();
+    let recorded_at = payload.recorded_at.unwrap_or(now);
+
+    let activities: Vec<Activity> = rodent_oids
+        .into_iter()
+        .map(|rodent_oid| Activity {
+            id: None,
+            rodent_id: rodent_oid,
+            activity_type: payload.activity_type.clone(),
+            duration_minutes: payload.duration_minutes,
+            notes: payload.notes.clone(),
+            recorded_at,
+            recorded_by: auth_info.user_id.clone(),
+            recorded_by_name: auth_info.username.clone(),
+            assisted_by: payload.assisted_by.clone(),
+            created_at: now,
+        })
+        .collect();
 
-    Ok(Json(SingleActivityResponse {
+    let collection = state.db.db.collection::<Activity>("activities");
+    let result = collection.insert_many(&activities, None).await?;
+
+    // insert_many reports ids keyed by input position
+    let created = activities
+        .iter()
+        .enumerate()
+        .map(|(index, activity)| {
+            let activity_id = result
+                .inserted_ids
+                .get(&index)
+                .and_then(|id| id.as_object_id())
+                .ok_or(AppError::InternalError)?;
+            Ok(GroupActivityCreated {
+                rodent_id: activity.rodent_id.to_hex(),
+                activity_id: activity_id.to_hex(),
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    Ok(Json(GroupActivityResponse {
         success: true,
-        activity: created_activity.into(),
+        activities: created,
     }))
 }
 
@@ -414,6 +842,103 @@ pub async fn delete_activity(
 
 // ============== Feeding Records ==============
 
+/// Recommended per-meal portions for a species, in food type order
+pub async fn get_feeding_recommendations(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<FeedingRecommendationsQueryParams>,
+) -> Result<Json<FeedingRecommendationsResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_view(&auth_info)?;
+
+    let species = params.species.trim().to_lowercase();
+    if species.is_empty() {
+        return Err(AppError::ValidationError("species is required".to_string()));
+    }
+
+    let recommendations = FoodType::ALL
+        .iter()
+        .filter_map(|food_type| {
+            state
+                .config
+                .recommended_portion(&species, food_type.as_str())
+                .map(|grams| PortionRecommendation {
+                    food_type: food_type.as_str(),
+                    label: food_type.label(),
+                    grams,
+                })
+        })
+        .collect();
+
+    Ok(Json(FeedingRecommendationsResponse {
+        success: true,
+        species,
+        tolerance_percent: state.config.portion_deviation_percent,
+        recommendations,
+    }))
+}
+
+/// How long a feeding create waits on the registry for the portion check
+const PORTION_CHECK_TIMEOUT_MS: u64 = 1500;
+
+/// Species of a rodent as reported by the registry, using the caller's token.
+/// Uses the batch lookup, which leaves out image data, as `GET /rodents/:id`
+/// would send every photo along. Best effort: any failure, including a slow
+/// registry, just means no portion check.
+async fn fetch_rodent_species(state: &AppState, headers: &HeaderMap, rodent_id: &str) -> Option<String> {
+    let url = format!("{}/api/rodents/batch", state.config.rodent_service_url);
+    let mut request = state
+        .http_client
+        .post(&url)
+        .json(&serde_json::json!({ "ids": [rodent_id] }))
+        .timeout(std::time::Duration::from_millis(PORTION_CHECK_TIMEOUT_MS));
+    if let Some(auth) = headers.get(header::AUTHORIZATION).and_then(|value| value.to_str().ok()) {
+        request = request.header(reqwest::header::AUTHORIZATION, auth);
+    }
+
+    let response = match request.send().await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            tracing::warn!("Rodent Registry returned {} for rodent {}", response.status(), rodent_id);
+            return None;
+        }
+        Err(e) => {
+            tracing::warn!("Failed to reach Rodent Registry for rodent {}: {}", rodent_id, e);
+            return None;
+        }
+    };
+
+    let body: serde_json::Value = response.json().await.ok()?;
+    body["rodents"][0]["species"].as_str().map(str::to_string)
+}
+
+/// Compare a logged quantity with the species' recommended portion, if there is one
+async fn check_portion(
+    state: &AppState,
+    headers: &HeaderMap,
+    rodent_id: &str,
+    food_type: &FoodType,
+    quantity_grams: f64,
+) -> Option<PortionCheck> {
+    if state.config.feeding_portions.is_empty() {
+        return None;
+    }
+
+    let species = fetch_rodent_species(state, headers, rodent_id).await?;
+    let recommended_grams = state.config.recommended_portion(&species, food_type.as_str())?;
+    if recommended_grams <= 0.0 {
+        return None;
+    }
+
+    let deviation_percent = (quantity_grams - recommended_grams) / recommended_grams * 100.0;
+    Some(PortionCheck {
+        species,
+        recommended_grams,
+        deviation_percent: (deviation_percent * 10.0).round() / 10.0,
+        exceeds_tolerance: deviation_percent.abs() > state.config.portion_deviation_percent,
+    })
+}
+
 pub async fn list_feeding_records(
     State(state): State<Arc<AppState>>,
     Path(rodent_id): Path<String>,
@@ -421,9 +946,12 @@ pub async fn list_feeding_records(
     Query(params): Query<FeedingQueryParams>,
 ) -> Result<Json<FeedingRecordListResponse>, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "list_feeding_records");
     can_view(&auth_info)?;
 
     let rodent_oid = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+    ensure_rodent_visible(&state, &headers, &auth_info, &rodent_id).await?;
+    validate_date_range(params.from_date, params.to_date)?;
 
     let collection = state.db.db.collection::<FeedingRecord>("feeding_records");
 
@@ -432,6 +960,9 @@ pub async fn list_feeding_records(
     if let Some(food_type) = params.food_type {
         filter.insert("food_type", food_type.as_str());
     }
+    if let Some(recorded_by) = params.recorded_by {
+        filter.insert("recorded_by", recorded_by);
+    }
     if let Some(from_date) = params.from_date {
         filter.insert("meal_time", doc! { "$gte": bson::DateTime::from_chrono(from_date) });
     }
@@ -446,9 +977,10 @@ pub async fn list_feeding_records(
     }
 
     let page = params.page.unwrap_or(1).max(1);
-    let limit = params.limit.unwrap_or(30).min(100);
+    let limit = page_limit(&state.config, params.limit, 30)?;
 
     let total = collection.count_documents(filter.clone(), None).await?;
+    let total_grams = sum_field(&state, "feeding_records", filter.clone(), "quantity_grams").await?;
 
     let find_options = FindOptions::builder()
         .skip(((page - 1) * limit) as u64)
@@ -464,9 +996,8 @@ pub async fn list_feeding_records(
     Ok(Json(FeedingRecordListResponse {
         success: true,
         feeding_records,
-        total,
-        page,
-        limit,
+        total_grams,
+        pagination: Pagination::new(total, page, limit),
     }))
 }
 
@@ -475,55 +1006,66 @@ pub async fn create_feeding_record(
     Path(rodent_id): Path<String>,
     headers: HeaderMap,
     Json(payload): Json<CreateFeedingRecordRequest>,
-) -> Result<Json<SingleFeedingRecordResponse>, AppError> {
+) -> Result<Response, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
     can_track_activities(&auth_info)?;
 
     payload.validate()?;
 
-    let rodent_oid = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
-
-    let now = Utc::now();
-
-    let record = FeedingRecord {
-        id: None,
-        rodent_id: rodent_oid,
-        food_type: payload.food_type,
-        quantity_grams: payload.quantity_grams,
-        meal_time: payload.meal_time.unwrap_or(now),
-        notes: payload.notes,
-        consumed_fully: payload.consumed_fully,
-        recorded_by: auth_info.user_id.clone(),
-        recorded_by_name: auth_info.username.clone(),
-        created_at: now,
-    };
-
-    let collection = state.db.db.collection::<FeedingRecord>("feeding_records");
-    let result = collection.insert_one(&record, None).await?;
-
-    let inserted_id = result.inserted_id.as_object_id().ok_or(AppError::InternalError)?;
-    let mut created_record = record.clone();
-    created_record.id = Some(inserted_id);
+    let key = IdempotencyKey::from_headers(&headers, &auth_info.user_id, "create_feeding_record", &(&rodent_id, &payload))?;
+
+    idempotency::run_once(&state, key, async {
+        let rodent_oid = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+        ensure_rodent_active(&state, &rodent_oid).await?;
+
+        let now = Utc::now();
+
+        let record = FeedingRecord {
+            id: None,
+            rodent_id: rodent_oid,
+            food_type: payload.food_type,
+            quantity_grams: payload.quantity_grams,
+            meal_time: payload.meal_time.unwrap_or(now),
+            notes: payload.notes,
+            consumed_fully: payload.consumed_fully,
+            recorded_by: auth_info.user_id.clone(),
+            recorded_by_name: auth_info.username.clone(),
+            created_at: now,
+            updated_at: None,
+        };
+
+        let collection = state.db.db.collection::<FeedingRecord>("feeding_records");
+        let result = collection.insert_one(&record, None).await?;
+
+        let inserted_id = result.inserted_id.as_object_id().ok_or(AppError::InternalError)?;
+        let mut created_record = record.clone();
+        created_record.id = Some(inserted_id);
+
+        // Publish FeedingRecorded event
+        let event = FeedingRecordedEvent::new(FeedingPayload {
+            record_id: inserted_id.to_hex(),
+            rodent_id: rodent_id.clone(),
+            feeding_time: record.meal_time,
+            food_type: record.food_type.as_str().to_string(),
+            quantity_grams: record.quantity_grams,
+            was_eaten: record.consumed_fully.unwrap_or(true),
+            recorded_by: auth_info.user_id.clone(),
+            recorded_by_name: auth_info.username.clone(),
+        });
+        if let Err(e) = state.publisher.publish_feeding(&event).await {
+            tracing::warn!("Failed to publish FeedingRecorded event: {}", e);
+        }
 
-    // Publish FeedingRecorded event
-    let event = FeedingRecordedEvent::new(FeedingPayload {
-        record_id: inserted_id.to_hex(),
-        rodent_id: rodent_id.clone(),
-        feeding_time: record.meal_time,
-        food_type: record.food_type.as_str().to_string(),
-        quantity_grams: record.quantity_grams,
-        was_eaten: record.consumed_fully.unwrap_or(true),
-        recorded_by: auth_info.user_id.clone(),
-        recorded_by_name: auth_info.username.clone(),
-    });
-    if let Err(e) = state.publisher.publish_feeding(&event).await {
-        tracing::warn!("Failed to publish FeedingRecorded event: {}", e);
-    }
+        let portion_check =
+            check_portion(&state, &headers, &rodent_id, &record.food_type, record.quantity_grams).await;
 
-    Ok(Json(SingleFeedingRecordResponse {
-        success: true,
-        feeding_record: created_record.into(),
-    }))
+        Ok(SingleFeedingRecordResponse {
+            success: true,
+            feeding_record: created_record.into(),
+            portion_check,
+        })
+    })
+    .await
 }
 
 pub async fn update_feeding_record(
@@ -558,6 +1100,7 @@ pub async fn update_feeding_record(
     if let Some(consumed) = payload.consumed_fully {
         update_doc.insert("consumed_fully", consumed);
     }
+    update_doc.insert("updated_at", bson::DateTime::from_chrono(Utc::now()));
 
     collection
         .update_one(
@@ -575,6 +1118,7 @@ pub async fn update_feeding_record(
     Ok(Json(SingleFeedingRecordResponse {
         success: true,
         feeding_record: updated_record.into(),
+        portion_check: None,
     }))
 }
 
@@ -617,6 +1161,7 @@ pub async fn get_daily_summary(
     can_view(&auth_info)?;
 
     let rodent_oid = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+    ensure_rodent_visible(&state, &headers, &auth_info, &rodent_id).await?;
 
     // Parse date from string (format: YYYY-MM-DD)
     let date = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
@@ -627,15 +1172,28 @@ pub async fn get_daily_summary(
     // For UTC+1, offset is -60, so local midnight = UTC midnight + 60 minutes = 01:00 UTC previous day
     // To get UTC time from local time: UTC = local + offset
     // So for local midnight at UTC+1: UTC = 00:00 + (-60min) = 23:00 previous day UTC
-    let tz_offset_minutes = params.tz_offset.unwrap_or(0);
-    let offset_duration = chrono::Duration::minutes(tz_offset_minutes as i64);
-    
-    let local_start = date.and_hms_opt(0, 0, 0).unwrap();
-    let local_end = date.and_hms_opt(23, 59, 59).unwrap();
-    
-    // Convert local time to UTC by adding the offset
-    let start_of_day = Utc.from_utc_datetime(&local_start) + offset_duration;
-    let end_of_day = Utc.from_utc_datetime(&local_end) + offset_duration;
+    // Without an explicit offset the day follows the server's configured timezone
+    let (start_of_day, end_of_day) = match params.tz_offset {
+        Some(tz_offset_minutes) => {
+            let offset_duration = chrono::Duration::minutes(tz_offset_minutes as i64);
+
+            let local_start = date.and_hms_opt(0, 0, 0).unwrap();
+            let local_end = date.and_hms_opt(23, 59, 59).unwrap();
+
+            // Convert local time to UTC by adding the offset
+            (
+                Utc.from_utc_datetime(&local_start) + offset_duration,
+                Utc.from_utc_datetime(&local_end) + offset_duration,
+            )
+        }
+        None => {
+            let next_day = date.succ_opt().ok_or_else(|| AppError::ValidationError("Date out of range".to_string()))?;
+            (
+                state.config.start_of_day(date),
+                state.config.start_of_day(next_day) - chrono::Duration::seconds(1),
+            )
+        }
+    };
 
     // Get daily record
     let daily_collection = state.db.db.collection::<DailyRecord>("daily_records");
@@ -699,3 +1257,124 @@ pub async fn get_daily_summary(
         total_food_grams,
     }))
 }
+
+// ============== Latest Vitals ==============
+
+/// Newest daily record for the rodent that has `field` set, projected to that field and its date
+async fn latest_vital(
+    collection: &Collection<Document>,
+    rodent_oid: ObjectId,
+    field: &str,
+) -> Result<Option<Document>, AppError> {
+    let options = FindOneOptions::builder()
+        .sort(doc! { "date": -1 })
+        .projection(doc! { field: 1, "date": 1 })
+        .build();
+
+    Ok(collection
+        .find_one(doc! { "rodent_id": rodent_oid, field: { "$ne": null } }, options)
+        .await?)
+}
+
+fn vital_date(record: &Document) -> Option<DateTime<Utc>> {
+    record.get_datetime("date").ok().map(|date| date.to_chrono())
+}
+
+fn f64_reading(record: Option<Document>, field: &str) -> Option<VitalReading<f64>> {
+    let record = record?;
+    let value = record
+        .get_f64(field)
+        .or_else(|_| record.get_i32(field).map(f64::from))
+        .or_else(|_| record.get_i64(field).map(|v| v as f64))
+        .ok()?;
+    Some(VitalReading { value, date: vital_date(&record)? })
+}
+
+fn i32_reading(record: Option<Document>, field: &str) -> Option<VitalReading<i32>> {
+    let record = record?;
+    let value = record.get_i32(field).ok()?;
+    Some(VitalReading { value, date: vital_date(&record)? })
+}
+
+pub async fn get_latest_vitals(
+    State(state): State<Arc<AppState>>,
+    Path(rodent_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<LatestVitalsResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_view(&auth_info)?;
+
+    let rodent_oid = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+    ensure_rodent_visible(&state, &headers, &auth_info, &rodent_id).await?;
+    let collection = state.db.db.collection::<Document>("daily_records");
+
+    let (weight, temperature, energy, mood) = futures::try_join!(
+        latest_vital(&collection, rodent_oid, "weight_grams"),
+        latest_vital(&collection, rodent_oid, "temperature_celsius"),
+        latest_vital(&collection, rodent_oid, "energy_level"),
+        latest_vital(&collection, rodent_oid, "mood_level"),
+    )?;
+
+    Ok(Json(LatestVitalsResponse {
+        success: true,
+        rodent_id,
+        weight_grams: f64_reading(weight, "weight_grams"),
+        temperature_celsius: f64_reading(temperature, "temperature_celsius"),
+        energy_level: i32_reading(energy, "energy_level"),
+        mood_level: i32_reading(mood, "mood_level"),
+    }))
+}
+
+// ============== Bulk Cleanup ==============
+
+/// Delete all daily records, activities and feeding records for a rodent.
+/// Used to clean up after a rodent has been removed from the registry.
+pub async fn delete_all_rodent_records(
+    State(state): State<Arc<AppState>>,
+    Path(rodent_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<BulkDeleteResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_track_activities(&auth_info)?;
+
+    let rodent_oid = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+    let filter = doc! { "rodent_id": rodent_oid };
+
+    let daily_result = state
+        .db
+        .db
+        .collection::<DailyRecord>("daily_records")
+        .delete_many(filter.clone(), None)
+        .await?;
+
+    let activities_result = state
+        .db
+        .db
+        .collection::<Activity>("activities")
+        .delete_many(filter.clone(), None)
+        .await?;
+
+    let feeding_result = state
+        .db
+        .db
+        .collection::<FeedingRecord>("feeding_records")
+        .delete_many(filter, None)
+        .await?;
+
+    tracing::info!(
+        "Deleted all records for rodent {} by user {} (daily: {}, activities: {}, feeding: {})",
+        rodent_id,
+        auth_info.username,
+        daily_result.deleted_count,
+        activities_result.deleted_count,
+        feeding_result.deleted_count
+    );
+
+    Ok(Json(BulkDeleteResponse {
+        success: true,
+        rodent_id,
+        daily_records_deleted: daily_result.deleted_count,
+        activities_deleted: activities_result.deleted_count,
+        feeding_records_deleted: feeding_result.deleted_count,
+    }))
+}
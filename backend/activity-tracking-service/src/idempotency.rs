@@ -0,0 +1,179 @@
+use axum::{
+    http::{header, HeaderMap, HeaderName, HeaderValue},
+    response::{IntoResponse, Response},
+    Json,
+};
+use bson::doc;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+
+use crate::{error::AppError, AppState};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Set on responses served from the store instead of a fresh insert
+pub const IDEMPOTENT_REPLAY_HEADER: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+const MAX_KEY_LENGTH: usize = 255;
+
+/// A claimed idempotency key and, once the request succeeded, its response body.
+/// Until then `expires_at` is a short lease; removed by the TTL index on it.
+#[derive(Debug, Serialize, Deserialize)]
+struct IdempotencyRecord {
+    #[serde(rename = "_id")]
+    key: String,
+    #[serde(default)]
+    request_hash: String,
+    response: Option<String>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    expires_at: DateTime<Utc>,
+}
+
+/// Client-supplied `Idempotency-Key`, scoped to the user and endpoint so keys
+/// from different callers or endpoints never collide, along with a hash of
+/// the request it was sent with
+pub struct IdempotencyKey {
+    key: String,
+    request_hash: String,
+}
+
+impl IdempotencyKey {
+    /// `request` is everything that identifies the create, e.g. the rodent id and payload
+    pub fn from_headers<R: Serialize>(
+        headers: &HeaderMap,
+        user_id: &str,
+        endpoint: &str,
+        request: &R,
+    ) -> Result<Option<Self>, AppError> {
+        let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+            return Ok(None);
+        };
+
+        let key = value
+            .to_str()
+            .map_err(|_| AppError::ValidationError("Idempotency-Key must be printable ASCII".to_string()))?
+            .trim();
+
+        if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+            return Err(AppError::ValidationError(format!(
+                "Idempotency-Key must be between 1 and {} characters",
+                MAX_KEY_LENGTH
+            )));
+        }
+
+        let request = serde_json::to_vec(request).map_err(|_| AppError::InternalError)?;
+
+        Ok(Some(Self {
+            key: format!("{}:{}:{}", user_id, endpoint, key),
+            request_hash: hex::encode(Sha256::digest(&request)),
+        }))
+    }
+}
+
+/// Run `create` at most once per idempotency key. A repeated key gets the
+/// original response back, or an error if it comes with a different request;
+/// a key whose first request is still running gets a conflict until its lease
+/// runs out. Failed requests release their key so the client can retry.
+pub async fn run_once<T, F>(state: &AppState, key: Option<IdempotencyKey>, create: F) -> Result<Response, AppError>
+where
+    T: Serialize,
+    F: Future<Output = Result<T, AppError>>,
+{
+    let Some(IdempotencyKey { key, request_hash }) = key else {
+        return create.await.map(|result| Json(result).into_response());
+    };
+
+    let collection = state.db.db.collection::<IdempotencyRecord>("idempotency_keys");
+    let now = Utc::now();
+    let lease_expires_at = now + Duration::seconds(state.config.idempotency_lease_secs);
+
+    let claim = IdempotencyRecord {
+        key: key.clone(),
+        request_hash: request_hash.clone(),
+        response: None,
+        created_at: now,
+        expires_at: lease_expires_at,
+    };
+
+    if let Err(e) = collection.insert_one(&claim, None).await {
+        if !is_duplicate_key(&e) {
+            return Err(e.into());
+        }
+
+        let Some(existing) = collection.find_one(doc! { "_id": &key }, None).await? else {
+            // Expired and purged in between; the client can simply retry
+            return Err(AppError::IdempotencyKeyInUse);
+        };
+        if existing.request_hash != request_hash {
+            return Err(AppError::IdempotencyKeyReused);
+        }
+        if let Some(body) = existing.response {
+            return Ok(replay(body));
+        }
+
+        // The first request may have died without releasing its key; take
+        // over the reservation once its lease has run out
+        let takeover = collection
+            .update_one(
+                doc! {
+                    "_id": &key,
+                    "response": null,
+                    "expires_at": { "$lt": bson::DateTime::from_chrono(now) },
+                },
+                doc! { "$set": { "expires_at": bson::DateTime::from_chrono(lease_expires_at) } },
+                None,
+            )
+            .await?;
+        if takeover.modified_count == 0 {
+            return Err(AppError::IdempotencyKeyInUse);
+        }
+    }
+
+    match create.await {
+        Ok(result) => {
+            let body = serde_json::to_string(&result).map_err(|_| AppError::InternalError)?;
+            // Keep the response around for replays for the full TTL
+            let expires_at = Utc::now() + Duration::seconds(state.config.idempotency_ttl_secs);
+            if let Err(e) = collection
+                .update_one(
+                    doc! { "_id": &key },
+                    doc! { "$set": { "response": &body, "expires_at": bson::DateTime::from_chrono(expires_at) } },
+                    None,
+                )
+                .await
+            {
+                tracing::warn!("Failed to store response for idempotency key {}: {}", key, e);
+            }
+            Ok(Json(result).into_response())
+        }
+        Err(err) => {
+            if let Err(e) = collection.delete_one(doc! { "_id": &key }, None).await {
+                tracing::warn!("Failed to release idempotency key {}: {}", key, e);
+            }
+            Err(err)
+        }
+    }
+}
+
+fn replay(body: String) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static("application/json")),
+            (IDEMPOTENT_REPLAY_HEADER, HeaderValue::from_static("true")),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+fn is_duplicate_key(err: &mongodb::error::Error) -> bool {
+    matches!(
+        err.kind.as_ref(),
+        mongodb::error::ErrorKind::Write(mongodb::error::WriteFailure::WriteError(write_error))
+            if write_error.code == 11000
+    )
+}
@@ -1,28 +1,48 @@
+use bson::{doc, oid::ObjectId};
+use chrono::Utc;
+use futures::{StreamExt, TryStreamExt};
 use lapin::{
-    options::{BasicPublishOptions, ExchangeDeclareOptions},
+    message::Delivery,
+    options::{
+        BasicAckOptions, BasicConsumeOptions, BasicPublishOptions, ExchangeDeclareOptions,
+        QueueBindOptions, QueueDeclareOptions,
+    },
     types::FieldTable,
     BasicProperties, Channel, Connection, ConnectionProperties, ExchangeKind,
 };
+use mongodb::{
+    options::{FindOptions, UpdateOptions},
+    Collection,
+};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
+use crate::db::MongoDB;
 use crate::events::{
-    DailyMetricsRecordedEvent, FeedingRecordedEvent, DAILY_METRICS_ROUTING_KEY, EXCHANGE_NAME,
-    FEEDING_ROUTING_KEY,
+    DailyMetricsRecordedEvent, EventVersion, FeedingRecordedEvent, RodentStatusChangedEvent,
+    ACTIVITY_DEAD_LETTER_QUEUE, ACTIVITY_QUEUE, DAILY_METRICS_ROUTING_KEY, EVENT_SCHEMA_VERSION,
+    EXCHANGE_NAME, FEEDING_ROUTING_KEY, RODENT_STATUS_CHANGED_ROUTING_KEY,
 };
+use crate::models::{OutboxEvent, INACTIVE_STATUSES};
+
+/// How many stored events the outbox relay delivers per round
+const OUTBOX_BATCH_SIZE: i64 = 100;
 
-/// RabbitMQ message publisher
+/// RabbitMQ message publisher. With the outbox enabled, events that can't be
+/// published are stored and delivered later by the outbox relay.
 pub struct MessagePublisher {
     channel: Arc<RwLock<Option<Channel>>>,
     rabbitmq_url: String,
+    outbox: Option<Collection<OutboxEvent>>,
 }
 
 impl MessagePublisher {
-    pub async fn new(rabbitmq_url: &str) -> Result<Self, lapin::Error> {
+    pub async fn new(rabbitmq_url: &str, outbox: Option<Collection<OutboxEvent>>) -> Result<Self, lapin::Error> {
         let publisher = Self {
             channel: Arc::new(RwLock::new(None)),
             rabbitmq_url: rabbitmq_url.to_string(),
+            outbox,
         };
 
         // Try to connect, but don't fail if RabbitMQ is not available
@@ -69,73 +89,391 @@ impl MessagePublisher {
         Ok(())
     }
 
-    pub async fn publish_daily_metrics(&self, event: &DailyMetricsRecordedEvent) -> Result<(), String> {
+    /// Publish a serialized event. A failed publish drops the channel so the
+    /// next attempt reconnects.
+    async fn publish(&self, routing_key: &str, payload: &[u8]) -> Result<(), String> {
         if let Err(e) = self.ensure_connected().await {
             error!("Failed to connect to RabbitMQ: {}", e);
             return Err(format!("RabbitMQ connection failed: {}", e));
         }
 
-        let payload = serde_json::to_vec(event).map_err(|e| e.to_string())?;
+        let result = match self.channel.read().await.as_ref() {
+            Some(ch) => ch
+                .basic_publish(
+                    EXCHANGE_NAME,
+                    routing_key,
+                    BasicPublishOptions::default(),
+                    payload,
+                    BasicProperties::default()
+                        .with_content_type("application/json".into())
+                        .with_delivery_mode(2), // Persistent
+                )
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            None => Err("No RabbitMQ channel available".to_string()),
+        };
 
-        let channel = self.channel.read().await;
-        if let Some(ch) = channel.as_ref() {
-            ch.basic_publish(
-                EXCHANGE_NAME,
-                DAILY_METRICS_ROUTING_KEY,
-                BasicPublishOptions::default(),
-                &payload,
-                BasicProperties::default()
-                    .with_content_type("application/json".into())
-                    .with_delivery_mode(2), // Persistent
-            )
+        if result.is_err() {
+            *self.channel.write().await = None;
+        }
+        result
+    }
+
+    /// Publish an event, storing it in the outbox if RabbitMQ is unavailable
+    /// or older events are still waiting there, so it can't overtake them.
+    /// Returns whether it was published right away; fails only if the event
+    /// could be neither published nor stored.
+    async fn publish_or_store(&self, routing_key: &str, event_id: &str, payload: Vec<u8>) -> Result<bool, String> {
+        let Some(outbox) = &self.outbox else {
+            return self.publish(routing_key, &payload).await.map(|_| true);
+        };
+
+        let backlog = match outbox.find_one(doc! {}, None).await {
+            Ok(entry) => entry.is_some(),
+            Err(e) => {
+                warn!("Failed to check outbox backlog, publishing directly: {}", e);
+                false
+            }
+        };
+
+        let (attempts, error) = if backlog {
+            (0, "Queued behind earlier undelivered events".to_string())
+        } else {
+            match self.publish(routing_key, &payload).await {
+                Ok(()) => return Ok(true),
+                Err(e) => (1, e),
+            }
+        };
+
+        let entry = OutboxEvent {
+            id: None,
+            event_id: event_id.to_string(),
+            routing_key: routing_key.to_string(),
+            payload: String::from_utf8(payload).map_err(|e| e.to_string())?,
+            attempts,
+            last_error: error.clone(),
+            created_at: Utc::now(),
+        };
+
+        outbox
+            .insert_one(&entry, None)
             .await
-            .map_err(|e| {
-                error!("Failed to publish DailyMetricsRecorded event: {}", e);
-                e.to_string()
-            })?;
+            .map_err(|e| format!("{} (storing in outbox also failed: {})", error, e))?;
 
+        warn!(
+            "Event {} ({}) stored in outbox for retry: {}",
+            event_id, routing_key, error
+        );
+        Ok(false)
+    }
+
+    /// Deliver stored events oldest first, stopping at the first failure so
+    /// they keep their order. Delivery is at-least-once: an event published
+    /// just before its removal fails is sent again.
+    async fn relay_outbox(&self) -> Result<u64, String> {
+        let Some(outbox) = &self.outbox else {
+            return Ok(0);
+        };
+
+        let options = FindOptions::builder()
+            .sort(doc! { "created_at": 1, "_id": 1 })
+            .limit(OUTBOX_BATCH_SIZE)
+            .build();
+
+        let entries: Vec<OutboxEvent> = outbox
+            .find(doc! {}, options)
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut delivered = 0;
+        for entry in entries {
+            let Some(id) = entry.id else { continue };
+
+            if let Err(e) = self.publish(&entry.routing_key, entry.payload.as_bytes()).await {
+                outbox
+                    .update_one(
+                        doc! { "_id": id },
+                        doc! { "$inc": { "attempts": 1 }, "$set": { "last_error": &e } },
+                        None,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+                break;
+            }
+
+            outbox
+                .delete_one(doc! { "_id": id }, None)
+                .await
+                .map_err(|e| e.to_string())?;
+            delivered += 1;
+        }
+
+        Ok(delivered)
+    }
+
+    /// Number of events waiting in the outbox
+    pub async fn outbox_backlog(&self) -> Result<u64, mongodb::error::Error> {
+        match &self.outbox {
+            Some(outbox) => outbox.count_documents(doc! {}, None).await,
+            None => Ok(0),
+        }
+    }
+
+    pub async fn publish_daily_metrics(&self, event: &DailyMetricsRecordedEvent) -> Result<(), String> {
+        let payload = serde_json::to_vec(event).map_err(|e| e.to_string())?;
+
+        if self
+            .publish_or_store(DAILY_METRICS_ROUTING_KEY, &event.event_id, payload)
+            .await?
+        {
             info!(
                 "Published DailyMetricsRecorded event: {} for rodent {}",
                 event.event_id, event.payload.rodent_id
             );
-            Ok(())
-        } else {
-            Err("No RabbitMQ channel available".to_string())
         }
+        Ok(())
     }
 
     pub async fn publish_feeding(&self, event: &FeedingRecordedEvent) -> Result<(), String> {
-        if let Err(e) = self.ensure_connected().await {
-            error!("Failed to connect to RabbitMQ: {}", e);
-            return Err(format!("RabbitMQ connection failed: {}", e));
+        let payload = serde_json::to_vec(event).map_err(|e| e.to_string())?;
+
+        if self
+            .publish_or_store(FEEDING_ROUTING_KEY, &event.event_id, payload)
+            .await?
+        {
+            info!(
+                "Published FeedingRecorded event: {} for rodent {}",
+                event.event_id, event.payload.rodent_id
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Why an event couldn't be processed
+enum ProcessError {
+    /// Published with a schema version this consumer doesn't understand yet
+    UnsupportedSchemaVersion(u32),
+    Failed(String),
+}
+
+impl From<String> for ProcessError {
+    fn from(message: String) -> Self {
+        ProcessError::Failed(message)
+    }
+}
+
+/// RabbitMQ consumer for registry events relevant to activity tracking
+pub struct EventConsumer {
+    rabbitmq_url: String,
+    db: Arc<MongoDB>,
+}
+
+impl EventConsumer {
+    pub fn new(rabbitmq_url: &str, db: Arc<MongoDB>) -> Self {
+        Self {
+            rabbitmq_url: rabbitmq_url.to_string(),
+            db,
         }
+    }
 
-        let payload = serde_json::to_vec(event).map_err(|e| e.to_string())?;
+    pub async fn start_consuming(&self) -> Result<(), lapin::Error> {
+        info!("Connecting to RabbitMQ for event consumption at {}", self.rabbitmq_url);
 
-        let channel = self.channel.read().await;
-        if let Some(ch) = channel.as_ref() {
-            ch.basic_publish(
+        let conn = Connection::connect(&self.rabbitmq_url, ConnectionProperties::default()).await?;
+        let channel = conn.create_channel().await?;
+
+        // Declare the exchange (same as publishers)
+        channel
+            .exchange_declare(
+                EXCHANGE_NAME,
+                ExchangeKind::Topic,
+                ExchangeDeclareOptions {
+                    durable: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await?;
+
+        // Declare the activity tracking queue
+        channel
+            .queue_declare(
+                ACTIVITY_QUEUE,
+                QueueDeclareOptions {
+                    durable: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await?;
+
+        // Declare the queue unknown schema versions are parked in
+        channel
+            .queue_declare(
+                ACTIVITY_DEAD_LETTER_QUEUE,
+                QueueDeclareOptions {
+                    durable: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await?;
+
+        // Bind queue to rodent status changes only
+        channel
+            .queue_bind(
+                ACTIVITY_QUEUE,
                 EXCHANGE_NAME,
-                FEEDING_ROUTING_KEY,
-                BasicPublishOptions::default(),
-                &payload,
-                BasicProperties::default()
-                    .with_content_type("application/json".into())
-                    .with_delivery_mode(2), // Persistent
+                RODENT_STATUS_CHANGED_ROUTING_KEY,
+                QueueBindOptions::default(),
+                FieldTable::default(),
+            )
+            .await?;
+
+        info!("Activity queue bound to exchange. Starting consumer...");
+
+        let mut consumer = channel
+            .basic_consume(
+                ACTIVITY_QUEUE,
+                "activity_tracking_consumer",
+                BasicConsumeOptions::default(),
+                FieldTable::default(),
+            )
+            .await?;
+
+        info!("Event consumer started. Waiting for messages...");
+
+        while let Some(delivery_result) = consumer.next().await {
+            match delivery_result {
+                Ok(delivery) => {
+                    match self.process_event(&delivery.data).await {
+                        Ok(()) => {}
+                        Err(ProcessError::UnsupportedSchemaVersion(version)) => {
+                            warn!("Dead-lettering event with unsupported schema version {}", version);
+                            if let Err(e) = dead_letter(&channel, &delivery).await {
+                                error!("Failed to dead-letter event: {}", e);
+                            }
+                        }
+                        Err(ProcessError::Failed(e)) => {
+                            error!("Failed to process event: {}", e);
+                        }
+                    }
+                    // Ack regardless of outcome to prevent infinite redelivery
+                    if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
+                        error!("Failed to ack message: {}", e);
+                    }
+                }
+                Err(e) => {
+                    error!("Error receiving message: {}", e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn process_event(&self, data: &[u8]) -> Result<(), ProcessError> {
+        let version: EventVersion = serde_json::from_slice(data)
+            .map_err(|e| format!("Failed to parse event: {}", e))?;
+        if !(1..=EVENT_SCHEMA_VERSION).contains(&version.schema_version) {
+            return Err(ProcessError::UnsupportedSchemaVersion(version.schema_version));
+        }
+
+        let event: RodentStatusChangedEvent = serde_json::from_slice(data)
+            .map_err(|e| format!("Failed to parse RodentStatusChanged: {}", e))?;
+
+        info!(
+            "Received RodentStatusChanged: rodent={}, {} -> {}",
+            event.payload.rodent_id, event.payload.old_status, event.payload.new_status
+        );
+
+        let rodent_oid = ObjectId::parse_str(&event.payload.rodent_id)
+            .map_err(|e| format!("Invalid rodent ID in event: {}", e))?;
+
+        let collection = self.db.db.collection::<bson::Document>("inactive_rodents");
+        let new_status = event.payload.new_status.to_lowercase();
+        let changed_at = bson::DateTime::from_chrono(event.timestamp);
+
+        // Outbox relays and redeliveries can arrive late: only a change newer
+        // than the stored one may overwrite it. A missing entry compares lower.
+        let newer = doc! { "$lt": ["$changed_at", changed_at] };
+        let update = vec![doc! {
+            "$set": {
+                "rodent_id": rodent_oid,
+                "status": { "$cond": [newer.clone(), &new_status, "$status"] },
+                "changed_at": { "$cond": [newer, changed_at, "$changed_at"] },
+            }
+        }];
+
+        // Only rodents that have been inactive need an entry; returning to
+        // care keeps it with the active status so older events stay ignored
+        let is_inactive = INACTIVE_STATUSES.contains(&new_status.as_str());
+        collection
+            .update_one(
+                doc! { "rodent_id": rodent_oid },
+                update,
+                UpdateOptions::builder().upsert(is_inactive).build(),
             )
             .await
-            .map_err(|e| {
-                error!("Failed to publish FeedingRecorded event: {}", e);
-                e.to_string()
-            })?;
+            .map_err(|e| format!("Failed to record rodent status: {}", e))?;
 
-            info!(
-                "Published FeedingRecorded event: {} for rodent {}",
-                event.event_id, event.payload.rodent_id
-            );
-            Ok(())
-        } else {
-            Err("No RabbitMQ channel available".to_string())
+        if is_inactive {
+            info!("Rodent {} marked as {}; new records will be rejected", rodent_oid, new_status);
         }
+
+        Ok(())
     }
 }
+
+/// Park an event in the dead-letter queue as received, for replay once it's supported
+async fn dead_letter(channel: &Channel, delivery: &Delivery) -> Result<(), lapin::Error> {
+    channel
+        .basic_publish(
+            "",
+            ACTIVITY_DEAD_LETTER_QUEUE,
+            BasicPublishOptions::default(),
+            &delivery.data,
+            delivery.properties.clone(),
+        )
+        .await
+        .map(|_| ())
+}
+
+/// Start the event consumer in a background task
+pub fn spawn_consumer(rabbitmq_url: String, db: Arc<MongoDB>) {
+    tokio::spawn(async move {
+        let consumer = EventConsumer::new(&rabbitmq_url, db);
+        loop {
+            match consumer.start_consuming().await {
+                Ok(_) => {
+                    info!("Consumer stopped normally");
+                    break;
+                }
+                Err(e) => {
+                    error!("Consumer error: {}. Reconnecting in 5 seconds...", e);
+                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                }
+            }
+        }
+    });
+}
+
+/// Start the outbox relay in a background task
+pub fn spawn_outbox_relay(publisher: Arc<MessagePublisher>, interval_secs: u64) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            match publisher.relay_outbox().await {
+                Ok(0) => {}
+                Ok(delivered) => info!("Delivered {} events from the outbox", delivered),
+                Err(e) => error!("Failed to relay outbox events: {}", e),
+            }
+        }
+    });
+}
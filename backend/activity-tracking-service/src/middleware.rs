@@ -98,6 +98,16 @@ pub fn can_track_activities(auth_info: &AuthInfo) -> Result<(), AppError> {
     check_role(auth_info, &["admin", "caretaker", "veterinarian"])
 }
 
+/// Check if user can purge tracking data (Admin only)
+pub fn can_purge_data(auth_info: &AuthInfo) -> Result<(), AppError> {
+    check_role(auth_info, &["admin"])
+}
+
+/// Check if user can move tracking data between rodents (Admin only)
+pub fn can_reassign_data(auth_info: &AuthInfo) -> Result<(), AppError> {
+    check_role(auth_info, &["admin"])
+}
+
 /// Check if user can view activities (all authenticated users)
 pub fn can_view(_auth_info: &AuthInfo) -> Result<(), AppError> {
     // All authenticated users can view
@@ -20,6 +20,32 @@ pub enum ActivityType {
 }
 
 impl ActivityType {
+    pub const ALL: [ActivityType; 9] = [
+        ActivityType::WheelRunning,
+        ActivityType::Swimming,
+        ActivityType::Digging,
+        ActivityType::SocialInteraction,
+        ActivityType::Playing,
+        ActivityType::Grooming,
+        ActivityType::Exploring,
+        ActivityType::Resting,
+        ActivityType::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ActivityType::WheelRunning => "Wheel running",
+            ActivityType::Swimming => "Swimming",
+            ActivityType::Digging => "Digging",
+            ActivityType::SocialInteraction => "Social interaction",
+            ActivityType::Playing => "Playing",
+            ActivityType::Grooming => "Grooming",
+            ActivityType::Exploring => "Exploring",
+            ActivityType::Resting => "Resting",
+            ActivityType::Other => "Other",
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             ActivityType::WheelRunning => "wheel_running",
@@ -50,6 +76,32 @@ pub enum FoodType {
 }
 
 impl FoodType {
+    pub const ALL: [FoodType; 9] = [
+        FoodType::Pellets,
+        FoodType::Hay,
+        FoodType::Vegetables,
+        FoodType::Fruit,
+        FoodType::Protein,
+        FoodType::Treats,
+        FoodType::Supplements,
+        FoodType::Water,
+        FoodType::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FoodType::Pellets => "Pellets",
+            FoodType::Hay => "Hay",
+            FoodType::Vegetables => "Vegetables",
+            FoodType::Fruit => "Fruit",
+            FoodType::Protein => "Protein",
+            FoodType::Treats => "Treats",
+            FoodType::Supplements => "Supplements",
+            FoodType::Water => "Water",
+            FoodType::Other => "Other",
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             FoodType::Pellets => "pellets",
@@ -109,10 +161,19 @@ pub struct Activity {
     pub recorded_at: DateTime<Utc>,
     pub recorded_by: String,
     pub recorded_by_name: String,
+    #[serde(default)]
+    pub assisted_by: Vec<ActivityAssistant>, // other staff who handled the session
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub created_at: DateTime<Utc>,
 }
 
+/// A staff member who helped the recorder with an activity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityAssistant {
+    pub user_id: String,
+    pub name: String,
+}
+
 /// Feeding record for a rodent
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FeedingRecord {
@@ -130,11 +191,42 @@ pub struct FeedingRecord {
     pub recorded_by_name: String,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub created_at: DateTime<Utc>,
+    #[serde(default, with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional")]
+    pub updated_at: Option<DateTime<Utc>>, // unset until the record is first edited
+}
+
+/// Rodent statuses after which no new records are accepted
+pub const INACTIVE_STATUSES: [&str; 2] = ["deceased", "adopted"];
+
+/// Latest status of a rodent that has been deceased or adopted; it accepts no
+/// new records while `status` is one of `INACTIVE_STATUSES`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InactiveRodent {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub rodent_id: ObjectId,
+    pub status: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Event that couldn't be published, kept until the outbox relay delivers it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEvent {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub event_id: String,
+    pub routing_key: String,
+    pub payload: String, // serialized event JSON
+    pub attempts: i32,
+    pub last_error: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
 }
 
 // ============== Request DTOs ==============
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateDailyRecordRequest {
     pub date: Option<NaiveDate>,
     #[validate(range(min = 0.0, max = 50000.0, message = "Weight must be between 0 and 50000 grams"))]
@@ -167,7 +259,7 @@ pub struct UpdateDailyRecordRequest {
     pub health_observations: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateActivityRequest {
     pub activity_type: ActivityType,
     #[validate(range(min = 1, max = 1440, message = "Duration must be between 1 and 1440 minutes"))]
@@ -175,9 +267,27 @@ pub struct CreateActivityRequest {
     #[validate(length(max = 500, message = "Notes must be at most 500 characters"))]
     pub notes: Option<String>,
     pub recorded_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    #[validate(length(max = 10, message = "At most 10 staff can assist with an activity"))]
+    pub assisted_by: Vec<ActivityAssistant>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
+pub struct CreateGroupActivityRequest {
+    #[validate(length(min = 1, max = 50, message = "Between 1 and 50 rodents can share a group activity"))]
+    pub rodent_ids: Vec<String>,
+    pub activity_type: ActivityType,
+    #[validate(range(min = 1, max = 1440, message = "Duration must be between 1 and 1440 minutes"))]
+    pub duration_minutes: i32,
+    #[validate(length(max = 500, message = "Notes must be at most 500 characters"))]
+    pub notes: Option<String>,
+    pub recorded_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    #[validate(length(max = 10, message = "At most 10 staff can assist with an activity"))]
+    pub assisted_by: Vec<ActivityAssistant>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateFeedingRecordRequest {
     pub food_type: FoodType,
     #[validate(range(min = 0.1, max = 5000.0, message = "Quantity must be between 0.1 and 5000 grams"))]
@@ -211,6 +321,7 @@ pub struct DailyRecordQueryParams {
 #[derive(Debug, Deserialize)]
 pub struct ActivityQueryParams {
     pub activity_type: Option<ActivityType>,
+    pub recorded_by: Option<String>,
     pub from_date: Option<DateTime<Utc>>,
     pub to_date: Option<DateTime<Utc>>,
     pub page: Option<u32>,
@@ -220,12 +331,18 @@ pub struct ActivityQueryParams {
 #[derive(Debug, Deserialize)]
 pub struct FeedingQueryParams {
     pub food_type: Option<FoodType>,
+    pub recorded_by: Option<String>,
     pub from_date: Option<DateTime<Utc>>,
     pub to_date: Option<DateTime<Utc>>,
     pub page: Option<u32>,
     pub limit: Option<u32>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct FeedingRecommendationsQueryParams {
+    pub species: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DailySummaryQueryParams {
     /// Timezone offset in minutes (e.g., -60 for UTC+1, 60 for UTC-1)
@@ -235,6 +352,31 @@ pub struct DailySummaryQueryParams {
 
 // ============== Response DTOs ==============
 
+/// Paging metadata shared by list responses; flattened into the response body
+#[derive(Debug, Serialize)]
+pub struct Pagination {
+    pub total: u64,
+    pub page: u32,
+    pub limit: u32,
+    pub total_pages: u64,
+    pub has_next: bool,
+    pub has_prev: bool,
+}
+
+impl Pagination {
+    pub fn new(total: u64, page: u32, limit: u32) -> Self {
+        let total_pages = total.div_ceil(u64::from(limit.max(1)));
+        Self {
+            total,
+            page,
+            limit,
+            total_pages,
+            has_next: u64::from(page) < total_pages,
+            has_prev: page > 1,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct DailyRecordResponse {
     pub id: String,
@@ -256,9 +398,8 @@ pub struct DailyRecordResponse {
 pub struct DailyRecordListResponse {
     pub success: bool,
     pub daily_records: Vec<DailyRecordResponse>,
-    pub total: u64,
-    pub page: u32,
-    pub limit: u32,
+    #[serde(flatten)]
+    pub pagination: Pagination,
 }
 
 #[derive(Debug, Serialize)]
@@ -277,6 +418,7 @@ pub struct ActivityResponse {
     pub recorded_at: DateTime<Utc>,
     pub recorded_by: String,
     pub recorded_by_name: String,
+    pub assisted_by: Vec<ActivityAssistant>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -284,9 +426,9 @@ pub struct ActivityResponse {
 pub struct ActivityListResponse {
     pub success: bool,
     pub activities: Vec<ActivityResponse>,
-    pub total: u64,
-    pub page: u32,
-    pub limit: u32,
+    pub total_minutes: i64, // across all matching activities, not just this page
+    #[serde(flatten)]
+    pub pagination: Pagination,
 }
 
 #[derive(Debug, Serialize)]
@@ -295,6 +437,73 @@ pub struct SingleActivityResponse {
     pub activity: ActivityResponse,
 }
 
+#[derive(Debug, Serialize)]
+pub struct TypeOption {
+    pub value: &'static str,
+    pub label: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TypeTaxonomyResponse {
+    pub success: bool,
+    pub activity_types: Vec<TypeOption>,
+    pub food_types: Vec<TypeOption>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PurgeRodentDataRequest {
+    pub rodent_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PurgeRodentDataResponse {
+    pub success: bool,
+    pub daily_records_deleted: u64,
+    pub activities_deleted: u64,
+    pub feeding_records_deleted: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReassignRodentDataRequest {
+    pub from_rodent_id: String,
+    pub to_rodent_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReassignRodentDataResponse {
+    pub success: bool,
+    pub daily_records_moved: u64,
+    pub activities_moved: u64,
+    pub feeding_records_moved: u64,
+}
+
+/// Activity and food types that have actually been logged
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityFacets {
+    pub activity_types: Vec<String>,
+    pub food_types: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActivityFacetsResponse {
+    pub success: bool,
+    #[serde(flatten)]
+    pub facets: ActivityFacets,
+}
+
+/// The activity created for one rodent of a group
+#[derive(Debug, Serialize)]
+pub struct GroupActivityCreated {
+    pub rodent_id: String,
+    pub activity_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GroupActivityResponse {
+    pub success: bool,
+    pub activities: Vec<GroupActivityCreated>, // in request order
+}
+
 #[derive(Debug, Serialize)]
 pub struct FeedingRecordResponse {
     pub id: String,
@@ -313,15 +522,41 @@ pub struct FeedingRecordResponse {
 pub struct FeedingRecordListResponse {
     pub success: bool,
     pub feeding_records: Vec<FeedingRecordResponse>,
-    pub total: u64,
-    pub page: u32,
-    pub limit: u32,
+    pub total_grams: f64, // across all matching records, not just this page
+    #[serde(flatten)]
+    pub pagination: Pagination,
 }
 
 #[derive(Debug, Serialize)]
 pub struct SingleFeedingRecordResponse {
     pub success: bool,
     pub feeding_record: FeedingRecordResponse,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub portion_check: Option<PortionCheck>, // only on create, when a recommendation exists
+}
+
+/// How a logged quantity compares with the species' recommended portion
+#[derive(Debug, Serialize)]
+pub struct PortionCheck {
+    pub species: String,
+    pub recommended_grams: f64,
+    pub deviation_percent: f64, // positive when more than recommended was given
+    pub exceeds_tolerance: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortionRecommendation {
+    pub food_type: &'static str,
+    pub label: &'static str,
+    pub grams: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeedingRecommendationsResponse {
+    pub success: bool,
+    pub species: String,
+    pub tolerance_percent: f64,
+    pub recommendations: Vec<PortionRecommendation>,
 }
 
 #[derive(Debug, Serialize)]
@@ -330,6 +565,15 @@ pub struct MessageResponse {
     pub message: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct BulkDeleteResponse {
+    pub success: bool,
+    pub rodent_id: String,
+    pub daily_records_deleted: u64,
+    pub activities_deleted: u64,
+    pub feeding_records_deleted: u64,
+}
+
 // Daily summary combining all data for a specific day
 #[derive(Debug, Serialize)]
 pub struct DailySummaryResponse {
@@ -343,6 +587,24 @@ pub struct DailySummaryResponse {
     pub total_food_grams: f64,
 }
 
+/// Most recent recorded value of a single vital and the day it was recorded
+#[derive(Debug, Serialize)]
+pub struct VitalReading<T> {
+    pub value: T,
+    pub date: DateTime<Utc>,
+}
+
+/// Latest known vitals for a rodent; each may come from a different daily record
+#[derive(Debug, Serialize)]
+pub struct LatestVitalsResponse {
+    pub success: bool,
+    pub rodent_id: String,
+    pub weight_grams: Option<VitalReading<f64>>,
+    pub temperature_celsius: Option<VitalReading<f64>>,
+    pub energy_level: Option<VitalReading<i32>>,
+    pub mood_level: Option<VitalReading<i32>>,
+}
+
 // ============== Auth Info ==============
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -404,6 +666,7 @@ impl From<Activity> for ActivityResponse {
             recorded_at: activity.recorded_at,
             recorded_by: activity.recorded_by,
             recorded_by_name: activity.recorded_by_name,
+            assisted_by: activity.assisted_by,
             created_at: activity.created_at,
         }
     }
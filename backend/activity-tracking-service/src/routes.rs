@@ -11,6 +11,12 @@ pub fn create_routes() -> Router<Arc<AppState>> {
         // Public routes
         .route("/health", get(handlers::health_check))
 
+        // Reference data
+        .route("/activities/types", get(handlers::list_types))
+        .route("/activities/facets", get(handlers::get_activity_facets))
+        .route("/activities/purge", post(handlers::purge_rodent_data))
+        .route("/activities/reassign", post(handlers::reassign_rodent_data))
+
         // Daily records routes
         .route("/activities/rodents/:rodent_id/daily-records", get(handlers::list_daily_records))
         .route("/activities/rodents/:rodent_id/daily-records", post(handlers::create_daily_record))
@@ -22,8 +28,10 @@ pub fn create_routes() -> Router<Arc<AppState>> {
         .route("/activities/rodents/:rodent_id/activities", get(handlers::list_activities))
         .route("/activities/rodents/:rodent_id/activities", post(handlers::create_activity))
         .route("/activities/rodents/:rodent_id/activities/:activity_id", delete(handlers::delete_activity))
+        .route("/activities/group", post(handlers::create_group_activity))
 
         // Feeding records routes
+        .route("/activities/feeding/recommendations", get(handlers::get_feeding_recommendations))
         .route("/activities/rodents/:rodent_id/feeding-records", get(handlers::list_feeding_records))
         .route("/activities/rodents/:rodent_id/feeding-records", post(handlers::create_feeding_record))
         .route("/activities/rodents/:rodent_id/feeding-records/:record_id", put(handlers::update_feeding_record))
@@ -31,4 +39,10 @@ pub fn create_routes() -> Router<Arc<AppState>> {
 
         // Daily summary (combined view)
         .route("/activities/rodents/:rodent_id/summary/:date", get(handlers::get_daily_summary))
+
+        // Most recent value of each vital sign
+        .route("/activities/rodents/:rodent_id/vitals/latest", get(handlers::get_latest_vitals))
+
+        // Bulk cleanup of all records for a rodent
+        .route("/activities/rodents/:rodent_id/all", delete(handlers::delete_all_rodent_records))
 }
@@ -1,4 +1,4 @@
-use mongodb::{Client, Database, IndexModel};
+use mongodb::{options::IndexOptions, Client, Database, IndexModel};
 use bson::doc;
 use tracing::info;
 
@@ -19,14 +19,24 @@ impl MongoDB {
         // Analytics service's own database
         let db = client.database(&config.database_name);
 
-        // Access to other services' databases for read-only aggregations
-        let rodent_db = client.database("rodent_registry");
-        let activity_db = client.database("activity_tracking");
-
         // Verify connection
         db.run_command(doc! { "ping": 1 }, None).await?;
         info!("Connected to MongoDB database: {}", config.database_name);
 
+        // Access to other services' databases for read-only aggregations,
+        // optionally through a replica so they don't compete with those services' writes
+        let read_client = match &config.mongodb_read_uri {
+            Some(read_uri) => {
+                let read_client = Client::with_uri_str(read_uri).await?;
+                read_client.database("admin").run_command(doc! { "ping": 1 }, None).await?;
+                info!("Connected to MongoDB read replica for cross-service aggregations");
+                read_client
+            }
+            None => client.clone(),
+        };
+        let rodent_db = read_client.database("rodent_registry");
+        let activity_db = read_client.database("activity_tracking");
+
         // analytics_db is the same as db, used by messaging consumer
         let analytics_db = db.clone();
 
@@ -69,6 +79,57 @@ impl MongoDB {
             cache_expire_index,
         ], None).await?;
 
+        // Event log, checked by event_id to skip redelivered events
+        let event_logs = self.db.collection::<bson::Document>("event_logs");
+
+        let event_id_index = IndexModel::builder()
+            .keys(doc! { "event_id": 1 })
+            .build();
+
+        // Retention purge removes old processed logs
+        let event_retention_index = IndexModel::builder()
+            .keys(doc! { "processed": 1, "received_at": 1 })
+            .build();
+
+        event_logs.create_indexes(vec![event_id_index, event_retention_index], None).await?;
+
+        // Anomalies collection indexes
+        let anomalies = self.db.collection::<bson::Document>("anomalies");
+
+        let anomaly_rodent_index = IndexModel::builder()
+            .keys(doc! { "rodent_id": 1, "detected_at": -1 })
+            .build();
+
+        anomalies.create_indexes(vec![anomaly_rodent_index], None).await?;
+
+        // Weight change events, listed newest first per rodent
+        let weight_changes = self.db.collection::<bson::Document>("weight_change_events");
+
+        let weight_change_rodent_index = IndexModel::builder()
+            .keys(doc! { "rodent_id": 1, "detected_at": -1 })
+            .build();
+
+        weight_changes.create_indexes(vec![weight_change_rodent_index], None).await?;
+
+        // Population snapshots, one per month
+        let snapshots = self.db.collection::<bson::Document>("population_snapshots");
+
+        let snapshot_month_index = IndexModel::builder()
+            .keys(doc! { "month": 1 })
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        snapshots.create_indexes(vec![snapshot_month_index], None).await?;
+
+        // Webhook registrations, looked up by subscribed event type
+        let webhooks = self.db.collection::<bson::Document>("webhooks");
+
+        let webhook_event_index = IndexModel::builder()
+            .keys(doc! { "event_types": 1, "active": 1 })
+            .build();
+
+        webhooks.create_indexes(vec![webhook_event_index], None).await?;
+
         info!("MongoDB indexes created successfully for Analytics Service");
 
         Ok(())
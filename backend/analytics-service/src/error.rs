@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -20,9 +20,15 @@ pub enum AppError {
     #[error("Invalid date range")]
     InvalidDateRange,
 
+    #[error("Invalid date range: must span at most {0} days")]
+    DateRangeTooLong(i64),
+
     #[error("Invalid ID format")]
     InvalidId,
 
+    #[error("Validation error: {0}")]
+    ValidationError(String),
+
     #[error("Database error: {0}")]
     DatabaseError(String),
 
@@ -34,6 +40,28 @@ pub enum AppError {
 
     #[error("Data not found")]
     NotFound,
+
+    #[error("Request body too large")]
+    PayloadTooLarge,
+}
+
+impl AppError {
+    /// Value of the `code` field in error responses
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::InvalidToken => "INVALID_TOKEN",
+            AppError::TokenExpired => "TOKEN_EXPIRED",
+            AppError::AccessDenied(_) => "ACCESS_DENIED",
+            AppError::InvalidDateRange | AppError::DateRangeTooLong(_) => "INVALID_DATE_RANGE",
+            AppError::InvalidId => "INVALID_ID",
+            AppError::ValidationError(_) => "VALIDATION_ERROR",
+            AppError::DatabaseError(_) => "DATABASE_ERROR",
+            AppError::InternalError => "INTERNAL_ERROR",
+            AppError::ServiceUnavailable(_) => "SERVICE_UNAVAILABLE",
+            AppError::NotFound => "NOT_FOUND",
+            AppError::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+        }
+    }
 }
 
 impl IntoResponse for AppError {
@@ -42,8 +70,11 @@ impl IntoResponse for AppError {
             AppError::InvalidToken => (StatusCode::UNAUTHORIZED, self.to_string()),
             AppError::TokenExpired => (StatusCode::UNAUTHORIZED, self.to_string()),
             AppError::AccessDenied(_) => (StatusCode::FORBIDDEN, self.to_string()),
-            AppError::InvalidDateRange => (StatusCode::BAD_REQUEST, self.to_string()),
+            AppError::InvalidDateRange | AppError::DateRangeTooLong(_) => {
+                (StatusCode::BAD_REQUEST, self.to_string())
+            }
             AppError::InvalidId => (StatusCode::BAD_REQUEST, self.to_string()),
+            AppError::ValidationError(_) => (StatusCode::BAD_REQUEST, self.to_string()),
             AppError::DatabaseError(_) => {
                 tracing::error!("Database error: {}", self);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Database error".to_string())
@@ -54,17 +85,33 @@ impl IntoResponse for AppError {
             }
             AppError::ServiceUnavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
             AppError::NotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::PayloadTooLarge => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
         };
 
         let body = Json(json!({
             "success": false,
-            "error": error_message
+            "error": error_message,
+            "code": self.code()
         }));
 
         (status, body).into_response()
     }
 }
 
+/// Replace axum's plain-text 413 (from `DefaultBodyLimit`) with the JSON error envelope
+pub async fn json_payload_too_large(response: Response) -> Response {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE && !is_json {
+        return AppError::PayloadTooLarge.into_response();
+    }
+
+    response
+}
+
 impl From<mongodb::error::Error> for AppError {
     fn from(err: mongodb::error::Error) -> Self {
         tracing::error!("MongoDB error: {:?}", err);
@@ -5,17 +5,29 @@ use serde::{Deserialize, Serialize};
 
 pub const EXCHANGE_NAME: &str = "rodent_care_events";
 pub const ANALYTICS_QUEUE: &str = "analytics_events";
+// Events with a schema version this service doesn't understand are parked here
+pub const ANALYTICS_DEAD_LETTER_QUEUE: &str = "analytics_events.dead_letter";
 
 // Routing keys to subscribe to
 pub const ACTIVITY_ROUTING_PATTERN: &str = "activity.*";
 pub const REGISTRY_ROUTING_PATTERN: &str = "registry.*";
 
+/// Latest event payload layout this consumer understands
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// Events published before versioning was introduced have the version 1 layout
+fn legacy_schema_version() -> u32 {
+    1
+}
+
 // ============== Activity Tracking Events ==============
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DailyMetricsRecordedEvent {
     pub event_type: String,
     pub event_id: String,
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub timestamp: DateTime<Utc>,
     pub payload: DailyMetricsPayload,
 }
@@ -38,6 +50,8 @@ pub struct DailyMetricsPayload {
 pub struct FeedingRecordedEvent {
     pub event_type: String,
     pub event_id: String,
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub timestamp: DateTime<Utc>,
     pub payload: FeedingPayload,
 }
@@ -60,6 +74,8 @@ pub struct FeedingPayload {
 pub struct RodentRegisteredEvent {
     pub event_type: String,
     pub event_id: String,
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub timestamp: DateTime<Utc>,
     pub payload: RodentRegisteredPayload,
 }
@@ -81,6 +97,8 @@ pub struct RodentRegisteredPayload {
 pub struct RodentStatusChangedEvent {
     pub event_type: String,
     pub event_id: String,
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub timestamp: DateTime<Utc>,
     pub payload: RodentStatusChangedPayload,
 }
@@ -99,6 +117,8 @@ pub struct RodentStatusChangedPayload {
 pub struct MedicalTreatmentAddedEvent {
     pub event_type: String,
     pub event_id: String,
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub timestamp: DateTime<Utc>,
     pub payload: MedicalTreatmentPayload,
 }
@@ -117,12 +137,25 @@ pub struct MedicalTreatmentPayload {
     pub added_by_name: String,
 }
 
+// ============== Webhooks ==============
+
+/// Event types external systems may subscribe to via webhooks
+pub const WEBHOOK_EVENT_TYPES: &[&str] = &[
+    "DailyMetricsRecorded",
+    "FeedingRecorded",
+    "RodentRegistered",
+    "RodentStatusChanged",
+    "MedicalTreatmentAdded",
+];
+
 // ============== Generic Event Wrapper ==============
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GenericEvent {
     pub event_type: String,
     pub event_id: String,
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub timestamp: DateTime<Utc>,
 }
 
@@ -138,4 +171,7 @@ pub struct AnalyticsEventLog {
     pub payload: serde_json::Value,
     pub received_at: DateTime<Utc>,
     pub processed: bool,
+    /// Why the event failed or was dead-lettered; unset on processed events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
 }
@@ -1,16 +1,24 @@
 use axum::{
-    extract::{Query, State},
-    http::{header, HeaderMap},
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use bson::{doc, Document};
 use chrono::{DateTime, Duration, Utc};
-use futures::TryStreamExt;
-use jsonwebtoken::{decode, DecodingKey, Validation};
+use futures::{StreamExt, TryStreamExt};
+use jsonwebtoken::{decode, DecodingKey};
 use std::sync::Arc;
 
+use crate::config::{Config, ExportNumberFormat};
 use crate::error::AppError;
+use crate::events::WEBHOOK_EVENT_TYPES;
+use crate::recompute;
+use crate::snapshots;
 use crate::models::*;
+use crate::timing::QueryTimer;
+use crate::webhooks::SecretCipher;
 use crate::AppState;
 
 // ============== Helper Functions ==============
@@ -30,7 +38,7 @@ fn extract_auth_info(state: &AppState, headers: &HeaderMap) -> Result<AuthInfo,
     let claims = decode::<Claims>(
         token,
         &DecodingKey::from_secret(state.config.jwt_secret.as_bytes()),
-        &Validation::default(),
+        &state.config.jwt_validation(),
     )
     .map_err(|e| {
         tracing::debug!("Token validation failed: {:?}", e);
@@ -44,14 +52,58 @@ fn extract_auth_info(state: &AppState, headers: &HeaderMap) -> Result<AuthInfo,
     Ok(AuthInfo::from(claims))
 }
 
-fn can_view_analytics(role: &str) -> bool {
-    matches!(role, "admin" | "caretaker" | "veterinarian")
+/// Roles allowed to read analytics come from `ANALYTICS_ROLES`
+fn can_view_analytics(config: &Config, role: &str) -> bool {
+    config.analytics_roles.iter().any(|allowed| allowed == role)
 }
 
-fn get_date_range(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> (DateTime<Utc>, DateTime<Utc>) {
+/// Resolve the query window. A missing `from` is `days` (or the configured
+/// default window) before `to`, and a missing `to` is now. Windows longer
+/// than `MAX_DATE_RANGE_DAYS` are rejected.
+fn get_date_range(
+    config: &Config,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    days: Option<i64>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), AppError> {
+    if let Some(days) = days {
+        if !(1..=config.max_date_range_days).contains(&days) {
+            return Err(AppError::ValidationError(format!(
+                "days must be between 1 and {}",
+                config.max_date_range_days
+            )));
+        }
+    }
+
+    let window = days.unwrap_or(config.default_date_range_days);
     let to_date = to.unwrap_or_else(Utc::now);
-    let from_date = from.unwrap_or_else(|| to_date - Duration::days(30));
-    (from_date, to_date)
+    let from_date = from.unwrap_or_else(|| to_date - Duration::days(window));
+
+    // A reversed range would silently match nothing
+    if from_date > to_date {
+        return Err(AppError::InvalidDateRange);
+    }
+    // An overly long one would scan years of records in a single request
+    if to_date - from_date > Duration::days(config.max_date_range_days) {
+        return Err(AppError::DateRangeTooLong(config.max_date_range_days));
+    }
+
+    Ok((from_date, to_date))
+}
+
+// Helper function to check a requested page size against MAX_PAGE_LIMIT
+fn page_limit(config: &Config, requested: Option<u32>, default: u32) -> Result<u32, AppError> {
+    let limit = requested.unwrap_or(default.min(config.max_page_limit));
+    if limit <= config.max_page_limit {
+        return Ok(limit);
+    }
+    if config.clamp_page_limit {
+        return Ok(config.max_page_limit);
+    }
+    Err(AppError::ValidationError(format!(
+        "limit must be at most {}",
+        config.max_page_limit
+    )))
 }
 
 // Helper function to get numeric value from BSON document (handles both i32 and i64)
@@ -79,42 +131,62 @@ fn get_number_as_f64(doc: &Document, key: &str) -> f64 {
     }
 }
 
-/// Helper function to get rodent ObjectIds filtered by species
-/// Returns None if no species filter is applied (meaning all rodents)
-async fn get_rodent_ids_by_species(
+/// Filter on the rodents collection that drops sample (demo) rodents when asked to
+fn sample_filter(exclude_samples: Option<bool>) -> Document {
+    if exclude_samples.unwrap_or(false) {
+        doc! { "is_sample": { "$ne": true } }
+    } else {
+        doc! {}
+    }
+}
+
+/// Helper function to get rodent ObjectIds filtered by species and, optionally,
+/// without sample rodents.
+/// Returns None if no filter is applied (meaning all rodents)
+async fn get_rodent_ids(
     state: &AppState,
     species: Option<&String>,
+    exclude_samples: Option<bool>,
 ) -> Result<Option<Vec<bson::oid::ObjectId>>, AppError> {
-    match species {
-        Some(species_filter) if !species_filter.is_empty() => {
-            let rodents_collection = state.db.rodent_db.collection::<Document>("rodents");
-            let cursor = rodents_collection
-                .find(doc! { "species": species_filter }, None)
-                .await?;
-            let rodents: Vec<Document> = cursor.try_collect().await?;
-            let ids: Vec<bson::oid::ObjectId> = rodents
-                .iter()
-                .filter_map(|doc| doc.get_object_id("_id").ok())
-                .collect();
-            Ok(Some(ids))
-        }
-        _ => Ok(None), // No filter - return None to indicate all rodents
+    let mut filter = sample_filter(exclude_samples);
+    if let Some(species_filter) = species.filter(|s| !s.is_empty()) {
+        filter.insert("species", species_filter);
+    }
+
+    if filter.is_empty() {
+        return Ok(None); // No filter - return None to indicate all rodents
     }
+
+    let rodents_collection = state.db.rodent_db.collection::<Document>("rodents");
+    let cursor = rodents_collection.find(filter, None).await?;
+    let rodents: Vec<Document> = cursor.try_collect().await?;
+    let ids: Vec<bson::oid::ObjectId> = rodents
+        .iter()
+        .filter_map(|doc| doc.get_object_id("_id").ok())
+        .collect();
+    Ok(Some(ids))
 }
 
-/// Build a match document that includes rodent_id filter if species is specified
+/// Build a match document that includes rodent_id filter if rodents were narrowed down
 fn build_rodent_filter(rodent_ids: &Option<Vec<bson::oid::ObjectId>>) -> Option<Document> {
     rodent_ids.as_ref().map(|ids| {
         doc! { "rodent_id": { "$in": ids } }
     })
 }
 
-/// Helper function to get rodent names by their IDs
-/// Returns a HashMap mapping rodent ID (hex string) to name
+/// Name and species of a rodent, for labelling per-rodent stats
+#[derive(Clone)]
+struct RodentLabel {
+    name: String,
+    species: String,
+}
+
+/// Helper function to get rodent names and species by their IDs
+/// Returns a HashMap mapping rodent ID (hex string) to its label
 async fn get_rodent_names_by_ids(
     state: &AppState,
     rodent_ids: &[String],
-) -> Result<std::collections::HashMap<String, String>, AppError> {
+) -> Result<std::collections::HashMap<String, RodentLabel>, AppError> {
     use std::collections::HashMap;
     
     if rodent_ids.is_empty() {
@@ -136,43 +208,343 @@ async fn get_rodent_names_by_ids(
     let mut names_map = HashMap::new();
     for rodent in rodents {
         if let (Ok(id), Ok(name)) = (rodent.get_object_id("_id"), rodent.get_str("name")) {
-            names_map.insert(id.to_hex(), name.to_string());
+            names_map.insert(
+                id.to_hex(),
+                RodentLabel {
+                    name: name.to_string(),
+                    species: rodent.get_str("species").unwrap_or("unknown").to_string(),
+                },
+            );
         }
     }
     
     Ok(names_map)
 }
 
+/// Least-squares slope of average weight over time, in grams per day.
+/// Returns None when there are fewer than two distinct days to fit.
+fn weight_trend_slope(trends: &[WeightTrendData]) -> Option<f64> {
+    let dated: Vec<(chrono::NaiveDate, f64)> = trends
+        .iter()
+        .filter_map(|t| {
+            chrono::NaiveDate::parse_from_str(&t.date, "%Y-%m-%d")
+                .ok()
+                .map(|d| (d, t.avg_weight))
+        })
+        .collect();
+
+    let first_day = dated.first()?.0;
+    let points: Vec<(f64, f64)> = dated
+        .iter()
+        .map(|(d, w)| ((*d - first_day).num_days() as f64, *w))
+        .collect();
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+
+    if variance == 0.0 {
+        return None;
+    }
+
+    Some(covariance / variance)
+}
+
+/// Percentage of fully consumed meals among records with a recorded outcome;
+/// meals where `consumed_fully` was never filled in don't count either way
+fn consumption_rate(consumed_fully_count: i64, known_outcome_count: i64) -> f64 {
+    if known_outcome_count > 0 {
+        consumed_fully_count as f64 / known_outcome_count as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// `$group` accumulator counting meals that were fully eaten
+fn consumed_fully_sum() -> Document {
+    doc! { "$sum": { "$cond": [{ "$eq": ["$consumed_fully", true] }, 1, 0] } }
+}
+
+/// `$group` accumulator counting meals with a recorded outcome, i.e. where
+/// `consumed_fully` is a boolean rather than null or missing
+fn known_outcome_sum() -> Document {
+    doc! { "$sum": { "$cond": [{ "$eq": [{ "$type": "$consumed_fully" }, "bool"] }, 1, 0] } }
+}
+
+/// A rodent's feeding gap is an outlier when its average exceeds the median
+/// of all rodents' averages by this factor
+const FEEDING_INTERVAL_OUTLIER_FACTOR: f64 = 1.5;
+
+/// Average and longest gap between consecutive meal times, in hours.
+/// Returns None with fewer than two meals.
+fn feeding_interval_hours(meal_times: &mut [DateTime<Utc>]) -> Option<(f64, f64)> {
+    if meal_times.len() < 2 {
+        return None;
+    }
+    meal_times.sort();
+
+    let gaps: Vec<f64> = meal_times
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).num_seconds() as f64 / 3600.0)
+        .collect();
+
+    let avg = gaps.iter().sum::<f64>() / gaps.len() as f64;
+    let max = gaps.iter().cloned().fold(0.0, f64::max);
+    Some((avg, max))
+}
+
+/// Validate a `±HH:MM` UTC offset as accepted by MongoDB date operators.
+/// None when no offset was given, so callers fall back to `TIMEZONE`.
+fn parse_tz_offset(tz_offset: Option<&str>) -> Result<Option<String>, AppError> {
+    let Some(offset) = tz_offset else {
+        return Ok(None);
+    };
+
+    // Checked byte by byte: slicing non-ASCII input could split a character,
+    // and parse::<u32> alone would accept a sign in a digit position
+    let bytes = offset.as_bytes();
+    let digits = |range: std::ops::Range<usize>| bytes[range].iter().all(u8::is_ascii_digit);
+    let valid = offset.is_ascii()
+        && bytes.len() == 6
+        && matches!(bytes[0], b'+' | b'-')
+        && bytes[3] == b':'
+        && digits(1..3)
+        && digits(4..6)
+        && offset[1..3].parse::<u32>().is_ok_and(|h| h <= 14)
+        && offset[4..6].parse::<u32>().is_ok_and(|m| m < 60);
+
+    if valid {
+        Ok(Some(offset.to_string()))
+    } else {
+        Err(AppError::ValidationError("tz_offset must look like +02:00".to_string()))
+    }
+}
+
+/// Local hours inside `[hour_from, hour_to)`, wrapping past midnight when
+/// `hour_from > hour_to`. Returns None when no window was requested.
+fn hour_window(hour_from: Option<u32>, hour_to: Option<u32>) -> Result<Option<Vec<i32>>, AppError> {
+    if hour_from.is_none() && hour_to.is_none() {
+        return Ok(None);
+    }
+
+    let from = hour_from.unwrap_or(0);
+    let to = hour_to.unwrap_or(24);
+    if from > 23 || to > 24 || from == to {
+        return Err(AppError::ValidationError(
+            "hour_from must be 0-23 and hour_to 1-24, and they must differ".to_string(),
+        ));
+    }
+
+    let hours = if from < to {
+        (from..to).collect::<Vec<_>>()
+    } else {
+        (from..24).chain(0..to).collect()
+    };
+
+    Ok(Some(hours.into_iter().map(|h| h as i32).collect()))
+}
+
+/// Label a weight slope; changes under 0.1 g/day are treated as stable
+fn weight_trend_direction(slope: Option<f64>) -> String {
+    match slope {
+        Some(s) if s > 0.1 => "increasing",
+        Some(s) if s < -0.1 => "decreasing",
+        Some(_) => "stable",
+        None => "insufficient_data",
+    }
+    .to_string()
+}
+
+// ============== Conditional GET ==============
+
+/// When the records behind an aggregation last changed, and how many there
+/// are; the count makes deletions change the validator as well
+#[derive(Default)]
+struct SourceVersion {
+    last_modified: Option<DateTime<Utc>>,
+    count: i64,
+}
+
+impl SourceVersion {
+    fn merge(self, other: Self) -> Self {
+        Self {
+            last_modified: self.last_modified.max(other.last_modified),
+            count: self.count + other.count,
+        }
+    }
+
+    /// For responses with windows relative to now: treat the data as changed
+    /// no earlier than `since`, so validators roll over with the window
+    fn not_before(self, since: DateTime<Utc>) -> Self {
+        Self {
+            last_modified: self.last_modified.max(Some(since)),
+            ..self
+        }
+    }
+
+    fn etag(&self) -> String {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.last_modified.map(|t| t.timestamp_millis()).hash(&mut hasher);
+        self.count.hash(&mut hasher);
+        format!("W/\"{:016x}\"", hasher.finish())
+    }
+
+    /// Whether the client's copy is still current. If-None-Match wins over
+    /// If-Modified-Since, as the ETag also catches deletions.
+    fn is_fresh(&self, headers: &HeaderMap) -> bool {
+        if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH).and_then(|h| h.to_str().ok()) {
+            let etag = self.etag();
+            let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+            return if_none_match
+                .split(',')
+                .any(|candidate| candidate.trim() == "*" || opaque(candidate) == opaque(&etag));
+        }
+
+        let if_modified_since = headers
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| DateTime::parse_from_rfc2822(h).ok());
+
+        match (self.last_modified, if_modified_since) {
+            // HTTP dates have whole-second precision
+            (Some(last_modified), Some(since)) => last_modified.timestamp() <= since.timestamp(),
+            _ => false,
+        }
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("private, no-cache"));
+        if let Ok(etag) = HeaderValue::from_str(&self.etag()) {
+            headers.insert(header::ETAG, etag);
+        }
+        if let Some(last_modified) = self.last_modified {
+            let http_date = last_modified.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+            if let Ok(value) = HeaderValue::from_str(&http_date) {
+                headers.insert(header::LAST_MODIFIED, value);
+            }
+        }
+        headers
+    }
+}
+
+/// Newest `timestamp` and record count among documents matching `filter`
+async fn source_version(
+    collection: &mongodb::Collection<Document>,
+    filter: Document,
+    timestamp: impl Into<bson::Bson>,
+) -> Result<SourceVersion, AppError> {
+    let pipeline = vec![
+        doc! { "$match": filter },
+        doc! { "$group": { "_id": null, "latest": { "$max": timestamp.into() }, "count": { "$sum": 1 } } },
+    ];
+
+    let mut cursor = collection.aggregate(pipeline, None).await?;
+    Ok(match cursor.try_next().await? {
+        Some(doc) => SourceVersion {
+            last_modified: doc.get_datetime("latest").ok().map(|dt| dt.to_chrono()),
+            count: get_number_as_i64(&doc, "count"),
+        },
+        None => SourceVersion::default(),
+    })
+}
+
+fn not_modified(version: &SourceVersion) -> Response {
+    (StatusCode::NOT_MODIFIED, version.headers()).into_response()
+}
+
+fn cached_json<T: serde::Serialize>(version: &SourceVersion, body: T) -> Response {
+    (version.headers(), Json(body)).into_response()
+}
+
 // ============== Population Analytics ==============
 
+/// Lower boundary in months and label of each population age group
+const AGE_BUCKETS: [(i32, &str); 5] = [
+    (0, "0-3 months"),
+    (3, "3-6 months"),
+    (6, "6-12 months"),
+    (12, "1-2 years"),
+    (24, "2+ years"),
+];
+
+/// Exclusive upper boundary of the oldest age group
+const MAX_AGE_MONTHS: i32 = 1200;
+
+/// Age group of rodents without a date of birth
+const UNKNOWN_AGE_GROUP: &str = "unknown";
+
+/// Turn `$bucket` output into one count per age group, in `AGE_BUCKETS` order
+/// with the unknown group last. Empty groups have no bucket and count as 0.
+fn age_group_counts(buckets: &[Document]) -> Vec<AgeGroupCount> {
+    let mut age_counts = std::collections::HashMap::new();
+    let mut unknown_age_count = 0;
+    for bucket in buckets {
+        let count = get_number_as_i64(bucket, "count");
+        // Buckets are identified by their lower boundary, the default bucket by its name
+        match bucket.get("_id") {
+            Some(bson::Bson::Int32(lower)) => {
+                age_counts.insert(*lower, count);
+            }
+            _ => unknown_age_count += count,
+        }
+    }
+
+    let mut by_age_group: Vec<AgeGroupCount> = AGE_BUCKETS
+        .iter()
+        .map(|(lower, label)| AgeGroupCount {
+            age_group: label.to_string(),
+            count: age_counts.get(lower).copied().unwrap_or(0),
+        })
+        .collect();
+    by_age_group.push(AgeGroupCount {
+        age_group: UNKNOWN_AGE_GROUP.to_string(),
+        count: unknown_age_count,
+    });
+    by_age_group
+}
+
 pub async fn get_population_stats(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Query(params): Query<AnalyticsQueryParams>,
-) -> Result<Json<PopulationStatsResponse>, AppError> {
+) -> Result<Response, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_population_stats");
 
-    if !can_view_analytics(&auth_info.role) {
+    if !can_view_analytics(&state.config, &auth_info.role) {
         return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
     }
 
     let rodents_collection = state.db.rodent_db.collection::<Document>("rodents");
 
     // Build base filter
-    let base_filter = if let Some(species) = &params.species {
-        doc! { "species": species }
-    } else {
-        doc! {}
-    };
+    let mut base_filter = sample_filter(params.exclude_samples);
+    if let Some(species) = &params.species {
+        base_filter.insert("species", species);
+    }
+
+    // Cheap freshness check so polling dashboards skip the aggregations
+    let status_history = state.db.rodent_db.collection::<Document>("status_history");
+    let version = source_version(&rodents_collection, base_filter.clone(), "$updated_at")
+        .await?
+        .merge(source_version(&status_history, doc! {}, "$changed_at").await?)
+        // Recent intakes and ages are relative to today, so a new day is a new version
+        .not_before(state.config.start_of_day(state.config.today()));
+    if version.is_fresh(&headers) {
+        return Ok(not_modified(&version));
+    }
 
     // Total count (with filter)
     let total_rodents = rodents_collection.count_documents(base_filter.clone(), None).await?;
 
     // By species
-    let mut species_pipeline = vec![];
-    if let Some(species) = &params.species {
-        species_pipeline.push(doc! { "$match": { "species": species } });
-    }
+    let mut species_pipeline = vec![doc! { "$match": base_filter.clone() }];
     species_pipeline.push(doc! { "$group": { "_id": "$species", "count": { "$sum": 1 } } });
     species_pipeline.push(doc! { "$sort": { "count": -1 } });
     let mut species_cursor = rodents_collection.aggregate(species_pipeline, None).await?;
@@ -189,10 +561,7 @@ pub async fn get_population_stats(
     }
 
     // By gender
-    let mut gender_pipeline = vec![];
-    if let Some(species) = &params.species {
-        gender_pipeline.push(doc! { "$match": { "species": species } });
-    }
+    let mut gender_pipeline = vec![doc! { "$match": base_filter.clone() }];
     gender_pipeline.push(doc! { "$group": { "_id": "$gender", "count": { "$sum": 1 } } });
     let mut gender_cursor = rodents_collection.aggregate(gender_pipeline, None).await?;
     let mut by_gender = GenderDistribution { male: 0, female: 0, unknown: 0 };
@@ -207,10 +576,7 @@ pub async fn get_population_stats(
     }
 
     // By status
-    let mut status_pipeline = vec![];
-    if let Some(species) = &params.species {
-        status_pipeline.push(doc! { "$match": { "species": species } });
-    }
+    let mut status_pipeline = vec![doc! { "$match": base_filter.clone() }];
     status_pipeline.push(doc! { "$group": { "_id": "$status", "count": { "$sum": 1 } } });
     status_pipeline.push(doc! { "$sort": { "count": -1 } });
     let mut status_cursor = rodents_collection.aggregate(status_pipeline, None).await?;
@@ -221,46 +587,54 @@ pub async fn get_population_stats(
         by_status.push(StatusCount { status, count });
     }
 
-    // By age group
-    let mut age_pipeline = vec![];
-    if let Some(species) = &params.species {
-        age_pipeline.push(doc! { "$match": { "species": species } });
-    }
+    // By age group, computed from date_of_birth; rodents without one land in the default bucket
+    let mut age_pipeline = vec![doc! { "$match": base_filter.clone() }];
+    age_pipeline.push(doc! {
+        "$addFields": {
+            "age_months": {
+                "$cond": [
+                    { "$eq": [{ "$type": "$date_of_birth" }, "date"] },
+                    { "$dateDiff": { "startDate": "$date_of_birth", "endDate": "$$NOW", "unit": "month" } },
+                    null
+                ]
+            }
+        }
+    });
+    let mut boundaries: Vec<i32> = AGE_BUCKETS.iter().map(|(lower, _)| *lower).collect();
+    boundaries.push(MAX_AGE_MONTHS);
     age_pipeline.push(doc! {
         "$bucket": {
             "groupBy": "$age_months",
-            "boundaries": [0, 3, 6, 12, 24, 100],
-            "default": "unknown",
+            "boundaries": boundaries,
+            "default": UNKNOWN_AGE_GROUP,
             "output": { "count": { "$sum": 1 } }
         }
     });
-    let mut age_cursor = rodents_collection.aggregate(age_pipeline, None).await?;
-    let mut by_age_group = Vec::new();
-    let age_labels = ["0-3 months", "3-6 months", "6-12 months", "1-2 years", "2+ years"];
-    let mut idx = 0;
-    while let Some(doc) = age_cursor.try_next().await? {
-        let count = get_number_as_i64(&doc, "count");
-        let age_group = age_labels.get(idx).unwrap_or(&"unknown").to_string();
-        by_age_group.push(AgeGroupCount { age_group, count });
-        idx += 1;
-    }
+    let age_buckets: Vec<Document> = rodents_collection
+        .aggregate(age_pipeline, None)
+        .await?
+        .try_collect()
+        .await?;
+    let by_age_group = age_group_counts(&age_buckets);
 
     // Recent intakes (last 30 days)
     let thirty_days_ago = Utc::now() - Duration::days(30);
-    let mut intake_filter = doc! { "intake_date": { "$gte": thirty_days_ago } };
-    if let Some(species) = &params.species {
-        intake_filter.insert("species", species);
-    }
+    let mut intake_filter = base_filter.clone();
+    intake_filter.insert("intake_date", doc! { "$gte": thirty_days_ago });
     let recent_intakes = rodents_collection.count_documents(intake_filter, None).await?;
 
     // Recent adoptions (last 30 days)
-    let status_history = state.db.rodent_db.collection::<Document>("status_history");
-    let recent_adoptions = status_history.count_documents(doc! {
+    let mut adoption_filter = doc! {
         "new_status": "adopted",
         "changed_at": { "$gte": thirty_days_ago }
-    }, None).await?;
+    };
+    let non_sample_ids = get_rodent_ids(&state, None, params.exclude_samples).await?;
+    if let Some(filter) = build_rodent_filter(&non_sample_ids) {
+        adoption_filter.extend(filter);
+    }
+    let recent_adoptions = status_history.count_documents(adoption_filter, None).await?;
 
-    Ok(Json(PopulationStatsResponse {
+    Ok(cached_json(&version, PopulationStatsResponse {
         success: true,
         total_rodents: total_rodents as i64,
         by_species,
@@ -272,6 +646,64 @@ pub async fn get_population_stats(
     }))
 }
 
+// ============== Population Snapshots ==============
+
+/// Snapshot the current population under this month's label (admin only).
+/// The scheduled month-end snapshot replaces it once the month is over.
+pub async fn create_population_snapshot(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<SinglePopulationSnapshotResponse>), AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    require_admin(&auth_info)?;
+
+    let month = snapshots::month_label(Utc::now(), state.config.timezone);
+    let snapshot = snapshots::take_population_snapshot(&state.db, &month, "manual").await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(SinglePopulationSnapshotResponse {
+            success: true,
+            snapshot: PopulationSnapshotResponse::from(snapshot),
+        }),
+    ))
+}
+
+/// Most recent monthly snapshots (default 12, up to 120), oldest first
+pub async fn list_population_snapshots(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<SnapshotQueryParams>,
+) -> Result<Json<PopulationSnapshotListResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "list_population_snapshots");
+
+    if !can_view_analytics(&state.config, &auth_info.role) {
+        return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
+    }
+
+    let months = params.months.unwrap_or(12).clamp(1, 120);
+
+    let find_options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "month": -1 })
+        .limit(months)
+        .build();
+
+    let cursor = state
+        .db
+        .analytics_db
+        .collection::<PopulationSnapshot>("population_snapshots")
+        .find(doc! {}, find_options)
+        .await?;
+    let mut snapshots: Vec<PopulationSnapshot> = cursor.try_collect().await?;
+    snapshots.reverse();
+
+    Ok(Json(PopulationSnapshotListResponse {
+        success: true,
+        snapshots: snapshots.into_iter().map(PopulationSnapshotResponse::from).collect(),
+    }))
+}
+
 // ============== Health Analytics ==============
 
 pub async fn get_health_analytics(
@@ -280,15 +712,16 @@ pub async fn get_health_analytics(
     Query(params): Query<AnalyticsQueryParams>,
 ) -> Result<Json<HealthAnalyticsResponse>, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_health_analytics");
 
-    if !can_view_analytics(&auth_info.role) {
+    if !can_view_analytics(&state.config, &auth_info.role) {
         return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
     }
 
-    let (from_date, to_date) = get_date_range(params.from_date, params.to_date);
+    let (from_date, to_date) = get_date_range(&state.config, params.from_date, params.to_date, params.days)?;
 
-    // Get rodent IDs filtered by species if specified
-    let rodent_ids = get_rodent_ids_by_species(&state, params.species.as_ref()).await?;
+    // Get rodent IDs filtered by species / sample flag if specified
+    let rodent_ids = get_rodent_ids(&state, params.species.as_ref(), params.exclude_samples).await?;
     
     // Build base match filter
     let mut base_match = doc! {
@@ -308,7 +741,7 @@ pub async fn get_health_analytics(
         doc! { "$match": weight_match },
         doc! {
             "$group": {
-                "_id": { "$dateToString": { "format": "%Y-%m-%d", "date": "$date" } },
+                "_id": { "$dateToString": { "format": "%Y-%m-%d", "date": "$date", "timezone": state.config.timezone.name() } },
                 "avg_weight": { "$avg": "$weight_grams" },
                 "min_weight": { "$min": "$weight_grams" },
                 "max_weight": { "$max": "$weight_grams" },
@@ -339,6 +772,9 @@ pub async fn get_health_analytics(
         });
     }
 
+    let weight_trend_slope = weight_trend_slope(&weight_trends);
+    let weight_trend_direction = weight_trend_direction(weight_trend_slope);
+
     // Note: Cross-database lookups are not supported in MongoDB, so we'll skip this for now
     let avg_weight_by_species: Vec<SpeciesWeightAvg> = Vec::new();
 
@@ -437,7 +873,7 @@ pub async fn get_health_analytics(
         .into_iter()
         .map(|(id, rodent_id, record_type, description, diagnosis, date, veterinarian_name)| {
             let rodent_name = treatment_names_map.get(&rodent_id)
-                .cloned()
+                .map(|r| r.name.clone())
                 .unwrap_or_else(|| format!("Rodent {}", &rodent_id[..8.min(rodent_id.len())]));
             RecentTreatment {
                 id,
@@ -471,6 +907,8 @@ pub async fn get_health_analytics(
     Ok(Json(HealthAnalyticsResponse {
         success: true,
         weight_trends,
+        weight_trend_slope,
+        weight_trend_direction,
         avg_weight_by_species,
         energy_level_distribution,
         mood_level_distribution,
@@ -480,95 +918,306 @@ pub async fn get_health_analytics(
     }))
 }
 
-// ============== Activity Analytics ==============
+// ============== Medication Usage ==============
 
-pub async fn get_activity_analytics(
+/// How often each medication was prescribed over the date range, grouped by
+/// name regardless of case or surrounding whitespace
+pub async fn get_medication_usage(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Query(params): Query<AnalyticsQueryParams>,
-) -> Result<Json<ActivityAnalyticsResponse>, AppError> {
+) -> Result<Json<MedicationUsageResponse>, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_medication_usage");
 
-    if !can_view_analytics(&auth_info.role) {
+    if !can_view_analytics(&state.config, &auth_info.role) {
         return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
     }
 
-    let (from_date, to_date) = get_date_range(params.from_date, params.to_date);
+    let (from_date, to_date) = get_date_range(&state.config, params.from_date, params.to_date, params.days)?;
+    let rodent_ids = get_rodent_ids(&state, params.species.as_ref(), params.exclude_samples).await?;
 
-    // Get rodent IDs filtered by species if specified
-    let rodent_ids = get_rodent_ids_by_species(&state, params.species.as_ref()).await?;
-    
-    // Build base match filter
-    let mut base_match = doc! {
-        "recorded_at": { "$gte": from_date, "$lte": to_date }
+    let mut record_match = doc! {
+        "date": { "$gte": from_date, "$lte": to_date }
     };
     if let Some(ref filter) = build_rodent_filter(&rodent_ids) {
-        base_match.extend(filter.clone());
+        record_match.extend(filter.clone());
     }
 
-    let activities = state.db.activity_db.collection::<Document>("activities");
-
-    // Total activity minutes
-    let total_pipeline = vec![
-        doc! { "$match": base_match.clone() },
-        doc! { "$group": { "_id": null, "total_minutes": { "$sum": "$duration_minutes" }, "session_count": { "$sum": 1 } } },
+    let pipeline = vec![
+        doc! { "$match": record_match },
+        doc! { "$unwind": "$medications" },
+        doc! {
+            "$group": {
+                "_id": { "$toLower": { "$trim": { "input": "$medications.name" } } },
+                "name": { "$first": { "$trim": { "input": "$medications.name" } } },
+                "count": { "$sum": 1 },
+                "rodents": { "$addToSet": "$rodent_id" },
+                "record_types": { "$addToSet": "$record_type" }
+            }
+        },
+        doc! { "$sort": { "count": -1, "_id": 1 } },
     ];
 
-    let mut total_cursor = activities.aggregate(total_pipeline, None).await?;
-    let (total_activity_minutes, _total_sessions) = if let Some(doc) = total_cursor.try_next().await? {
-        (get_number_as_i64(&doc, "total_minutes"), get_number_as_i64(&doc, "session_count"))
-    } else {
-        (0, 0)
-    };
-
-    let days_in_range = (to_date - from_date).num_days().max(1);
-    let avg_daily_activity = total_activity_minutes as f64 / days_in_range as f64;
+    let medical_records = state.db.rodent_db.collection::<Document>("medical_records");
+    let mut cursor = medical_records.aggregate(pipeline, None).await?;
+    let mut medications = Vec::new();
 
-    // By activity type
-    let type_pipeline = vec![
-        doc! { "$match": base_match.clone() },
-        doc! { "$group": { "_id": "$activity_type", "total_minutes": { "$sum": "$duration_minutes" }, "session_count": { "$sum": 1 } } },
-        doc! { "$sort": { "total_minutes": -1 } },
-    ];
+    while let Some(doc) = cursor.try_next().await? {
+        let mut record_types: Vec<String> = doc
+            .get_array("record_types")
+            .map(|types| types.iter().filter_map(|t| t.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+        record_types.sort();
 
-    let mut type_cursor = activities.aggregate(type_pipeline, None).await?;
-    let mut by_activity_type = Vec::new();
-    while let Some(doc) = type_cursor.try_next().await? {
-        let total_minutes = get_number_as_i64(&doc, "total_minutes");
-        let session_count = get_number_as_i64(&doc, "session_count");
-        by_activity_type.push(ActivityTypeStats {
-            activity_type: doc.get_str("_id").unwrap_or("unknown").to_string(),
-            total_minutes,
-            session_count,
-            avg_duration: if session_count > 0 { total_minutes as f64 / session_count as f64 } else { 0.0 },
+        medications.push(MedicationUsage {
+            name: doc.get_str("name").unwrap_or("unknown").to_string(),
+            count: get_number_as_i64(&doc, "count"),
+            rodent_count: doc.get_array("rodents").map(|r| r.len() as i64).unwrap_or(0),
+            record_types,
         });
     }
 
-    // Activity by hour
-    let hour_pipeline = vec![
-        doc! { "$match": base_match.clone() },
-        doc! { "$group": { "_id": { "$hour": "$recorded_at" }, "total_minutes": { "$sum": "$duration_minutes" }, "session_count": { "$sum": 1 } } },
-        doc! { "$sort": { "_id": 1 } },
+    Ok(Json(MedicationUsageResponse {
+        success: true,
+        from_date,
+        to_date,
+        medications,
+    }))
+}
+
+// ============== Staff Activity ==============
+
+/// Records one user created in a collection, with their display name as of the latest one
+struct UserRecordCount {
+    user_id: String,
+    name: String,
+    last_recorded_at: Option<DateTime<Utc>>,
+    count: i64,
+}
+
+/// Count the records each user created in `collection` between `from` and `to`,
+/// keyed by user ID with the most recent display name
+async fn count_records_by_user(
+    collection: &mongodb::Collection<Document>,
+    date_field: &str,
+    user_field: &str,
+    name_field: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<UserRecordCount>, AppError> {
+    let pipeline = vec![
+        doc! { "$match": { date_field: { "$gte": from, "$lte": to } } },
+        doc! { "$sort": { date_field: 1 } },
+        doc! {
+            "$group": {
+                "_id": format!("${}", user_field),
+                "name": { "$last": format!("${}", name_field) },
+                "last_recorded_at": { "$max": format!("${}", date_field) },
+                "count": { "$sum": 1 }
+            }
+        },
     ];
 
-    let mut hour_cursor = activities.aggregate(hour_pipeline, None).await?;
-    let mut activity_by_hour = Vec::new();
-    while let Some(doc) = hour_cursor.try_next().await? {
-        activity_by_hour.push(HourlyActivity {
-            hour: get_number_as_i64(&doc, "_id") as i32,
-            total_minutes: get_number_as_i64(&doc, "total_minutes"),
-            session_count: get_number_as_i64(&doc, "session_count"),
+    let mut counts = Vec::new();
+    let mut cursor = collection.aggregate(pipeline, None).await?;
+    while let Some(doc) = cursor.try_next().await? {
+        counts.push(UserRecordCount {
+            user_id: doc.get_str("_id").unwrap_or("unknown").to_string(),
+            name: doc.get_str("name").unwrap_or("unknown").to_string(),
+            last_recorded_at: doc.get_datetime("last_recorded_at").ok().map(|d| d.to_chrono()),
+            count: get_number_as_i64(&doc, "count"),
         });
     }
+    Ok(counts)
+}
 
-    // Activity by day of week
-    let day_pipeline = vec![
-        doc! { "$match": base_match.clone() },
-        doc! { "$group": { "_id": { "$dayOfWeek": "$recorded_at" }, "total_minutes": { "$sum": "$duration_minutes" }, "session_count": { "$sum": 1 } } },
-        doc! { "$sort": { "_id": 1 } },
-    ];
+/// Staff totals keyed by user ID, with the time their current name was recorded
+type StaffByUser = std::collections::HashMap<String, (StaffActivity, Option<DateTime<Utc>>)>;
+
+/// Fold one collection's counts into `by_user`. The name is taken from
+/// whichever collection holds the user's most recent record.
+fn merge_staff_counts(
+    by_user: &mut StaffByUser,
+    counts: Vec<UserRecordCount>,
+    set_count: fn(&mut StaffActivity, i64),
+) {
+    for record in counts {
+        let (staff, name_as_of) = by_user.entry(record.user_id.clone()).or_insert_with(|| {
+            let staff = StaffActivity {
+                user_id: record.user_id,
+                ..Default::default()
+            };
+            (staff, None)
+        });
+        if staff.name.is_empty() || record.last_recorded_at > *name_as_of {
+            staff.name = record.name;
+            *name_as_of = record.last_recorded_at;
+        }
+        set_count(staff, record.count);
+    }
+}
 
-    let mut day_cursor = activities.aggregate(day_pipeline, None).await?;
+/// Records logged per staff member across activities, feedings and daily records
+pub async fn get_staff_activity(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<StaffActivityQueryParams>,
+) -> Result<Json<StaffActivityResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_staff_activity");
+
+    if !matches!(auth_info.role.as_str(), "admin" | "caretaker") {
+        return Err(AppError::AccessDenied("Admin or caretaker access required".to_string()));
+    }
+
+    let (from_date, to_date) = get_date_range(&state.config, params.from_date, params.to_date, params.days)?;
+
+    let activities = state.db.activity_db.collection::<Document>("activities");
+    let feeding_records = state.db.activity_db.collection::<Document>("feeding_records");
+    let daily_records = state.db.activity_db.collection::<Document>("daily_records");
+
+    let (activity_counts, feeding_counts, daily_counts) = futures::try_join!(
+        count_records_by_user(&activities, "recorded_at", "recorded_by", "recorded_by_name", from_date, to_date),
+        count_records_by_user(&feeding_records, "meal_time", "recorded_by", "recorded_by_name", from_date, to_date),
+        count_records_by_user(&daily_records, "date", "created_by", "created_by_name", from_date, to_date),
+    )?;
+
+    let mut by_user = StaffByUser::new();
+    merge_staff_counts(&mut by_user, activity_counts, |staff, count| staff.activities = count);
+    merge_staff_counts(&mut by_user, feeding_counts, |staff, count| staff.feeding_records = count);
+    merge_staff_counts(&mut by_user, daily_counts, |staff, count| staff.daily_records = count);
+
+    let mut staff: Vec<StaffActivity> = by_user
+        .into_values()
+        .map(|(mut s, _)| {
+            s.total = s.activities + s.feeding_records + s.daily_records;
+            s
+        })
+        .collect();
+    staff.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(Json(StaffActivityResponse {
+        success: true,
+        from_date,
+        to_date,
+        staff,
+    }))
+}
+
+// ============== Activity Analytics ==============
+
+pub async fn get_activity_analytics(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<ActivityAnalyticsQueryParams>,
+) -> Result<Response, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_activity_analytics");
+
+    if !can_view_analytics(&state.config, &auth_info.role) {
+        return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
+    }
+
+    let (from_date, to_date) = get_date_range(&state.config, params.from_date, params.to_date, params.days)?;
+
+    // Get rodent IDs filtered by species / sample flag if specified
+    let rodent_ids = get_rodent_ids(&state, params.species.as_ref(), params.exclude_samples).await?;
+    
+    // Build base match filter
+    let mut base_match = doc! {
+        "recorded_at": { "$gte": from_date, "$lte": to_date }
+    };
+    if let Some(ref filter) = build_rodent_filter(&rodent_ids) {
+        base_match.extend(filter.clone());
+    }
+
+    // Bucket by the shelter's timezone like the other analytics unless an offset is given
+    let tz_offset = parse_tz_offset(params.tz_offset.as_deref())?
+        .unwrap_or_else(|| state.config.timezone.name().to_string());
+    let hours = hour_window(params.hour_from, params.hour_to)?;
+
+    // Activities are only edited when reassigned to another rodent, which sets updated_at
+    let activities = state.db.activity_db.collection::<Document>("activities");
+    let version = source_version(
+        &activities,
+        base_match.clone(),
+        doc! { "$ifNull": ["$updated_at", "$created_at"] },
+    )
+    .await?;
+    if version.is_fresh(&headers) {
+        return Ok(not_modified(&version));
+    }
+
+    // Every pipeline starts by resolving the local hour, then (optionally) keeping only the window
+    let mut prefix = vec![
+        doc! { "$match": base_match },
+        doc! { "$addFields": { "local_hour": { "$hour": { "date": "$recorded_at", "timezone": &tz_offset } } } },
+    ];
+    if let Some(ref hours) = hours {
+        prefix.push(doc! { "$match": { "local_hour": { "$in": hours } } });
+    }
+    let pipeline = |stages: Vec<Document>| -> Vec<Document> {
+        prefix.iter().cloned().chain(stages).collect()
+    };
+
+    // Total activity minutes
+    let total_pipeline = pipeline(vec![
+        doc! { "$group": { "_id": null, "total_minutes": { "$sum": "$duration_minutes" }, "session_count": { "$sum": 1 } } },
+    ]);
+
+    let mut total_cursor = activities.aggregate(total_pipeline, None).await?;
+    let (total_activity_minutes, _total_sessions) = if let Some(doc) = total_cursor.try_next().await? {
+        (get_number_as_i64(&doc, "total_minutes"), get_number_as_i64(&doc, "session_count"))
+    } else {
+        (0, 0)
+    };
+
+    let days_in_range = (to_date - from_date).num_days().max(1);
+    let avg_daily_activity = total_activity_minutes as f64 / days_in_range as f64;
+
+    // By activity type
+    let type_pipeline = pipeline(vec![
+        doc! { "$group": { "_id": "$activity_type", "total_minutes": { "$sum": "$duration_minutes" }, "session_count": { "$sum": 1 } } },
+        doc! { "$sort": { "total_minutes": -1 } },
+    ]);
+
+    let mut type_cursor = activities.aggregate(type_pipeline, None).await?;
+    let mut by_activity_type = Vec::new();
+    while let Some(doc) = type_cursor.try_next().await? {
+        let total_minutes = get_number_as_i64(&doc, "total_minutes");
+        let session_count = get_number_as_i64(&doc, "session_count");
+        by_activity_type.push(ActivityTypeStats {
+            activity_type: doc.get_str("_id").unwrap_or("unknown").to_string(),
+            total_minutes,
+            session_count,
+            avg_duration: if session_count > 0 { total_minutes as f64 / session_count as f64 } else { 0.0 },
+        });
+    }
+
+    // Activity by hour
+    let hour_pipeline = pipeline(vec![
+        doc! { "$group": { "_id": "$local_hour", "total_minutes": { "$sum": "$duration_minutes" }, "session_count": { "$sum": 1 } } },
+        doc! { "$sort": { "_id": 1 } },
+    ]);
+
+    let mut hour_cursor = activities.aggregate(hour_pipeline, None).await?;
+    let mut activity_by_hour = Vec::new();
+    while let Some(doc) = hour_cursor.try_next().await? {
+        activity_by_hour.push(HourlyActivity {
+            hour: get_number_as_i64(&doc, "_id") as i32,
+            total_minutes: get_number_as_i64(&doc, "total_minutes"),
+            session_count: get_number_as_i64(&doc, "session_count"),
+        });
+    }
+
+    // Activity by day of week
+    let day_pipeline = pipeline(vec![
+        doc! { "$group": { "_id": { "$dayOfWeek": { "date": "$recorded_at", "timezone": &tz_offset } }, "total_minutes": { "$sum": "$duration_minutes" }, "session_count": { "$sum": 1 } } },
+        doc! { "$sort": { "_id": 1 } },
+    ]);
+
+    let mut day_cursor = activities.aggregate(day_pipeline, None).await?;
     let mut activity_by_day_of_week = Vec::new();
     let day_names = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
     while let Some(doc) = day_cursor.try_next().await? {
@@ -581,12 +1230,11 @@ pub async fn get_activity_analytics(
     }
 
     // Most active rodents
-    let active_pipeline = vec![
-        doc! { "$match": base_match.clone() },
+    let active_pipeline = pipeline(vec![
         doc! { "$group": { "_id": "$rodent_id", "total_minutes": { "$sum": "$duration_minutes" }, "session_count": { "$sum": 1 } } },
         doc! { "$sort": { "total_minutes": -1 } },
         doc! { "$limit": 10 },
-    ];
+    ]);
 
     let mut active_cursor = activities.aggregate(active_pipeline, None).await?;
     let mut rodent_stats_temp = Vec::new();
@@ -607,18 +1255,22 @@ pub async fn get_activity_analytics(
         .into_iter()
         .map(|(rodent_id, total_minutes, session_count)| {
             let rodent_name = names_map.get(&rodent_id)
-                .cloned()
+                .map(|r| r.name.clone())
                 .unwrap_or_else(|| format!("Rodent {}", &rodent_id[..8.min(rodent_id.len())]));
+            let species = names_map.get(&rodent_id)
+                .map(|r| r.species.clone())
+                .unwrap_or_else(|| "unknown".to_string());
             RodentActivityStats {
                 rodent_id,
                 rodent_name,
+                species,
                 total_minutes,
                 session_count,
             }
         })
         .collect();
 
-    Ok(Json(ActivityAnalyticsResponse {
+    Ok(cached_json(&version, ActivityAnalyticsResponse {
         success: true,
         total_activity_minutes,
         avg_daily_activity,
@@ -629,23 +1281,331 @@ pub async fn get_activity_analytics(
     }))
 }
 
+// ============== Activity Intensity ==============
+
+/// Weight for activity types missing from `ACTIVITY_INTENSITY_WEIGHTS`
+const DEFAULT_INTENSITY_WEIGHT: f64 = 1.0;
+
+/// Rank rodents by activity minutes weighted by how strenuous each activity type is
+pub async fn get_intensity_scores(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<IntensityQueryParams>,
+) -> Result<Json<IntensityScoresResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_intensity_scores");
+
+    if !can_view_analytics(&state.config, &auth_info.role) {
+        return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
+    }
+
+    let (from_date, to_date) = get_date_range(&state.config, params.from_date, params.to_date, params.days)?;
+    let limit = page_limit(&state.config, params.limit, 20)?;
+
+    let rodent_ids = get_rodent_ids(&state, params.species.as_ref(), params.exclude_samples).await?;
+    let mut base_match = doc! {
+        "recorded_at": { "$gte": from_date, "$lte": to_date }
+    };
+    if let Some(filter) = build_rodent_filter(&rodent_ids) {
+        base_match.extend(filter);
+    }
+
+    let weights = &state.config.activity_intensity_weights;
+    let branches: Vec<Document> = weights
+        .iter()
+        .map(|(activity_type, weight)| doc! {
+            "case": { "$eq": ["$activity_type", activity_type] },
+            "then": weight,
+        })
+        .collect();
+    let weight_expr = doc! { "$switch": { "branches": branches, "default": DEFAULT_INTENSITY_WEIGHT } };
+
+    let pipeline = vec![
+        doc! { "$match": base_match },
+        doc! {
+            "$group": {
+                "_id": "$rodent_id",
+                "score": { "$sum": { "$multiply": ["$duration_minutes", weight_expr] } },
+                "total_minutes": { "$sum": "$duration_minutes" },
+                "session_count": { "$sum": 1 }
+            }
+        },
+        doc! { "$sort": { "score": -1, "_id": 1 } },
+        doc! { "$limit": limit as i64 },
+    ];
+
+    let activities = state.db.activity_db.collection::<Document>("activities");
+    let mut cursor = activities.aggregate(pipeline, None).await?;
+    let mut scored = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        let rodent_id = doc.get_object_id("_id").map(|id| id.to_hex()).unwrap_or_else(|_| "unknown".to_string());
+        scored.push((
+            rodent_id,
+            get_number_as_f64(&doc, "score"),
+            get_number_as_i64(&doc, "total_minutes"),
+            get_number_as_i64(&doc, "session_count"),
+        ));
+    }
+
+    let ids: Vec<String> = scored.iter().map(|(id, ..)| id.clone()).collect();
+    let names_map = get_rodent_names_by_ids(&state, &ids).await?;
+
+    let rodents = scored
+        .into_iter()
+        .enumerate()
+        .map(|(index, (rodent_id, score, total_minutes, session_count))| {
+            let rodent_name = names_map.get(&rodent_id)
+                .map(|r| r.name.clone())
+                .unwrap_or_else(|| format!("Rodent {}", &rodent_id[..8.min(rodent_id.len())]));
+            RodentIntensityScore {
+                rank: index + 1,
+                rodent_id,
+                rodent_name,
+                score: (score * 100.0).round() / 100.0,
+                total_minutes,
+                session_count,
+                avg_intensity: if total_minutes > 0 {
+                    ((score / total_minutes as f64) * 100.0).round() / 100.0
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect();
+
+    Ok(Json(IntensityScoresResponse {
+        success: true,
+        from_date,
+        to_date,
+        weights: weights.clone(),
+        default_weight: DEFAULT_INTENSITY_WEIGHT,
+        rodents,
+    }))
+}
+
+// ============== Activity / Feeding Correlation ==============
+
+/// Coefficient below which a rodent's activity and feeding count as decoupled
+const DEFAULT_CORRELATION_THRESHOLD: f64 = 0.3;
+
+/// Fewest days with records needed before a coefficient means anything
+const DEFAULT_CORRELATION_MIN_DAYS: usize = 7;
+
+/// Daily totals per rodent, keyed by rodent id (hex) and then `YYYY-MM-DD`
+type DailyTotals = std::collections::HashMap<String, std::collections::HashMap<String, f64>>;
+
+/// Sum `value_field` per rodent per local day over the documents matching `filter`
+async fn daily_totals_by_rodent(
+    collection: &mongodb::Collection<Document>,
+    filter: Document,
+    date_field: &str,
+    value_field: &str,
+    timezone: &str,
+) -> Result<DailyTotals, AppError> {
+    let pipeline = vec![
+        doc! { "$match": filter },
+        doc! {
+            "$group": {
+                "_id": {
+                    "rodent_id": "$rodent_id",
+                    "day": { "$dateToString": { "format": "%Y-%m-%d", "date": format!("${}", date_field), "timezone": timezone } }
+                },
+                "total": { "$sum": format!("${}", value_field) }
+            }
+        },
+    ];
+
+    let mut totals = DailyTotals::new();
+    let mut cursor = collection.aggregate(pipeline, None).await?;
+    while let Some(doc) = cursor.try_next().await? {
+        let Ok(key) = doc.get_document("_id") else { continue };
+        let (Ok(rodent_id), Ok(day)) = (key.get_object_id("rodent_id"), key.get_str("day")) else {
+            continue;
+        };
+        totals
+            .entry(rodent_id.to_hex())
+            .or_default()
+            .insert(day.to_string(), get_number_as_f64(&doc, "total"));
+    }
+
+    Ok(totals)
+}
+
+/// Pearson correlation coefficient of paired samples, or why there is none:
+/// fewer than two pairs, or a side that never varies
+fn pearson_correlation(pairs: &[(f64, f64)]) -> Result<f64, CorrelationSkipReason> {
+    if pairs.len() < 2 {
+        return Err(CorrelationSkipReason::TooFewDays);
+    }
+
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let covariance: f64 = pairs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance_x: f64 = pairs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let variance_y: f64 = pairs.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+
+    if variance_x == 0.0 {
+        return Err(CorrelationSkipReason::ConstantActivity);
+    }
+    if variance_y == 0.0 {
+        return Err(CorrelationSkipReason::ConstantFeeding);
+    }
+
+    Ok(covariance / (variance_x * variance_y).sqrt())
+}
+
+/// Find rodents whose daily activity minutes don't track their daily food intake
+pub async fn get_activity_feeding_correlation(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<ActivityFeedingCorrelationQueryParams>,
+) -> Result<Json<ActivityFeedingCorrelationResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_activity_feeding_correlation");
+
+    if !can_view_analytics(&state.config, &auth_info.role) {
+        return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
+    }
+
+    let (from_date, to_date) = get_date_range(&state.config, params.from_date, params.to_date, params.days)?;
+
+    let threshold = params.threshold.unwrap_or(DEFAULT_CORRELATION_THRESHOLD);
+    if !(-1.0..=1.0).contains(&threshold) {
+        return Err(AppError::ValidationError("threshold must be between -1 and 1".to_string()));
+    }
+    let min_days = params.min_days.unwrap_or(DEFAULT_CORRELATION_MIN_DAYS).max(2);
+
+    let rodent_ids = get_rodent_ids(&state, params.species.as_ref(), params.exclude_samples).await?;
+    let rodent_filter = build_rodent_filter(&rodent_ids);
+
+    let mut activity_match = doc! { "recorded_at": { "$gte": from_date, "$lte": to_date } };
+    // Water isn't food; it would swamp the grams on hot days
+    let mut feeding_match = doc! {
+        "meal_time": { "$gte": from_date, "$lte": to_date },
+        "food_type": { "$ne": "water" }
+    };
+    if let Some(filter) = rodent_filter {
+        activity_match.extend(filter.clone());
+        feeding_match.extend(filter);
+    }
+
+    let timezone = state.config.timezone.name();
+    let activities = state.db.activity_db.collection::<Document>("activities");
+    let feeding_records = state.db.activity_db.collection::<Document>("feeding_records");
+    let (activity_totals, feeding_totals) = futures::try_join!(
+        daily_totals_by_rodent(&activities, activity_match, "recorded_at", "duration_minutes", timezone),
+        daily_totals_by_rodent(&feeding_records, feeding_match, "meal_time", "quantity_grams", timezone),
+    )?;
+
+    // Join per rodent on the day; a day recorded on only one side counts as zero on the other
+    let no_days = std::collections::HashMap::new();
+    let mut rodent_ids: std::collections::BTreeSet<&String> = activity_totals.keys().collect();
+    rodent_ids.extend(feeding_totals.keys());
+
+    let mut rodents_analyzed = 0;
+    let mut decoupled = Vec::new();
+    let mut skipped = Vec::new();
+    for rodent_id in rodent_ids {
+        let activity_days = activity_totals.get(rodent_id).unwrap_or(&no_days);
+        let feeding_days = feeding_totals.get(rodent_id).unwrap_or(&no_days);
+
+        let mut days: std::collections::BTreeSet<&String> = activity_days.keys().collect();
+        days.extend(feeding_days.keys());
+
+        let pairs: Vec<(f64, f64)> = days
+            .iter()
+            .map(|day| {
+                (
+                    activity_days.get(*day).copied().unwrap_or(0.0),
+                    feeding_days.get(*day).copied().unwrap_or(0.0),
+                )
+            })
+            .collect();
+
+        let correlation = if pairs.len() < min_days {
+            Err(CorrelationSkipReason::TooFewDays)
+        } else {
+            pearson_correlation(&pairs)
+        };
+        let correlation = match correlation {
+            Ok(correlation) => correlation,
+            Err(reason) => {
+                skipped.push(SkippedActivityFeedingCorrelation {
+                    rodent_id: rodent_id.clone(),
+                    rodent_name: String::new(),
+                    days: pairs.len(),
+                    reason,
+                });
+                continue;
+            }
+        };
+        rodents_analyzed += 1;
+
+        if correlation < threshold {
+            decoupled.push(RodentActivityFeedingCorrelation {
+                rodent_name: String::new(),
+                correlation: (correlation * 1000.0).round() / 1000.0,
+                days: pairs.len(),
+                total_activity_minutes: pairs.iter().map(|(minutes, _)| minutes).sum(),
+                total_food_grams: pairs.iter().map(|(_, grams)| grams).sum(),
+                rodent_id: rodent_id.clone(),
+            });
+        }
+    }
+
+    decoupled.sort_by(|a, b| a.correlation.total_cmp(&b.correlation).then_with(|| a.rodent_id.cmp(&b.rodent_id)));
+
+    let ids: Vec<String> = decoupled
+        .iter()
+        .map(|r| r.rodent_id.clone())
+        .chain(skipped.iter().map(|r| r.rodent_id.clone()))
+        .collect();
+    let names_map = get_rodent_names_by_ids(&state, &ids).await?;
+    let rodent_name = |rodent_id: &str| {
+        names_map
+            .get(rodent_id)
+            .map(|r| r.name.clone())
+            .unwrap_or_else(|| format!("Rodent {}", &rodent_id[..8.min(rodent_id.len())]))
+    };
+    for rodent in &mut decoupled {
+        rodent.rodent_name = rodent_name(&rodent.rodent_id);
+    }
+    for rodent in &mut skipped {
+        rodent.rodent_name = rodent_name(&rodent.rodent_id);
+    }
+
+    Ok(Json(ActivityFeedingCorrelationResponse {
+        success: true,
+        from_date,
+        to_date,
+        threshold,
+        min_days,
+        rodents_analyzed,
+        decoupled,
+        skipped,
+    }))
+}
+
 // ============== Feeding Analytics ==============
 
 pub async fn get_feeding_analytics(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-    Query(params): Query<AnalyticsQueryParams>,
-) -> Result<Json<FeedingAnalyticsResponse>, AppError> {
+    Query(params): Query<FeedingAnalyticsQueryParams>,
+) -> Result<Response, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_feeding_analytics");
 
-    if !can_view_analytics(&auth_info.role) {
+    if !can_view_analytics(&state.config, &auth_info.role) {
         return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
     }
 
-    let (from_date, to_date) = get_date_range(params.from_date, params.to_date);
+    let (from_date, to_date) = get_date_range(&state.config, params.from_date, params.to_date, params.days)?;
 
-    // Get rodent IDs filtered by species if specified
-    let rodent_ids = get_rodent_ids_by_species(&state, params.species.as_ref()).await?;
+    // Get rodent IDs filtered by species / sample flag if specified
+    let rodent_ids = get_rodent_ids(&state, params.species.as_ref(), params.exclude_samples).await?;
     
     // Build base match filter
     let mut base_match = doc! {
@@ -657,27 +1617,51 @@ pub async fn get_feeding_analytics(
 
     let feeding_records = state.db.activity_db.collection::<Document>("feeding_records");
 
-    // Total food consumption
+    let version = source_version(
+        &feeding_records,
+        base_match.clone(),
+        doc! { "$ifNull": ["$updated_at", "$created_at"] },
+    )
+    .await?;
+    if version.is_fresh(&headers) {
+        return Ok(not_modified(&version));
+    }
+
+    // Total food consumption, with water summed separately as hydration
     let total_pipeline = vec![
         doc! { "$match": base_match.clone() },
-        doc! { "$group": { "_id": null, "total_grams": { "$sum": "$quantity_grams" }, "feeding_count": { "$sum": 1 }, "consumed_fully_count": { "$sum": { "$cond": [{ "$eq": ["$consumed_fully", true] }, 1, 0] } } } },
+        doc! { "$group": { "_id": null, "total_grams": { "$sum": "$quantity_grams" }, "water_grams": { "$sum": { "$cond": [{ "$eq": ["$food_type", "water"] }, "$quantity_grams", 0] } }, "feeding_count": { "$sum": 1 }, "consumed_fully_count": consumed_fully_sum(), "known_outcome_count": known_outcome_sum() } },
     ];
 
     let mut total_cursor = feeding_records.aggregate(total_pipeline, None).await?;
-    let (total_food_grams, feeding_count, consumed_fully_count) = if let Some(doc) = total_cursor.try_next().await? {
-        (get_number_as_f64(&doc, "total_grams"), get_number_as_i64(&doc, "feeding_count"), get_number_as_i64(&doc, "consumed_fully_count"))
+    let (total_grams, total_water_grams, feeding_count, consumed_fully_count, known_outcome_count) = if let Some(doc) = total_cursor.try_next().await? {
+        (
+            get_number_as_f64(&doc, "total_grams"),
+            get_number_as_f64(&doc, "water_grams"),
+            get_number_as_i64(&doc, "feeding_count"),
+            get_number_as_i64(&doc, "consumed_fully_count"),
+            get_number_as_i64(&doc, "known_outcome_count"),
+        )
+    } else {
+        (0.0, 0.0, 0, 0, 0)
+    };
+
+    let total_food_grams = if params.exclude_water.unwrap_or(false) {
+        total_grams - total_water_grams
     } else {
-        (0.0, 0, 0)
+        total_grams
     };
 
     let days_in_range = (to_date - from_date).num_days().max(1);
     let avg_daily_food = total_food_grams / days_in_range as f64;
-    let consumption_rate = if feeding_count > 0 { consumed_fully_count as f64 / feeding_count as f64 * 100.0 } else { 0.0 };
+    let avg_daily_water = total_water_grams / days_in_range as f64;
+    let overall_consumption_rate = consumption_rate(consumed_fully_count, known_outcome_count);
+    let unknown_outcome_count = feeding_count - known_outcome_count;
 
     // By food type
     let type_pipeline = vec![
         doc! { "$match": base_match.clone() },
-        doc! { "$group": { "_id": "$food_type", "total_grams": { "$sum": "$quantity_grams" }, "feeding_count": { "$sum": 1 } } },
+        doc! { "$group": { "_id": "$food_type", "total_grams": { "$sum": "$quantity_grams" }, "feeding_count": { "$sum": 1 }, "consumed_fully_count": consumed_fully_sum(), "known_outcome_count": known_outcome_sum() } },
         doc! { "$sort": { "total_grams": -1 } },
     ];
 
@@ -691,13 +1675,17 @@ pub async fn get_feeding_analytics(
             total_grams,
             feeding_count,
             avg_quantity: if feeding_count > 0 { total_grams / feeding_count as f64 } else { 0.0 },
+            consumption_rate: consumption_rate(
+                get_number_as_i64(&doc, "consumed_fully_count"),
+                get_number_as_i64(&doc, "known_outcome_count"),
+            ),
         });
     }
 
     // Feeding by hour
     let hour_pipeline = vec![
         doc! { "$match": base_match.clone() },
-        doc! { "$group": { "_id": { "$hour": "$meal_time" }, "total_grams": { "$sum": "$quantity_grams" }, "feeding_count": { "$sum": 1 } } },
+        doc! { "$group": { "_id": { "$hour": { "date": "$meal_time", "timezone": state.config.timezone.name() } }, "total_grams": { "$sum": "$quantity_grams" }, "feeding_count": { "$sum": 1 } } },
         doc! { "$sort": { "_id": 1 } },
     ];
 
@@ -738,58 +1726,310 @@ pub async fn get_feeding_analytics(
         .into_iter()
         .map(|(rodent_id, total_grams, feeding_count)| {
             let rodent_name = names_map.get(&rodent_id)
-                .cloned()
+                .map(|r| r.name.clone())
                 .unwrap_or_else(|| format!("Rodent {}", &rodent_id[..8.min(rodent_id.len())]));
+            let species = names_map.get(&rodent_id)
+                .map(|r| r.species.clone())
+                .unwrap_or_else(|| "unknown".to_string());
             RodentFeedingStats {
                 rodent_id,
                 rodent_name,
+                species,
                 total_grams,
                 feeding_count,
             }
         })
         .collect();
 
-    Ok(Json(FeedingAnalyticsResponse {
+    // Feeding regularity: collect each rodent's meal times, then average the gaps in code
+    let interval_pipeline = vec![
+        doc! { "$match": base_match.clone() },
+        doc! { "$group": { "_id": "$rodent_id", "meal_times": { "$push": "$meal_time" } } },
+    ];
+
+    let mut interval_cursor = feeding_records.aggregate(interval_pipeline, None).await?;
+    let mut interval_temp = Vec::new();
+    while let Some(doc) = interval_cursor.try_next().await? {
+        let rodent_id = doc.get_object_id("_id").map(|id| id.to_hex()).unwrap_or_else(|_| "unknown".to_string());
+        let mut meal_times: Vec<DateTime<Utc>> = doc
+            .get_array("meal_times")
+            .map(|times| times.iter().filter_map(|t| t.as_datetime().map(|dt| dt.to_chrono())).collect())
+            .unwrap_or_default();
+
+        if let Some((avg_hours, max_hours)) = feeding_interval_hours(&mut meal_times) {
+            interval_temp.push((rodent_id, meal_times.len() as i64, avg_hours, max_hours));
+        }
+    }
+    interval_temp.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    let interval_rodent_ids: Vec<String> = interval_temp.iter().map(|(id, _, _, _)| id.clone()).collect();
+    let interval_names_map = get_rodent_names_by_ids(&state, &interval_rodent_ids).await?;
+
+    let feeding_intervals: Vec<RodentFeedingInterval> = interval_temp
+        .into_iter()
+        .map(|(rodent_id, feeding_count, avg_interval_hours, max_interval_hours)| {
+            let rodent_name = interval_names_map.get(&rodent_id)
+                .map(|r| r.name.clone())
+                .unwrap_or_else(|| format!("Rodent {}", &rodent_id[..8.min(rodent_id.len())]));
+            RodentFeedingInterval {
+                rodent_id,
+                rodent_name,
+                feeding_count,
+                avg_interval_hours,
+                max_interval_hours,
+            }
+        })
+        .collect();
+
+    // Sorted descending, so the median sits in the middle
+    let feeding_interval_outliers = match feeding_intervals.get(feeding_intervals.len() / 2) {
+        Some(median) => {
+            let cutoff = median.avg_interval_hours * FEEDING_INTERVAL_OUTLIER_FACTOR;
+            feeding_intervals
+                .iter()
+                .take_while(|i| i.avg_interval_hours > cutoff)
+                .cloned()
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    Ok(cached_json(&version, FeedingAnalyticsResponse {
         success: true,
         total_food_grams,
         avg_daily_food,
+        total_water_grams,
+        avg_daily_water,
         by_food_type,
         feeding_by_hour,
-        consumption_rate,
+        consumption_rate: overall_consumption_rate,
+        unknown_outcome_count,
         top_consumers,
+        feeding_intervals,
+        feeding_interval_outliers,
     }))
 }
 
-// ============== Dashboard Summary ==============
+// ============== Cohort Analytics ==============
 
-pub async fn get_dashboard_summary(
+/// Upper bound on how many rodents a single cohort request may name
+const MAX_COHORT_SIZE: usize = 200;
+
+pub async fn get_cohort_stats(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-) -> Result<Json<DashboardSummaryResponse>, AppError> {
+    Json(payload): Json<CohortStatsRequest>,
+) -> Result<Json<CohortStatsResponse>, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_cohort_stats");
 
-    if !can_view_analytics(&auth_info.role) {
+    if !can_view_analytics(&state.config, &auth_info.role) {
         return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
     }
 
-    let now = Utc::now();
-    let today_start = now.date_naive().and_hms_opt(0, 0, 0)
-        .map(|dt| DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc))
-        .unwrap_or(now);
-    let week_ago = now - Duration::days(7);
+    if payload.rodent_ids.is_empty() {
+        return Err(AppError::ValidationError("rodent_ids must not be empty".to_string()));
+    }
+    if payload.rodent_ids.len() > MAX_COHORT_SIZE {
+        return Err(AppError::ValidationError(format!(
+            "rodent_ids may contain at most {} entries",
+            MAX_COHORT_SIZE
+        )));
+    }
 
-    // Population summary
-    let rodents_collection = state.db.rodent_db.collection::<Document>("rodents");
-    let total_rodents = rodents_collection.count_documents(doc! {}, None).await? as i64;
-    let available_for_adoption = rodents_collection.count_documents(doc! { "status": "available" }, None).await? as i64;
-    let in_medical_care = rodents_collection.count_documents(doc! { "status": "medical_care" }, None).await? as i64;
-    let recent_intakes_week = rodents_collection.count_documents(doc! { "intake_date": { "$gte": week_ago } }, None).await? as i64;
+    let mut object_ids = payload
+        .rodent_ids
+        .iter()
+        .map(|id| bson::oid::ObjectId::parse_str(id).map_err(|_| AppError::InvalidId))
+        .collect::<Result<Vec<_>, _>>()?;
+    object_ids.sort();
+    object_ids.dedup();
 
-    // Activity summary
-    let activities = state.db.activity_db.collection::<Document>("activities");
+    let (from_date, to_date) = get_date_range(&state.config, payload.from_date, payload.to_date, payload.days)?;
+    let days_in_range = (to_date - from_date).num_days().max(1);
+
+    let rodent_ids = Some(object_ids);
+    let rodent_filter = build_rodent_filter(&rodent_ids).unwrap_or_default();
+
+    // Activity
+    let activities = state.db.activity_db.collection::<Document>("activities");
+    let mut activity_match = doc! { "recorded_at": { "$gte": from_date, "$lte": to_date } };
+    activity_match.extend(rodent_filter.clone());
+
+    let activity_pipeline = vec![
+        doc! { "$match": activity_match },
+        doc! { "$group": { "_id": "$activity_type", "total_minutes": { "$sum": "$duration_minutes" }, "session_count": { "$sum": 1 } } },
+        doc! { "$sort": { "total_minutes": -1 } },
+    ];
+
+    let mut activity_cursor = activities.aggregate(activity_pipeline, None).await?;
+    let mut by_activity_type = Vec::new();
+    while let Some(doc) = activity_cursor.try_next().await? {
+        let total_minutes = get_number_as_i64(&doc, "total_minutes");
+        let session_count = get_number_as_i64(&doc, "session_count");
+        by_activity_type.push(ActivityTypeStats {
+            activity_type: doc.get_str("_id").unwrap_or("unknown").to_string(),
+            total_minutes,
+            session_count,
+            avg_duration: if session_count > 0 { total_minutes as f64 / session_count as f64 } else { 0.0 },
+        });
+    }
+
+    let total_minutes: i64 = by_activity_type.iter().map(|t| t.total_minutes).sum();
+    let session_count: i64 = by_activity_type.iter().map(|t| t.session_count).sum();
+
+    // Feeding
+    let feeding_records = state.db.activity_db.collection::<Document>("feeding_records");
+    let mut feeding_match = doc! { "meal_time": { "$gte": from_date, "$lte": to_date } };
+    feeding_match.extend(rodent_filter.clone());
+
+    let feeding_pipeline = vec![
+        doc! { "$match": feeding_match },
+        doc! { "$group": { "_id": "$food_type", "total_grams": { "$sum": "$quantity_grams" }, "feeding_count": { "$sum": 1 }, "consumed_fully_count": consumed_fully_sum(), "known_outcome_count": known_outcome_sum() } },
+        doc! { "$sort": { "total_grams": -1 } },
+    ];
+
+    let mut feeding_cursor = feeding_records.aggregate(feeding_pipeline, None).await?;
+    let mut by_food_type = Vec::new();
+    let mut consumed_fully_count = 0;
+    let mut known_outcome_count = 0;
+    while let Some(doc) = feeding_cursor.try_next().await? {
+        let total_grams = get_number_as_f64(&doc, "total_grams");
+        let feeding_count = get_number_as_i64(&doc, "feeding_count");
+        consumed_fully_count += get_number_as_i64(&doc, "consumed_fully_count");
+        known_outcome_count += get_number_as_i64(&doc, "known_outcome_count");
+        by_food_type.push(FoodTypeStats {
+            food_type: doc.get_str("_id").unwrap_or("unknown").to_string(),
+            total_grams,
+            feeding_count,
+            avg_quantity: if feeding_count > 0 { total_grams / feeding_count as f64 } else { 0.0 },
+            consumption_rate: consumption_rate(
+                get_number_as_i64(&doc, "consumed_fully_count"),
+                get_number_as_i64(&doc, "known_outcome_count"),
+            ),
+        });
+    }
+
+    let total_grams: f64 = by_food_type.iter().map(|t| t.total_grams).sum();
+    let feeding_count: i64 = by_food_type.iter().map(|t| t.feeding_count).sum();
+
+    // Weight
+    let daily_records = state.db.activity_db.collection::<Document>("daily_records");
+    let mut weight_match = doc! {
+        "date": { "$gte": from_date, "$lte": to_date },
+        "weight_grams": { "$exists": true, "$ne": null }
+    };
+    weight_match.extend(rodent_filter);
+
+    let weight_pipeline = vec![
+        doc! { "$match": weight_match },
+        doc! {
+            "$group": {
+                "_id": { "$dateToString": { "format": "%Y-%m-%d", "date": "$date", "timezone": state.config.timezone.name() } },
+                "avg_weight": { "$avg": "$weight_grams" },
+                "min_weight": { "$min": "$weight_grams" },
+                "max_weight": { "$max": "$weight_grams" },
+                "sample_count": { "$sum": 1 },
+                "rodent_count": { "$addToSet": "$rodent_id" }
+            }
+        },
+        doc! { "$sort": { "_id": 1 } },
+    ];
+
+    let mut weight_cursor = daily_records.aggregate(weight_pipeline, None).await?;
+    let mut weight_trends = Vec::new();
+    let mut sample_count = 0;
+    let mut weight_sum = 0.0;
+    while let Some(doc) = weight_cursor.try_next().await? {
+        let day_samples = get_number_as_i64(&doc, "sample_count");
+        let avg_weight = get_number_as_f64(&doc, "avg_weight");
+        sample_count += day_samples;
+        weight_sum += avg_weight * day_samples as f64;
+        weight_trends.push(WeightTrendData {
+            date: doc.get_str("_id").unwrap_or("").to_string(),
+            avg_weight,
+            min_weight: get_number_as_f64(&doc, "min_weight"),
+            max_weight: get_number_as_f64(&doc, "max_weight"),
+            rodent_count: doc.get_array("rodent_count").map(|a| a.len() as i64).unwrap_or(0),
+        });
+    }
+
+    let weight_trend_slope = weight_trend_slope(&weight_trends);
+
+    Ok(Json(CohortStatsResponse {
+        success: true,
+        rodent_ids: payload.rodent_ids,
+        activity: CohortActivityStats {
+            total_minutes,
+            session_count,
+            avg_daily_activity: total_minutes as f64 / days_in_range as f64,
+            by_activity_type,
+        },
+        feeding: CohortFeedingStats {
+            total_grams,
+            feeding_count,
+            avg_daily_food: total_grams / days_in_range as f64,
+            consumption_rate: consumption_rate(consumed_fully_count, known_outcome_count),
+            unknown_outcome_count: feeding_count - known_outcome_count,
+            by_food_type,
+        },
+        weight: CohortWeightStats {
+            avg_weight: (sample_count > 0).then(|| weight_sum / sample_count as f64),
+            min_weight: weight_trends.iter().map(|t| t.min_weight).reduce(f64::min),
+            max_weight: weight_trends.iter().map(|t| t.max_weight).reduce(f64::max),
+            sample_count,
+            weight_trend_direction: weight_trend_direction(weight_trend_slope),
+            weight_trend_slope,
+            weight_trends,
+        },
+    }))
+}
+
+// ============== Dashboard Summary ==============
+
+pub async fn get_dashboard_summary(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<DashboardQueryParams>,
+) -> Result<Json<DashboardSummaryResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_dashboard_summary");
+
+    if !can_view_analytics(&state.config, &auth_info.role) {
+        return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
+    }
+
+    // Day boundaries follow the shelter's local calendar, not UTC
+    let today = state.config.today();
+    let today_start = state.config.start_of_day(today);
+    let week_start = state.config.start_of_day(today - Duration::days(6));
+
+    // Population summary
+    let rodents_collection = state.db.rodent_db.collection::<Document>("rodents");
+    let rodent_base = sample_filter(params.exclude_samples);
+    let with_base = |mut filter: Document| {
+        filter.extend(rodent_base.clone());
+        filter
+    };
+    let total_rodents = rodents_collection.count_documents(rodent_base.clone(), None).await? as i64;
+    let available_for_adoption = rodents_collection.count_documents(with_base(doc! { "status": "available" }), None).await? as i64;
+    let in_medical_care = rodents_collection.count_documents(with_base(doc! { "status": "medical_care" }), None).await? as i64;
+    let recent_intakes_week = rodents_collection.count_documents(with_base(doc! { "intake_date": { "$gte": week_start } }), None).await? as i64;
+
+    // Activity and feeding records are narrowed to non-sample rodents by ID
+    let rodent_ids = get_rodent_ids(&state, None, params.exclude_samples).await?;
+    let rodent_filter = build_rodent_filter(&rodent_ids).unwrap_or_default();
+    let since = |field: &str, start: DateTime<Utc>| {
+        let mut filter = doc! { field: { "$gte": start } };
+        filter.extend(rodent_filter.clone());
+        filter
+    };
+
+    // Activity summary
+    let activities = state.db.activity_db.collection::<Document>("activities");
 
     let today_activity_pipeline = vec![
-        doc! { "$match": { "recorded_at": { "$gte": today_start } } },
+        doc! { "$match": since("recorded_at", today_start) },
         doc! { "$group": { "_id": null, "total_minutes": { "$sum": "$duration_minutes" }, "rodent_ids": { "$addToSet": "$rodent_id" } } },
     ];
     let mut today_cursor = activities.aggregate(today_activity_pipeline, None).await?;
@@ -801,7 +2041,7 @@ pub async fn get_dashboard_summary(
     };
 
     let week_activity_pipeline = vec![
-        doc! { "$match": { "recorded_at": { "$gte": week_ago } } },
+        doc! { "$match": since("recorded_at", week_start) },
         doc! { "$group": { "_id": null, "total_minutes": { "$sum": "$duration_minutes" } } },
     ];
     let mut week_cursor = activities.aggregate(week_activity_pipeline, None).await?;
@@ -812,7 +2052,7 @@ pub async fn get_dashboard_summary(
     };
 
     let most_common_pipeline = vec![
-        doc! { "$match": { "recorded_at": { "$gte": week_ago } } },
+        doc! { "$match": since("recorded_at", week_start) },
         doc! { "$group": { "_id": "$activity_type", "count": { "$sum": 1 } } },
         doc! { "$sort": { "count": -1 } },
         doc! { "$limit": 1 },
@@ -828,7 +2068,7 @@ pub async fn get_dashboard_summary(
     let feeding_records = state.db.activity_db.collection::<Document>("feeding_records");
 
     let today_feeding_pipeline = vec![
-        doc! { "$match": { "meal_time": { "$gte": today_start } } },
+        doc! { "$match": since("meal_time", today_start) },
         doc! { "$group": { "_id": null, "total_grams": { "$sum": "$quantity_grams" }, "count": { "$sum": 1 } } },
     ];
     let mut today_feeding_cursor = feeding_records.aggregate(today_feeding_pipeline, None).await?;
@@ -839,7 +2079,7 @@ pub async fn get_dashboard_summary(
     };
 
     let week_feeding_pipeline = vec![
-        doc! { "$match": { "meal_time": { "$gte": week_ago } } },
+        doc! { "$match": since("meal_time", week_start) },
         doc! { "$group": { "_id": null, "total_grams": { "$sum": "$quantity_grams" }, "count": { "$sum": 1 } } },
     ];
     let mut week_feeding_cursor = feeding_records.aggregate(week_feeding_pipeline, None).await?;
@@ -883,15 +2123,16 @@ pub async fn get_weight_trends(
     Query(params): Query<AnalyticsQueryParams>,
 ) -> Result<Json<TrendDataResponse>, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_weight_trends");
 
-    if !can_view_analytics(&auth_info.role) {
+    if !can_view_analytics(&state.config, &auth_info.role) {
         return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
     }
 
-    let (from_date, to_date) = get_date_range(params.from_date, params.to_date);
+    let (from_date, to_date) = get_date_range(&state.config, params.from_date, params.to_date, params.days)?;
 
-    // Get rodent IDs filtered by species if specified
-    let rodent_ids = get_rodent_ids_by_species(&state, params.species.as_ref()).await?;
+    // Get rodent IDs filtered by species / sample flag if specified
+    let rodent_ids = get_rodent_ids(&state, params.species.as_ref(), params.exclude_samples).await?;
     
     // Build base match filter
     let mut base_match = doc! {
@@ -906,7 +2147,7 @@ pub async fn get_weight_trends(
 
     let pipeline = vec![
         doc! { "$match": base_match },
-        doc! { "$group": { "_id": { "$dateToString": { "format": "%Y-%m-%d", "date": "$date" } }, "value": { "$avg": "$weight_grams" }, "count": { "$sum": 1 } } },
+        doc! { "$group": { "_id": { "$dateToString": { "format": "%Y-%m-%d", "date": "$date", "timezone": state.config.timezone.name() } }, "value": { "$avg": "$weight_grams" }, "count": { "$sum": 1 } } },
         doc! { "$sort": { "_id": 1 } },
     ];
 
@@ -933,15 +2174,16 @@ pub async fn get_activity_trends(
     Query(params): Query<AnalyticsQueryParams>,
 ) -> Result<Json<TrendDataResponse>, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_activity_trends");
 
-    if !can_view_analytics(&auth_info.role) {
+    if !can_view_analytics(&state.config, &auth_info.role) {
         return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
     }
 
-    let (from_date, to_date) = get_date_range(params.from_date, params.to_date);
+    let (from_date, to_date) = get_date_range(&state.config, params.from_date, params.to_date, params.days)?;
 
-    // Get rodent IDs filtered by species if specified
-    let rodent_ids = get_rodent_ids_by_species(&state, params.species.as_ref()).await?;
+    // Get rodent IDs filtered by species / sample flag if specified
+    let rodent_ids = get_rodent_ids(&state, params.species.as_ref(), params.exclude_samples).await?;
     
     // Build base match filter
     let mut base_match = doc! {
@@ -955,7 +2197,7 @@ pub async fn get_activity_trends(
 
     let pipeline = vec![
         doc! { "$match": base_match },
-        doc! { "$group": { "_id": { "$dateToString": { "format": "%Y-%m-%d", "date": "$recorded_at" } }, "value": { "$sum": "$duration_minutes" }, "count": { "$sum": 1 } } },
+        doc! { "$group": { "_id": { "$dateToString": { "format": "%Y-%m-%d", "date": "$recorded_at", "timezone": state.config.timezone.name() } }, "value": { "$sum": "$duration_minutes" }, "count": { "$sum": 1 } } },
         doc! { "$sort": { "_id": 1 } },
     ];
 
@@ -982,15 +2224,16 @@ pub async fn get_feeding_trends(
     Query(params): Query<AnalyticsQueryParams>,
 ) -> Result<Json<TrendDataResponse>, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_feeding_trends");
 
-    if !can_view_analytics(&auth_info.role) {
+    if !can_view_analytics(&state.config, &auth_info.role) {
         return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
     }
 
-    let (from_date, to_date) = get_date_range(params.from_date, params.to_date);
+    let (from_date, to_date) = get_date_range(&state.config, params.from_date, params.to_date, params.days)?;
 
-    // Get rodent IDs filtered by species if specified
-    let rodent_ids = get_rodent_ids_by_species(&state, params.species.as_ref()).await?;
+    // Get rodent IDs filtered by species / sample flag if specified
+    let rodent_ids = get_rodent_ids(&state, params.species.as_ref(), params.exclude_samples).await?;
     
     // Build base match filter
     let mut base_match = doc! {
@@ -1004,7 +2247,7 @@ pub async fn get_feeding_trends(
 
     let pipeline = vec![
         doc! { "$match": base_match },
-        doc! { "$group": { "_id": { "$dateToString": { "format": "%Y-%m-%d", "date": "$meal_time" } }, "value": { "$sum": "$quantity_grams" }, "count": { "$sum": 1 } } },
+        doc! { "$group": { "_id": { "$dateToString": { "format": "%Y-%m-%d", "date": "$meal_time", "timezone": state.config.timezone.name() } }, "value": { "$sum": "$quantity_grams" }, "count": { "$sum": 1 } } },
         doc! { "$sort": { "_id": 1 } },
     ];
 
@@ -1025,29 +2268,775 @@ pub async fn get_feeding_trends(
     }))
 }
 
+// ============== Alerts ==============
+
+pub async fn list_weight_change_alerts(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<WeightChangeQueryParams>,
+) -> Result<Json<WeightChangeListResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "list_weight_change_alerts");
+
+    if !can_view_analytics(&state.config, &auth_info.role) {
+        return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
+    }
+
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = page_limit(&state.config, params.limit, 20)?;
+    let skip = (page - 1) * limit;
+
+    let mut filter = doc! {};
+    if let Some(rodent_id) = &params.rodent_id {
+        filter.insert("rodent_id", rodent_id);
+    }
+    if let Some(direction) = params.direction {
+        filter.insert(
+            "direction",
+            bson::to_bson(&direction).map_err(|_| AppError::InternalError)?,
+        );
+    }
+    let mut recorded_at = doc! {};
+    if let Some(from_date) = params.from_date {
+        recorded_at.insert("$gte", from_date);
+    }
+    if let Some(to_date) = params.to_date {
+        recorded_at.insert("$lte", to_date);
+    }
+    if !recorded_at.is_empty() {
+        filter.insert("recorded_at", recorded_at);
+    }
+
+    let collection = state
+        .db
+        .analytics_db
+        .collection::<WeightChangeEvent>("weight_change_events");
+
+    let total = collection.count_documents(filter.clone(), None).await?;
+
+    let find_options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "detected_at": -1 })
+        .skip(skip as u64)
+        .limit(limit as i64)
+        .build();
+
+    let cursor = collection.find(filter, find_options).await?;
+    let events: Vec<WeightChangeEvent> = cursor.try_collect().await?;
+
+    Ok(Json(WeightChangeListResponse {
+        success: true,
+        events: events.into_iter().map(WeightChangeEventResponse::from).collect(),
+        pagination: Pagination::new(total, page, limit),
+    }))
+}
+
+// ============== Weight Percentiles ==============
+
+/// Percentile of sorted `values` by linear interpolation between closest ranks
+fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    let last = sorted.len().checked_sub(1)?;
+    let rank = p / 100.0 * last as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    let value = sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64);
+    Some((value * 10.0).round() / 10.0)
+}
+
+/// Percentile breakpoints of each rodent's latest weight within a species
+pub async fn get_weight_percentiles(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<WeightPercentilesQueryParams>,
+) -> Result<Json<WeightPercentilesResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_weight_percentiles");
+
+    if !can_view_analytics(&state.config, &auth_info.role) {
+        return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
+    }
+
+    if params.species.trim().is_empty() {
+        return Err(AppError::ValidationError("species is required".to_string()));
+    }
+    let target = params
+        .rodent_id
+        .as_deref()
+        .map(|id| bson::oid::ObjectId::parse_str(id).map_err(|_| AppError::InvalidId))
+        .transpose()?;
+
+    let (from_date, to_date) = get_date_range(&state.config, None, None, params.days)?;
+    let rodent_ids = get_rodent_ids(&state, Some(&params.species), params.exclude_samples)
+        .await?
+        .unwrap_or_default();
+
+    // Latest weight per rodent, so frequently weighed rodents don't count more
+    let pipeline = vec![
+        doc! {
+            "$match": {
+                "rodent_id": { "$in": &rodent_ids },
+                "date": { "$gte": from_date, "$lte": to_date },
+                "weight_grams": { "$exists": true, "$ne": null }
+            }
+        },
+        doc! { "$sort": { "date": -1 } },
+        doc! { "$group": { "_id": "$rodent_id", "weight": { "$first": "$weight_grams" } } },
+    ];
+
+    let daily_records = state.db.activity_db.collection::<Document>("daily_records");
+    let mut cursor = daily_records.aggregate(pipeline, None).await?;
+    let mut weights = Vec::new();
+    let mut target_weight = None;
+    while let Some(doc) = cursor.try_next().await? {
+        let weight = get_number_as_f64(&doc, "weight");
+        if target.is_some() && doc.get_object_id("_id").ok() == target {
+            target_weight = Some(weight);
+        }
+        weights.push(weight);
+    }
+    weights.sort_by(f64::total_cmp);
+
+    let rodent = match (params.rodent_id, target_weight) {
+        (Some(rodent_id), Some(weight_grams)) => {
+            let lighter = weights.partition_point(|w| *w < weight_grams);
+            Some(RodentWeightPercentile {
+                rodent_id,
+                weight_grams,
+                percentile: (lighter as f64 / weights.len() as f64 * 1000.0).round() / 10.0,
+            })
+        }
+        // A rodent of another species or without a recent weight has no place in the distribution
+        (Some(_), None) => return Err(AppError::NotFound),
+        (None, _) => None,
+    };
+
+    Ok(Json(WeightPercentilesResponse {
+        success: true,
+        species: params.species,
+        from_date,
+        to_date,
+        rodent_count: weights.len(),
+        p10: percentile(&weights, 10.0),
+        p25: percentile(&weights, 25.0),
+        p50: percentile(&weights, 50.0),
+        p75: percentile(&weights, 75.0),
+        p90: percentile(&weights, 90.0),
+        rodent,
+    }))
+}
+
+// ============== Welfare Checks ==============
+
+pub async fn get_stale_rodents(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<StaleRodentsQueryParams>,
+) -> Result<Json<StaleRodentsResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_stale_rodents");
+
+    if !can_view_analytics(&state.config, &auth_info.role) {
+        return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
+    }
+
+    let days = params.days.unwrap_or(7).clamp(1, 365);
+    let cutoff = Utc::now() - Duration::days(days);
+
+    // Active rodents from the registry
+    let rodents_collection = state.db.rodent_db.collection::<Document>("rodents");
+    let mut active_filter = sample_filter(params.exclude_samples);
+    active_filter.insert("status", "active");
+    let cursor = rodents_collection.find(active_filter, None).await?;
+    let active_rodents: Vec<Document> = cursor.try_collect().await?;
+
+    let active_ids: Vec<bson::oid::ObjectId> = active_rodents
+        .iter()
+        .filter_map(|r| r.get_object_id("_id").ok())
+        .collect();
+
+    // Latest daily record per active rodent
+    let daily_records = state.db.activity_db.collection::<Document>("daily_records");
+    let pipeline = vec![
+        doc! { "$match": { "rodent_id": { "$in": &active_ids } } },
+        doc! { "$group": { "_id": "$rodent_id", "last_date": { "$max": "$date" } } },
+    ];
+
+    let mut cursor = daily_records.aggregate(pipeline, None).await?;
+    let mut last_dates = std::collections::HashMap::new();
+    while let Some(doc) = cursor.try_next().await? {
+        if let (Ok(id), Ok(date)) = (doc.get_object_id("_id"), doc.get_datetime("last_date")) {
+            last_dates.insert(id, date.to_chrono());
+        }
+    }
+
+    let mut rodents: Vec<StaleRodent> = active_rodents
+        .iter()
+        .filter_map(|r| {
+            let id = r.get_object_id("_id").ok()?;
+            let last_record_date = last_dates.get(&id).copied();
+
+            if last_record_date.is_some_and(|d| d >= cutoff) {
+                return None;
+            }
+
+            Some(StaleRodent {
+                id: id.to_hex(),
+                name: r.get_str("name").unwrap_or("Unknown").to_string(),
+                species: r.get_str("species").unwrap_or("unknown").to_string(),
+                last_record_date,
+            })
+        })
+        .collect();
+
+    // Never-recorded rodents first, then the longest without a record
+    rodents.sort_by_key(|r| r.last_record_date);
+
+    Ok(Json(StaleRodentsResponse {
+        success: true,
+        days,
+        total: rodents.len(),
+        rodents,
+    }))
+}
+
+/// Rodents in care whose latest check-up (or, with `any_record`, latest
+/// medical record of any type) is more than `months` old, or who have none
+pub async fn get_overdue_checkups(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<OverdueCheckupsQueryParams>,
+) -> Result<Json<OverdueCheckupsResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_overdue_checkups");
+
+    if !can_view_analytics(&state.config, &auth_info.role) {
+        return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
+    }
+
+    let months = params.months.unwrap_or(6).clamp(1, 60);
+    let cutoff = Utc::now()
+        .checked_sub_months(chrono::Months::new(months))
+        .ok_or(AppError::InternalError)?;
+
+    // Rodents still in the shelter's care
+    let rodents_collection = state.db.rodent_db.collection::<Document>("rodents");
+    let mut in_care_filter = sample_filter(params.exclude_samples);
+    in_care_filter.insert("status", doc! { "$nin": ["adopted", "deceased"] });
+    let cursor = rodents_collection.find(in_care_filter, None).await?;
+    let in_care: Vec<Document> = cursor.try_collect().await?;
+
+    let rodent_ids: Vec<bson::oid::ObjectId> = in_care
+        .iter()
+        .filter_map(|r| r.get_object_id("_id").ok())
+        .collect();
+
+    // Latest qualifying medical record per rodent
+    let mut record_match = doc! { "rodent_id": { "$in": &rodent_ids } };
+    if !params.any_record.unwrap_or(false) {
+        record_match.insert("record_type", "check_up");
+    }
+
+    let medical_records = state.db.rodent_db.collection::<Document>("medical_records");
+    let pipeline = vec![
+        doc! { "$match": record_match },
+        doc! { "$group": { "_id": "$rodent_id", "last_date": { "$max": "$date" } } },
+    ];
+
+    let mut cursor = medical_records.aggregate(pipeline, None).await?;
+    let mut last_dates = std::collections::HashMap::new();
+    while let Some(doc) = cursor.try_next().await? {
+        if let (Ok(id), Ok(date)) = (doc.get_object_id("_id"), doc.get_datetime("last_date")) {
+            last_dates.insert(id, date.to_chrono());
+        }
+    }
+
+    let mut rodents: Vec<OverdueCheckupRodent> = in_care
+        .iter()
+        .filter_map(|r| {
+            let id = r.get_object_id("_id").ok()?;
+            let last_checkup_date = last_dates.get(&id).copied();
+
+            if last_checkup_date.is_some_and(|d| d >= cutoff) {
+                return None;
+            }
+
+            Some(OverdueCheckupRodent {
+                id: id.to_hex(),
+                name: r.get_str("name").unwrap_or("Unknown").to_string(),
+                species: r.get_str("species").unwrap_or("unknown").to_string(),
+                status: r.get_str("status").unwrap_or("unknown").to_string(),
+                last_checkup_date,
+            })
+        })
+        .collect();
+
+    // Never checked first, then the longest overdue
+    rodents.sort_by_key(|r| r.last_checkup_date);
+
+    Ok(Json(OverdueCheckupsResponse {
+        success: true,
+        months,
+        total: rodents.len(),
+        rodents,
+    }))
+}
+
+/// Share of `capacity` taken by `occupants`, as a percentage with one decimal
+fn utilization_percent(occupants: i64, capacity: u32) -> f64 {
+    if capacity == 0 {
+        return if occupants > 0 { 100.0 } else { 0.0 };
+    }
+    (occupants as f64 / capacity as f64 * 1000.0).round() / 10.0
+}
+
+/// Rodents in care per enclosure against the configured capacities. Listed
+/// enclosures without occupants are included; others default to
+/// `DEFAULT_ENCLOSURE_CAPACITY`.
+pub async fn get_capacity_utilization(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<CapacityQueryParams>,
+) -> Result<Json<CapacityResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_capacity_utilization");
+
+    if !can_view_analytics(&state.config, &auth_info.role) {
+        return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
+    }
+
+    let mut in_care_filter = sample_filter(params.exclude_samples);
+    in_care_filter.insert("status", doc! { "$nin": ["adopted", "deceased"] });
+
+    let pipeline = vec![
+        doc! { "$match": in_care_filter },
+        doc! { "$group": { "_id": "$enclosure", "count": { "$sum": 1 } } },
+    ];
+
+    let rodents_collection = state.db.rodent_db.collection::<Document>("rodents");
+    let mut cursor = rodents_collection.aggregate(pipeline, None).await?;
+    let mut occupancy: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
+    let mut unassigned_rodents = 0;
+    while let Some(doc) = cursor.try_next().await? {
+        let count = get_number_as_i64(&doc, "count");
+        match doc.get_str("_id") {
+            Ok(enclosure) if !enclosure.is_empty() => *occupancy.entry(enclosure.to_string()).or_default() += count,
+            _ => unassigned_rodents += count,
+        }
+    }
+
+    for enclosure in state.config.enclosure_capacities.keys() {
+        occupancy.entry(enclosure.clone()).or_default();
+    }
+
+    let mut enclosures: Vec<EnclosureCapacity> = occupancy
+        .into_iter()
+        .map(|(enclosure, occupants)| {
+            let capacity = state
+                .config
+                .enclosure_capacities
+                .get(&enclosure)
+                .copied()
+                .unwrap_or(state.config.default_enclosure_capacity);
+            EnclosureCapacity {
+                utilization_percent: utilization_percent(occupants, capacity),
+                over_capacity: occupants > capacity as i64,
+                enclosure,
+                occupants,
+                capacity,
+            }
+        })
+        .collect();
+    enclosures.sort_by(|a, b| {
+        b.utilization_percent
+            .total_cmp(&a.utilization_percent)
+            .then_with(|| a.enclosure.cmp(&b.enclosure))
+    });
+
+    let total_rodents = enclosures.iter().map(|e| e.occupants).sum::<i64>() + unassigned_rodents;
+    let total_capacity = state
+        .config
+        .shelter_capacity
+        .unwrap_or_else(|| enclosures.iter().map(|e| e.capacity).sum());
+
+    Ok(Json(CapacityResponse {
+        success: true,
+        total_rodents,
+        unassigned_rodents,
+        total_capacity,
+        utilization_percent: utilization_percent(total_rodents, total_capacity),
+        over_capacity: total_rodents > total_capacity as i64,
+        enclosures,
+    }))
+}
+
+/// Rodents whose status changed to deceased in the last `days` days, with
+/// the reason given for the change
+pub async fn get_recent_mortality(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<RecentMortalityQueryParams>,
+) -> Result<Json<RecentMortalityResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "get_recent_mortality");
+
+    if !can_view_analytics(&state.config, &auth_info.role) {
+        return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
+    }
+
+    let days = params.days.unwrap_or(30).clamp(1, 365);
+    let cutoff = Utc::now() - Duration::days(days);
+
+    let mut filter = doc! {
+        "new_status": "deceased",
+        "changed_at": { "$gte": cutoff }
+    };
+    if let Some(filter_ids) = build_rodent_filter(&get_rodent_ids(&state, None, params.exclude_samples).await?) {
+        filter.extend(filter_ids);
+    }
+
+    let status_history = state.db.rodent_db.collection::<Document>("status_history");
+    let find_options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "changed_at": -1 })
+        .build();
+    let transitions: Vec<Document> = status_history.find(filter, find_options).await?.try_collect().await?;
+
+    let rodent_ids: Vec<bson::oid::ObjectId> = transitions
+        .iter()
+        .filter_map(|t| t.get_object_id("rodent_id").ok())
+        .collect();
+    let rodents_collection = state.db.rodent_db.collection::<Document>("rodents");
+    let rodents: Vec<Document> = rodents_collection
+        .find(doc! { "_id": { "$in": &rodent_ids } }, None)
+        .await?
+        .try_collect()
+        .await?;
+    let rodents: std::collections::HashMap<bson::oid::ObjectId, &Document> = rodents
+        .iter()
+        .filter_map(|r| Some((r.get_object_id("_id").ok()?, r)))
+        .collect();
+
+    let deaths: Vec<RecentDeath> = transitions
+        .iter()
+        .filter_map(|t| {
+            let rodent_id = t.get_object_id("rodent_id").ok()?;
+            let rodent = rodents.get(&rodent_id);
+            Some(RecentDeath {
+                rodent_id: rodent_id.to_hex(),
+                name: rodent.and_then(|r| r.get_str("name").ok()).unwrap_or("Unknown").to_string(),
+                species: rodent.and_then(|r| r.get_str("species").ok()).unwrap_or("unknown").to_string(),
+                previous_status: t.get_str("old_status").unwrap_or("unknown").to_string(),
+                reason: t.get_str("reason").ok().map(str::to_string),
+                recorded_by: t.get_str("changed_by_name").unwrap_or("Unknown").to_string(),
+                died_at: t.get_datetime("changed_at").ok()?.to_chrono(),
+            })
+        })
+        .collect();
+
+    Ok(Json(RecentMortalityResponse {
+        success: true,
+        days,
+        total: deaths.len(),
+        deaths,
+    }))
+}
+
+// ============== Event Log Stats ==============
+
+/// Count stored event logs by event type and processed status
+pub async fn get_event_log_stats(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<EventLogStatsResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    require_admin(&auth_info)?;
+    let _timer = QueryTimer::start(&state.config, "get_event_log_stats");
+
+    let pipeline = vec![
+        doc! {
+            "$group": {
+                "_id": "$event_type",
+                "processed": { "$sum": { "$cond": [{ "$eq": ["$processed", true] }, 1, 0] } },
+                "unprocessed": { "$sum": { "$cond": [{ "$eq": ["$processed", true] }, 0, 1] } }
+            }
+        },
+        doc! { "$sort": { "_id": 1 } },
+    ];
+
+    let by_event_type: Vec<EventLogTypeCount> = state
+        .db
+        .analytics_db
+        .collection::<Document>("event_logs")
+        .aggregate(pipeline, None)
+        .await?
+        .try_collect::<Vec<Document>>()
+        .await?
+        .iter()
+        .map(|doc| EventLogTypeCount {
+            event_type: doc.get_str("_id").unwrap_or("unknown").to_string(),
+            processed: get_number_as_i64(doc, "processed"),
+            unprocessed: get_number_as_i64(doc, "unprocessed"),
+        })
+        .collect();
+
+    let total = by_event_type
+        .iter()
+        .map(|entry| entry.processed + entry.unprocessed)
+        .sum();
+
+    Ok(Json(EventLogStatsResponse {
+        success: true,
+        total,
+        retention_days: state.config.event_log_retention_days,
+        by_event_type,
+    }))
+}
+
+// ============== Webhooks ==============
+
+fn require_admin(auth_info: &AuthInfo) -> Result<(), AppError> {
+    if auth_info.role != "admin" {
+        return Err(AppError::AccessDenied("Admin access required".to_string()));
+    }
+    Ok(())
+}
+
+/// Webhook targets must be absolute http(s) URLs with a host
+fn validate_webhook_url(url: &str) -> Result<(), AppError> {
+    let invalid = || AppError::ValidationError("url must be an http(s) URL with a host".to_string());
+    let parsed = reqwest::Url::parse(url).map_err(|_| invalid())?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none_or(str::is_empty) {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+/// Register a URL to receive signed POSTs for the given event types
+pub async fn create_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<WebhookCreatedResponse>), AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    require_admin(&auth_info)?;
+
+    validate_webhook_url(&payload.url)?;
+    if payload.event_types.is_empty() {
+        return Err(AppError::ValidationError("event_types must not be empty".to_string()));
+    }
+    if let Some(unknown) = payload
+        .event_types
+        .iter()
+        .find(|t| !WEBHOOK_EVENT_TYPES.contains(&t.as_str()))
+    {
+        return Err(AppError::ValidationError(format!("Unknown event type: {}", unknown)));
+    }
+
+    let secret = match payload.secret {
+        Some(secret) if secret.len() < 16 => {
+            return Err(AppError::ValidationError(
+                "secret must be at least 16 characters".to_string(),
+            ));
+        }
+        Some(secret) => secret,
+        None => uuid::Uuid::new_v4().simple().to_string(),
+    };
+
+    let mut webhook = Webhook {
+        id: None,
+        url: payload.url,
+        encrypted_secret: SecretCipher::new(&state.config.webhook_secret_key).encrypt(&secret),
+        event_types: payload.event_types,
+        active: true,
+        created_by: auth_info.user_id.clone(),
+        created_at: Utc::now(),
+    };
+
+    let result = state
+        .db
+        .db
+        .collection::<Webhook>("webhooks")
+        .insert_one(&webhook, None)
+        .await?;
+    webhook.id = result.inserted_id.as_object_id();
+
+    tracing::info!(
+        "Webhook {} registered by {} for {:?}",
+        webhook.url, auth_info.username, webhook.event_types
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(WebhookCreatedResponse {
+            success: true,
+            webhook: WebhookResponse::from(webhook),
+            secret,
+        }),
+    ))
+}
+
+pub async fn list_webhooks(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<WebhookListResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    require_admin(&auth_info)?;
+
+    let cursor = state
+        .db
+        .db
+        .collection::<Webhook>("webhooks")
+        .find(doc! {}, None)
+        .await?;
+    let webhooks: Vec<Webhook> = cursor.try_collect().await?;
+
+    Ok(Json(WebhookListResponse {
+        success: true,
+        webhooks: webhooks.into_iter().map(WebhookResponse::from).collect(),
+    }))
+}
+
+pub async fn delete_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<MessageResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    require_admin(&auth_info)?;
+
+    let object_id = bson::oid::ObjectId::parse_str(&id).map_err(|_| AppError::InvalidId)?;
+
+    let result = state
+        .db
+        .db
+        .collection::<Webhook>("webhooks")
+        .delete_one(doc! { "_id": object_id }, None)
+        .await?;
+
+    if result.deleted_count == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(Json(MessageResponse {
+        success: true,
+        message: "Webhook deleted".to_string(),
+    }))
+}
+
+// ============== Recompute ==============
+
+/// Rebuild event-derived analytics for a date range from the source
+/// collections. Short ranges are rebuilt inline; longer ones run in the
+/// background and are answered with 202 and a job to poll.
+pub async fn recompute_analytics(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<RecomputeRequest>,
+) -> Result<(StatusCode, Json<RecomputeJobResponse>), AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    require_admin(&auth_info)?;
+
+    if payload.from_date > payload.to_date {
+        return Err(AppError::InvalidDateRange);
+    }
+
+    let mut job = RecomputeJob {
+        id: None,
+        from_date: payload.from_date,
+        to_date: payload.to_date,
+        status: RecomputeStatus::Running,
+        requested_by: auth_info.user_id.clone(),
+        started_at: Utc::now(),
+        finished_at: None,
+        summary: None,
+        error: None,
+    };
+
+    let result = recompute::jobs(&state.db).insert_one(&job, None).await?;
+    job.id = result.inserted_id.as_object_id();
+
+    if (job.to_date - job.from_date).num_days() > recompute::INLINE_RECOMPUTE_MAX_DAYS {
+        let background_state = state.clone();
+        let background_job = job.clone();
+        tokio::spawn(async move {
+            recompute::run_job(&background_state.db, background_job, background_state.config.timezone).await;
+        });
+
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(RecomputeJobResponse {
+                success: true,
+                job: RecomputeJobView::from(job),
+            }),
+        ));
+    }
+
+    let job = recompute::run_job(&state.db, job, state.config.timezone).await;
+    if let Some(error) = job.error {
+        return Err(AppError::DatabaseError(error));
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(RecomputeJobResponse {
+            success: true,
+            job: RecomputeJobView::from(job),
+        }),
+    ))
+}
+
+/// Progress and outcome of a recompute job
+pub async fn get_recompute_job(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<RecomputeJobResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    require_admin(&auth_info)?;
+
+    let object_id = bson::oid::ObjectId::parse_str(&id).map_err(|_| AppError::InvalidId)?;
+
+    let job = recompute::jobs(&state.db)
+        .find_one(doc! { "_id": object_id }, None)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(RecomputeJobResponse {
+        success: true,
+        job: RecomputeJobView::from(job),
+    }))
+}
+
 // ============== Export Handlers ==============
 
+/// One CSV line, with columns separated to suit the export number format
+fn csv_line(fields: &[&str], format: ExportNumberFormat) -> String {
+    let mut line = fields.join(format.separator());
+    line.push('\n');
+    line
+}
+
 pub async fn export_population_csv(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Query(params): Query<ExportQueryParams>,
 ) -> Result<String, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "export_population_csv");
 
-    if !can_view_analytics(&auth_info.role) {
+    if !can_view_analytics(&state.config, &auth_info.role) {
         return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
     }
 
     let rodents_collection = state.db.rodent_db.collection::<Document>("rodents");
 
-    let mut filter = doc! {};
+    let mut filter = sample_filter(params.exclude_samples);
     if let Some(species) = &params.species {
         filter.insert("species", species);
     }
 
     let mut cursor = rodents_collection.find(filter, None).await?;
 
-    let mut csv = String::from("id,name,species,gender,age_months,status,intake_date\n");
+    let format = state.config.export_number_format;
+    let mut csv = csv_line(&["id", "name", "species", "gender", "age_months", "status", "intake_date"], format);
 
     while let Some(doc) = cursor.try_next().await? {
         let id = doc.get_object_id("_id").map(|id| id.to_hex()).unwrap_or_default();
@@ -1060,7 +3049,10 @@ pub async fn export_population_csv(
             .map(|dt| dt.to_chrono().format("%Y-%m-%d").to_string())
             .unwrap_or_default();
 
-        csv.push_str(&format!("{},{},{},{},{},{},{}\n", id, name, species, gender, age_months, status, intake_date));
+        csv.push_str(&csv_line(
+            &[&id, name, species, gender, &format.number(age_months), status, &intake_date],
+            format,
+        ));
     }
 
     Ok(csv)
@@ -1072,20 +3064,29 @@ pub async fn export_activity_csv(
     Query(params): Query<ExportQueryParams>,
 ) -> Result<String, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "export_activity_csv");
 
-    if !can_view_analytics(&auth_info.role) {
+    if !can_view_analytics(&state.config, &auth_info.role) {
         return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
     }
 
-    let (from_date, to_date) = get_date_range(params.from_date, params.to_date);
+    let (from_date, to_date) = get_date_range(&state.config, params.from_date, params.to_date, params.days)?;
 
     let activities = state.db.activity_db.collection::<Document>("activities");
 
-    let filter = doc! { "recorded_at": { "$gte": from_date, "$lte": to_date } };
+    let mut filter = doc! { "recorded_at": { "$gte": from_date, "$lte": to_date } };
+    let rodent_ids = get_rodent_ids(&state, None, params.exclude_samples).await?;
+    if let Some(rodent_filter) = build_rodent_filter(&rodent_ids) {
+        filter.extend(rodent_filter);
+    }
 
     let mut cursor = activities.find(filter, None).await?;
 
-    let mut csv = String::from("id,rodent_id,activity_type,duration_minutes,recorded_at,recorded_by_name\n");
+    let format = state.config.export_number_format;
+    let mut csv = csv_line(
+        &["id", "rodent_id", "activity_type", "duration_minutes", "recorded_at", "recorded_by_name", "assisted_by"],
+        format,
+    );
 
     while let Some(doc) = cursor.try_next().await? {
         let id = doc.get_object_id("_id").map(|id| id.to_hex()).unwrap_or_default();
@@ -1096,33 +3097,113 @@ pub async fn export_activity_csv(
             .map(|dt| dt.to_chrono().format("%Y-%m-%d %H:%M:%S").to_string())
             .unwrap_or_default();
         let recorded_by_name = doc.get_str("recorded_by_name").unwrap_or("");
+        // Assistant names share one quoted column, separated by semicolons
+        let assisted_by = doc
+            .get_array("assisted_by")
+            .map(|assistants| {
+                assistants
+                    .iter()
+                    .filter_map(|a| a.as_document()?.get_str("name").ok())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            })
+            .unwrap_or_default();
 
-        csv.push_str(&format!("{},{},{},{},{},{}\n", id, rodent_id, activity_type, duration_minutes, recorded_at, recorded_by_name));
+        csv.push_str(&csv_line(
+            &[
+                &id,
+                &rodent_id,
+                activity_type,
+                &format.number(duration_minutes),
+                &recorded_at,
+                recorded_by_name,
+                &format!("\"{}\"", assisted_by.replace('"', "\"\"")),
+            ],
+            format,
+        ));
     }
 
     Ok(csv)
 }
 
+/// Stream activity records as newline-delimited JSON, one record per line
+pub async fn export_activities_ndjson(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<AnalyticsQueryParams>,
+) -> Result<Response, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+
+    if !can_view_analytics(&state.config, &auth_info.role) {
+        return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
+    }
+
+    let (from_date, to_date) = get_date_range(&state.config, params.from_date, params.to_date, params.days)?;
+
+    // Get rodent IDs filtered by species / sample flag if specified
+    let rodent_ids = get_rodent_ids(&state, params.species.as_ref(), params.exclude_samples).await?;
+
+    let mut filter = doc! { "recorded_at": { "$gte": from_date, "$lte": to_date } };
+    if let Some(ref rodent_filter) = build_rodent_filter(&rodent_ids) {
+        filter.extend(rodent_filter.clone());
+    }
+
+    let activities = state.db.activity_db.collection::<Document>("activities");
+    let cursor = activities.find(filter, None).await?;
+
+    // Serialize each document as it comes off the cursor instead of buffering the export
+    let lines = cursor.map(|result| {
+        result.map(|doc| {
+            let record = serde_json::json!({
+                "id": doc.get_object_id("_id").map(|id| id.to_hex()).unwrap_or_default(),
+                "rodent_id": doc.get_object_id("rodent_id").map(|id| id.to_hex()).unwrap_or_default(),
+                "activity_type": doc.get_str("activity_type").unwrap_or(""),
+                "duration_minutes": get_number_as_i64(&doc, "duration_minutes"),
+                "notes": doc.get_str("notes").ok(),
+                "recorded_at": doc.get_datetime("recorded_at").ok().map(|dt| dt.to_chrono().to_rfc3339()),
+                "recorded_by": doc.get_str("recorded_by").unwrap_or(""),
+                "recorded_by_name": doc.get_str("recorded_by_name").unwrap_or(""),
+            });
+            format!("{}\n", record)
+        })
+    });
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+        .into_response())
+}
+
 pub async fn export_feeding_csv(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Query(params): Query<ExportQueryParams>,
 ) -> Result<String, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "export_feeding_csv");
 
-    if !can_view_analytics(&auth_info.role) {
+    if !can_view_analytics(&state.config, &auth_info.role) {
         return Err(AppError::AccessDenied("Insufficient permissions".to_string()));
     }
 
-    let (from_date, to_date) = get_date_range(params.from_date, params.to_date);
+    let (from_date, to_date) = get_date_range(&state.config, params.from_date, params.to_date, params.days)?;
 
     let feeding_records = state.db.activity_db.collection::<Document>("feeding_records");
 
-    let filter = doc! { "meal_time": { "$gte": from_date, "$lte": to_date } };
+    let mut filter = doc! { "meal_time": { "$gte": from_date, "$lte": to_date } };
+    let rodent_ids = get_rodent_ids(&state, None, params.exclude_samples).await?;
+    if let Some(rodent_filter) = build_rodent_filter(&rodent_ids) {
+        filter.extend(rodent_filter);
+    }
 
     let mut cursor = feeding_records.find(filter, None).await?;
 
-    let mut csv = String::from("id,rodent_id,food_type,quantity_grams,meal_time,consumed_fully,recorded_by_name\n");
+    let format = state.config.export_number_format;
+    let mut csv = csv_line(
+        &["id", "rodent_id", "food_type", "quantity_grams", "meal_time", "consumed_fully", "recorded_by_name"],
+        format,
+    );
 
     while let Some(doc) = cursor.try_next().await? {
         let id = doc.get_object_id("_id").map(|id| id.to_hex()).unwrap_or_default();
@@ -1132,10 +3213,22 @@ pub async fn export_feeding_csv(
         let meal_time = doc.get_datetime("meal_time")
             .map(|dt| dt.to_chrono().format("%Y-%m-%d %H:%M:%S").to_string())
             .unwrap_or_default();
-        let consumed_fully = doc.get_bool("consumed_fully").unwrap_or(false);
+        // Leave the column empty when the outcome was never recorded
+        let consumed_fully = doc.get_bool("consumed_fully").map(|b| b.to_string()).unwrap_or_default();
         let recorded_by_name = doc.get_str("recorded_by_name").unwrap_or("");
 
-        csv.push_str(&format!("{},{},{},{},{},{},{}\n", id, rodent_id, food_type, quantity_grams, meal_time, consumed_fully, recorded_by_name));
+        csv.push_str(&csv_line(
+            &[
+                &id,
+                &rodent_id,
+                food_type,
+                &format.number(quantity_grams),
+                &meal_time,
+                &consumed_fully,
+                recorded_by_name,
+            ],
+            format,
+        ));
     }
 
     Ok(csv)
@@ -1149,3 +3242,134 @@ pub async fn health_check() -> Json<MessageResponse> {
         message: "Analytics Service is healthy".to_string(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn tz_offset_accepts_signed_hours_and_minutes() {
+        assert_eq!(parse_tz_offset(None).unwrap(), None);
+        assert_eq!(parse_tz_offset(Some("+02:00")).unwrap().as_deref(), Some("+02:00"));
+        assert_eq!(parse_tz_offset(Some("-09:30")).unwrap().as_deref(), Some("-09:30"));
+        assert_eq!(parse_tz_offset(Some("+14:00")).unwrap().as_deref(), Some("+14:00"));
+    }
+
+    #[test]
+    fn tz_offset_rejects_malformed_input() {
+        for offset in ["é1:00", "+01:+5", "++1:00", "+1:000", "+15:00", "+01:60", "01:000", "+01-00", ""] {
+            assert!(parse_tz_offset(Some(offset)).is_err(), "{} should be rejected", offset);
+        }
+    }
+
+    #[test]
+    fn known_outcomes_count_only_boolean_consumed_fully() {
+        // null and missing values have other BSON types, so they drop out of the denominator
+        assert_eq!(
+            known_outcome_sum(),
+            doc! { "$sum": { "$cond": [{ "$eq": [{ "$type": "$consumed_fully" }, "bool"] }, 1, 0] } }
+        );
+        assert_eq!(
+            consumed_fully_sum(),
+            doc! { "$sum": { "$cond": [{ "$eq": ["$consumed_fully", true] }, 1, 0] } }
+        );
+    }
+
+    #[test]
+    fn consumption_rate_is_share_of_known_outcomes() {
+        // 2 eaten, 1 left over, any number of unrecorded meals
+        assert!((consumption_rate(2, 3) - 200.0 / 3.0).abs() < 1e-9);
+        assert_eq!(consumption_rate(4, 4), 100.0);
+    }
+
+    #[test]
+    fn consumption_rate_without_known_outcomes_is_zero() {
+        let rate = consumption_rate(0, 0);
+        assert!(!rate.is_nan());
+        assert_eq!(rate, 0.0);
+    }
+
+    fn counts(groups: &[AgeGroupCount]) -> Vec<(&str, i64)> {
+        groups.iter().map(|g| (g.age_group.as_str(), g.count)).collect()
+    }
+
+    #[test]
+    fn age_groups_include_rodents_without_date_of_birth() {
+        // 3-6 months and 1-2 years have no rodents, so $bucket emits nothing for them
+        let buckets = vec![
+            doc! { "_id": 0, "count": 2 },
+            doc! { "_id": 6, "count": 1 },
+            doc! { "_id": UNKNOWN_AGE_GROUP, "count": 3 },
+            doc! { "_id": 24, "count": 4_i64 },
+        ];
+
+        assert_eq!(
+            counts(&age_group_counts(&buckets)),
+            vec![
+                ("0-3 months", 2),
+                ("3-6 months", 0),
+                ("6-12 months", 1),
+                ("1-2 years", 0),
+                ("2+ years", 4),
+                ("unknown", 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn age_groups_without_buckets_are_all_zero() {
+        let groups = age_group_counts(&[]);
+        assert_eq!(groups.len(), AGE_BUCKETS.len() + 1);
+        assert!(groups.iter().all(|g| g.count == 0));
+    }
+
+    #[test]
+    fn webhook_urls_need_an_http_scheme_and_a_host() {
+        assert!(validate_webhook_url("https://hooks.example.com/rodent-care").is_ok());
+        assert!(validate_webhook_url("http://10.0.0.5:8080/events").is_ok());
+
+        for url in ["https://", "ftp://example.com", "mailto:ops@example.com", "example.com", ""] {
+            assert!(validate_webhook_url(url).is_err(), "{} should be rejected", url);
+        }
+    }
+
+    fn record_count(name: &str, last_recorded_at: DateTime<Utc>, count: i64) -> UserRecordCount {
+        UserRecordCount {
+            user_id: "u1".to_string(),
+            name: name.to_string(),
+            last_recorded_at: Some(last_recorded_at),
+            count,
+        }
+    }
+
+    #[test]
+    fn staff_name_comes_from_the_most_recent_record() {
+        let earlier = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let later = Utc.with_ymd_and_hms(2024, 3, 20, 9, 0, 0).unwrap();
+
+        let mut by_user = StaffByUser::new();
+        merge_staff_counts(&mut by_user, vec![record_count("Ana Novak", later, 4)], |s, c| s.activities = c);
+        merge_staff_counts(&mut by_user, vec![record_count("Ana Petrović", earlier, 2)], |s, c| s.feeding_records = c);
+
+        let (staff, _) = &by_user["u1"];
+        assert_eq!(staff.name, "Ana Novak");
+        assert_eq!((staff.activities, staff.feeding_records), (4, 2));
+    }
+
+    #[test]
+    fn correlation_reports_why_it_cannot_be_computed() {
+        assert_eq!(pearson_correlation(&[(30.0, 20.0)]), Err(CorrelationSkipReason::TooFewDays));
+        assert_eq!(
+            pearson_correlation(&[(0.0, 20.0), (0.0, 25.0), (0.0, 18.0)]),
+            Err(CorrelationSkipReason::ConstantActivity)
+        );
+        assert_eq!(
+            pearson_correlation(&[(30.0, 20.0), (45.0, 20.0), (10.0, 20.0)]),
+            Err(CorrelationSkipReason::ConstantFeeding)
+        );
+
+        let correlation = pearson_correlation(&[(10.0, 5.0), (20.0, 10.0), (30.0, 15.0)]).unwrap();
+        assert!((correlation - 1.0).abs() < 1e-9);
+    }
+}
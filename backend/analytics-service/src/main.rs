@@ -1,8 +1,8 @@
-use axum::Router;
+use axum::{extract::DefaultBodyLimit, Router};
 use std::sync::Arc;
 use tokio::net::TcpListener;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
-use tracing::info;
+use tower_http::{compression::CompressionLayer, cors::CorsLayer, trace::TraceLayer};
+use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 mod config;
@@ -12,7 +12,12 @@ mod events;
 mod handlers;
 mod messaging;
 mod models;
+mod recompute;
+mod retention;
 mod routes;
+mod snapshots;
+mod timing;
+mod webhooks;
 
 use config::Config;
 use db::MongoDB;
@@ -27,13 +32,18 @@ async fn main() {
     // Load environment variables
     dotenvy::dotenv().ok();
 
-    // Initialize tracing
+    // Initialize tracing (LOG_FORMAT=json for machine-readable output)
+    let json_logs = std::env::var("LOG_FORMAT")
+        .map(|format| format.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "analytics_service=debug,tower_http=debug".into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with(json_logs.then(|| tracing_subscriber::fmt::layer().json()))
+        .with((!json_logs).then(tracing_subscriber::fmt::layer))
         .init();
 
     // Load configuration
@@ -50,11 +60,32 @@ async fn main() {
         .await
         .expect("Failed to create database indexes");
 
+    // Recompute jobs still marked running were cut short by the last shutdown
+    match recompute::fail_interrupted_jobs(&db).await {
+        Ok(0) => {}
+        Ok(count) => warn!("Marked {} interrupted recompute job(s) as failed", count),
+        Err(e) => error!("Failed to mark interrupted recompute jobs as failed: {}", e),
+    }
+
     // Create shared DB for the consumer
     let db_arc = Arc::new(db.clone());
 
+    // Month-end population snapshots
+    snapshots::spawn_monthly_snapshots(db_arc.clone(), config.timezone);
+
+    // Drop old processed event logs so the collection doesn't grow forever
+    retention::spawn_event_log_purge(db_arc.clone(), config.event_log_retention_days);
+
+    // Outbound webhook delivery for consumed events
+    let webhook_dispatcher = webhooks::WebhookDispatcher::new(
+        db_arc.clone(),
+        webhooks::SecretCipher::new(&config.webhook_secret_key),
+        config.webhook_timeout_secs,
+        config.webhook_max_attempts,
+    );
+
     // Start RabbitMQ event consumer in background
-    messaging::spawn_consumer(config.rabbitmq_url.clone(), db_arc);
+    messaging::spawn_consumer(&config, db_arc, webhook_dispatcher);
 
     // Create application state
     let state = Arc::new(AppState {
@@ -65,6 +96,9 @@ async fn main() {
     // Build router with middleware
     let app = Router::new()
         .nest("/api", routes::create_routes())
+        .layer(DefaultBodyLimit::max(config.max_body_size_mb * 1024 * 1024))
+        .layer(axum::middleware::map_response(error::json_payload_too_large))
+        .layer(CompressionLayer::new())
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
         .with_state(state);
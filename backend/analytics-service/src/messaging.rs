@@ -1,31 +1,110 @@
+use bson::doc;
 use futures::StreamExt;
 use lapin::{
-    options::{BasicAckOptions, BasicConsumeOptions, ExchangeDeclareOptions, QueueBindOptions, QueueDeclareOptions},
+    options::{
+        BasicAckOptions, BasicConsumeOptions, BasicNackOptions, BasicPublishOptions, BasicQosOptions,
+        ExchangeDeclareOptions, QueueBindOptions, QueueDeclareOptions,
+    },
+    message::Delivery,
     types::FieldTable,
-    Connection, ConnectionProperties, ExchangeKind,
+    Channel, Connection, ConnectionProperties, ExchangeKind,
+};
+use mongodb::{
+    options::{FindOneAndUpdateOptions, ReturnDocument},
+    Collection,
 };
-use mongodb::Collection;
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
+use crate::config::Config;
 use crate::db::MongoDB;
+use crate::models::{Anomaly, RodentWeightStats, WeightChangeDirection, WeightChangeEvent};
+use crate::webhooks::WebhookDispatcher;
 use crate::events::{
     AnalyticsEventLog, DailyMetricsRecordedEvent, FeedingRecordedEvent, GenericEvent,
     MedicalTreatmentAddedEvent, RodentRegisteredEvent, RodentStatusChangedEvent,
-    ACTIVITY_ROUTING_PATTERN, ANALYTICS_QUEUE, EXCHANGE_NAME, REGISTRY_ROUTING_PATTERN,
+    ACTIVITY_ROUTING_PATTERN, ANALYTICS_DEAD_LETTER_QUEUE, ANALYTICS_QUEUE, EVENT_SCHEMA_VERSION, EXCHANGE_NAME,
+    REGISTRY_ROUTING_PATTERN,
 };
 
+/// Why an event couldn't be processed
+enum ProcessError {
+    /// Published with a schema version this consumer doesn't understand yet
+    UnsupportedSchemaVersion(u32),
+    Failed(String),
+}
+
+impl From<String> for ProcessError {
+    fn from(message: String) -> Self {
+        ProcessError::Failed(message)
+    }
+}
+
+/// Park an event in the dead-letter queue as received, for replay once it's supported
+async fn dead_letter(channel: &Channel, delivery: &Delivery) -> Result<(), lapin::Error> {
+    channel
+        .basic_publish(
+            "",
+            ANALYTICS_DEAD_LETTER_QUEUE,
+            BasicPublishOptions::default(),
+            &delivery.data,
+            delivery.properties.clone(),
+        )
+        .await
+        .map(|_| ())
+}
+
+/// Record an event that wasn't processed, so it shows up in event log stats
+/// and survives the retention purge. Best effort: the event is acked regardless.
+async fn log_unprocessed_event(
+    collection: &Collection<AnalyticsEventLog>,
+    routing_key: &str,
+    data: &[u8],
+    reason: String,
+) {
+    let payload_str = String::from_utf8_lossy(data);
+    let generic = serde_json::from_str::<GenericEvent>(&payload_str).ok();
+    let event_log = AnalyticsEventLog {
+        id: None,
+        event_type: generic
+            .as_ref()
+            .map(|g| g.event_type.clone())
+            .unwrap_or_else(|| "unknown".to_string()),
+        event_id: generic.map(|g| g.event_id).unwrap_or_default(),
+        routing_key: routing_key.to_string(),
+        payload: serde_json::from_str(&payload_str)
+            .unwrap_or_else(|_| serde_json::Value::String(payload_str.to_string())),
+        received_at: chrono::Utc::now(),
+        processed: false,
+        failure_reason: Some(reason),
+    };
+
+    if let Err(e) = collection.insert_one(event_log, None).await {
+        error!("Failed to log unprocessed event: {}", e);
+    }
+}
+
 /// RabbitMQ event consumer for analytics
 pub struct EventConsumer {
     rabbitmq_url: String,
     db: Arc<MongoDB>,
+    anomaly_weight_threshold_percent: f64,
+    weight_change_threshold_percent: f64,
+    prefetch_count: u16,
+    concurrency: usize,
+    webhooks: WebhookDispatcher,
 }
 
 impl EventConsumer {
-    pub fn new(rabbitmq_url: &str, db: Arc<MongoDB>) -> Self {
+    pub fn new(config: &Config, db: Arc<MongoDB>, webhooks: WebhookDispatcher) -> Self {
         Self {
-            rabbitmq_url: rabbitmq_url.to_string(),
+            rabbitmq_url: config.rabbitmq_url.clone(),
             db,
+            anomaly_weight_threshold_percent: config.anomaly_weight_threshold_percent,
+            weight_change_threshold_percent: config.weight_change_threshold_percent,
+            prefetch_count: config.consumer_prefetch_count,
+            concurrency: config.consumer_concurrency.max(1),
+            webhooks,
         }
     }
 
@@ -60,6 +139,18 @@ impl EventConsumer {
             )
             .await?;
 
+        // Declare the queue unknown schema versions are parked in
+        channel
+            .queue_declare(
+                ANALYTICS_DEAD_LETTER_QUEUE,
+                QueueDeclareOptions {
+                    durable: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await?;
+
         // Bind queue to activity events
         channel
             .queue_bind(
@@ -84,7 +175,12 @@ impl EventConsumer {
 
         info!("Analytics queue bound to exchange. Starting consumer...");
 
-        let mut consumer = channel
+        // Cap unacked deliveries so a backlog is pulled in batches, not all at once
+        channel
+            .basic_qos(self.prefetch_count, BasicQosOptions::default())
+            .await?;
+
+        let consumer = channel
             .basic_consume(
                 ANALYTICS_QUEUE,
                 "analytics_consumer",
@@ -95,35 +191,68 @@ impl EventConsumer {
 
         let collection: Collection<AnalyticsEventLog> = self.db.analytics_db.collection("event_logs");
 
-        info!("Event consumer started. Waiting for messages...");
+        info!(
+            "Event consumer started (prefetch {}, concurrency {}). Waiting for messages...",
+            self.prefetch_count, self.concurrency
+        );
 
-        while let Some(delivery_result) = consumer.next().await {
-            match delivery_result {
-                Ok(delivery) => {
-                    let routing_key = delivery.routing_key.to_string();
-                    let data = &delivery.data;
+        consumer
+            .for_each_concurrent(self.concurrency, |delivery_result| async {
+                match delivery_result {
+                    Ok(delivery) => {
+                        let routing_key = delivery.routing_key.to_string();
 
-                    match self.process_event(&routing_key, data, &collection).await {
-                        Ok(_) => {
-                            if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
-                                error!("Failed to ack message: {}", e);
+                        match self.process_event(&routing_key, &delivery.data, &collection).await {
+                            Ok(_) => {
+                                if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
+                                    error!("Failed to ack message: {}", e);
+                                }
                             }
-                        }
-                        Err(e) => {
-                            error!("Failed to process event: {}", e);
-                            // Still ack to prevent infinite redelivery
-                            // In production, you might want to use dead-letter queues
-                            if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
-                                error!("Failed to ack message after error: {}", e);
+                            Err(ProcessError::UnsupportedSchemaVersion(version)) => {
+                                warn!(
+                                    "Dead-lettering event with unsupported schema version {} (routing_key={})",
+                                    version, routing_key
+                                );
+                                if let Err(e) = dead_letter(&channel, &delivery).await {
+                                    // Requeue rather than ack, so the event isn't lost
+                                    error!("Failed to dead-letter event, requeueing: {}", e);
+                                    let requeue = BasicNackOptions {
+                                        requeue: true,
+                                        ..BasicNackOptions::default()
+                                    };
+                                    if let Err(e) = delivery.nack(requeue).await {
+                                        error!("Failed to nack message: {}", e);
+                                    }
+                                    return;
+                                }
+                                log_unprocessed_event(
+                                    &collection,
+                                    &routing_key,
+                                    &delivery.data,
+                                    format!("Dead-lettered: unsupported schema version {}", version),
+                                )
+                                .await;
+                                if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
+                                    error!("Failed to ack message: {}", e);
+                                }
+                            }
+                            Err(ProcessError::Failed(e)) => {
+                                error!("Failed to process event: {}", e);
+                                log_unprocessed_event(&collection, &routing_key, &delivery.data, e).await;
+                                // Still ack to prevent infinite redelivery
+                                // In production, you might want to use dead-letter queues
+                                if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
+                                    error!("Failed to ack message after error: {}", e);
+                                }
                             }
                         }
                     }
+                    Err(e) => {
+                        error!("Error receiving message: {}", e);
+                    }
                 }
-                Err(e) => {
-                    error!("Error receiving message: {}", e);
-                }
-            }
-        }
+            })
+            .await;
 
         Ok(())
     }
@@ -133,7 +262,7 @@ impl EventConsumer {
         routing_key: &str,
         data: &[u8],
         collection: &Collection<AnalyticsEventLog>,
-    ) -> Result<(), String> {
+    ) -> Result<(), ProcessError> {
         let payload_str = String::from_utf8(data.to_vec()).map_err(|e| e.to_string())?;
 
         // First, parse as generic event to get type
@@ -145,6 +274,23 @@ impl EventConsumer {
             generic.event_type, generic.event_id, routing_key
         );
 
+        // Newer payload layouts can't be read safely with this version's structs
+        if !(1..=EVENT_SCHEMA_VERSION).contains(&generic.schema_version) {
+            return Err(ProcessError::UnsupportedSchemaVersion(generic.schema_version));
+        }
+
+        // Redelivered events were already counted; processing them again would
+        // double up anomalies, weight stats and webhook deliveries
+        let already_processed = collection
+            .find_one(doc! { "event_id": &generic.event_id, "processed": true }, None)
+            .await
+            .map_err(|e| format!("Failed to check event log: {}", e))?
+            .is_some();
+        if already_processed {
+            info!("Skipping already processed event {}", generic.event_id);
+            return Ok(());
+        }
+
         // Process based on event type
         match generic.event_type.as_str() {
             "DailyMetricsRecorded" => {
@@ -177,6 +323,11 @@ impl EventConsumer {
             }
         }
 
+        // Forward to external subscribers; a webhook failure shouldn't fail the event
+        if let Err(e) = self.webhooks.dispatch(&generic.event_type, &payload_str).await {
+            warn!("Failed to dispatch webhooks for {}: {}", generic.event_type, e);
+        }
+
         // Log the event
         let event_log = AnalyticsEventLog {
             id: None,
@@ -186,6 +337,7 @@ impl EventConsumer {
             payload: serde_json::from_str(&payload_str).unwrap_or_default(),
             received_at: chrono::Utc::now(),
             processed: true,
+            failure_reason: None,
         };
 
         collection
@@ -201,8 +353,163 @@ impl EventConsumer {
             "Processing DailyMetricsRecorded: rodent={}, weight={:?}, energy={:?}",
             event.payload.rodent_id, event.payload.weight_grams, event.payload.energy_level
         );
-        // Analytics processing can be added here
-        // For example: update running averages, detect anomalies, etc.
+
+        if let Some(weight) = event.payload.weight_grams {
+            self.check_weight_anomaly(event, weight).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Fold a weight reading into the rodent's stats, then compare it with the
+    /// stats as they were just before: record an anomaly if it deviates from the
+    /// running average beyond the threshold, and a weight change event if it
+    /// moved too far from the previous reading.
+    ///
+    /// The stats are updated in a single atomic step so concurrent readings for
+    /// the same rodent are never lost.
+    async fn check_weight_anomaly(&self, event: &DailyMetricsRecordedEvent, weight: f64) -> Result<(), String> {
+        let stats_collection: Collection<RodentWeightStats> = self.db.analytics_db.collection("rodent_weight_stats");
+        let rodent_id = &event.payload.rodent_id;
+
+        let now = chrono::Utc::now();
+        let recorded_at = event.payload.date;
+        let recorded_at_bson = bson::DateTime::from_chrono(recorded_at);
+
+        // Missing stats compare lower than any date, so the first reading is always the latest
+        let is_latest = doc! { "$lte": ["$last_weighed_at", recorded_at_bson] };
+        let previous_count = doc! { "$ifNull": ["$sample_count", 0_i64] };
+
+        let update = vec![doc! {
+            "$set": {
+                "avg_weight": {
+                    "$cond": [
+                        { "$gt": [previous_count.clone(), 0_i64] },
+                        { "$add": [
+                            "$avg_weight",
+                            { "$divide": [
+                                { "$subtract": [weight, "$avg_weight"] },
+                                { "$add": [previous_count.clone(), 1_i64] }
+                            ] }
+                        ] },
+                        weight
+                    ]
+                },
+                "sample_count": { "$add": [previous_count, 1_i64] },
+                // Keep the latest reading as the baseline for the next comparison
+                "last_weight": { "$cond": [is_latest.clone(), weight, "$last_weight"] },
+                "last_weighed_at": { "$cond": [is_latest, recorded_at_bson, "$last_weighed_at"] },
+                "updated_at": bson::DateTime::from_chrono(now),
+            }
+        }];
+
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(true)
+            .return_document(ReturnDocument::Before)
+            .build();
+
+        let previous = stats_collection
+            .find_one_and_update(doc! { "_id": rodent_id }, update, options)
+            .await
+            .map_err(|e| format!("Failed to update weight stats: {}", e))?;
+
+        // First reading establishes the baseline
+        let Some(stats) = previous else {
+            return Ok(());
+        };
+
+        if let (Some(previous_weight), Some(previous_recorded_at)) = (stats.last_weight, stats.last_weighed_at) {
+            // Late-arriving older readings aren't compared against newer ones
+            if recorded_at >= previous_recorded_at {
+                self.check_weight_change(event, weight, previous_weight, previous_recorded_at)
+                    .await?;
+            }
+        }
+
+        if stats.avg_weight > 0.0 {
+            let deviation_percent = (weight - stats.avg_weight).abs() / stats.avg_weight * 100.0;
+
+            if deviation_percent > self.anomaly_weight_threshold_percent {
+                warn!(
+                    "Weight anomaly for rodent {}: {}g vs average {:.1}g ({:.1}% deviation)",
+                    rodent_id, weight, stats.avg_weight, deviation_percent
+                );
+
+                let anomaly = Anomaly {
+                    id: None,
+                    rodent_id: rodent_id.clone(),
+                    record_id: event.payload.record_id.clone(),
+                    metric: "weight_grams".to_string(),
+                    value: weight,
+                    expected: stats.avg_weight,
+                    deviation_percent,
+                    threshold_percent: self.anomaly_weight_threshold_percent,
+                    recorded_by_name: event.payload.recorded_by_name.clone(),
+                    detected_at: now,
+                };
+
+                self.db
+                    .analytics_db
+                    .collection::<Anomaly>("anomalies")
+                    .insert_one(anomaly, None)
+                    .await
+                    .map_err(|e| format!("Failed to store anomaly: {}", e))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Record a weight change event when a reading differs from the rodent's
+    /// previous one by more than the configured percentage
+    async fn check_weight_change(
+        &self,
+        event: &DailyMetricsRecordedEvent,
+        weight: f64,
+        previous_weight: f64,
+        previous_recorded_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), String> {
+        if previous_weight <= 0.0 {
+            return Ok(());
+        }
+
+        let change_percent = (weight - previous_weight) / previous_weight * 100.0;
+        if change_percent.abs() <= self.weight_change_threshold_percent {
+            return Ok(());
+        }
+
+        let direction = if change_percent > 0.0 {
+            WeightChangeDirection::Gain
+        } else {
+            WeightChangeDirection::Loss
+        };
+
+        info!(
+            "Weight change for rodent {}: {}g -> {}g ({:+.1}%)",
+            event.payload.rodent_id, previous_weight, weight, change_percent
+        );
+
+        let change = WeightChangeEvent {
+            id: None,
+            rodent_id: event.payload.rodent_id.clone(),
+            record_id: event.payload.record_id.clone(),
+            direction,
+            previous_weight,
+            current_weight: weight,
+            change_percent: change_percent.abs(),
+            threshold_percent: self.weight_change_threshold_percent,
+            previous_recorded_at,
+            recorded_at: event.payload.date,
+            detected_at: chrono::Utc::now(),
+        };
+
+        self.db
+            .analytics_db
+            .collection::<WeightChangeEvent>("weight_change_events")
+            .insert_one(change, None)
+            .await
+            .map_err(|e| format!("Failed to store weight change event: {}", e))?;
+
         Ok(())
     }
 
@@ -247,9 +554,9 @@ impl EventConsumer {
 }
 
 /// Start the event consumer in a background task
-pub fn spawn_consumer(rabbitmq_url: String, db: Arc<MongoDB>) {
+pub fn spawn_consumer(config: &Config, db: Arc<MongoDB>, webhooks: WebhookDispatcher) {
+    let consumer = EventConsumer::new(config, db, webhooks);
     tokio::spawn(async move {
-        let consumer = EventConsumer::new(&rabbitmq_url, db);
         loop {
             match consumer.start_consuming().await {
                 Ok(_) => {
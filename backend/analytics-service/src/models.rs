@@ -33,6 +33,21 @@ pub enum TimePeriod {
     Custom,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightChangeDirection {
+    Gain,
+    Loss,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecomputeStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
 // ============== Database Models ==============
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,14 +73,175 @@ pub struct ReportParameters {
     pub rodent_ids: Option<Vec<String>>,
 }
 
+/// Running weight average per rodent, maintained by the event consumer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RodentWeightStats {
+    #[serde(rename = "_id")]
+    pub rodent_id: String,
+    pub avg_weight: f64,
+    pub sample_count: i64,
+    // Most recent reading, the baseline for weight change events
+    #[serde(default)]
+    pub last_weight: Option<f64>,
+    #[serde(default, with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional")]
+    pub last_weighed_at: Option<DateTime<Utc>>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Metric reading that deviated from a rodent's running average beyond the threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Anomaly {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub rodent_id: String,
+    pub record_id: String,
+    pub metric: String,
+    pub value: f64,
+    pub expected: f64,
+    pub deviation_percent: f64,
+    pub threshold_percent: f64,
+    pub recorded_by_name: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub detected_at: DateTime<Utc>,
+}
+
+/// Weight gain or loss between two consecutive readings beyond the threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightChangeEvent {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub rodent_id: String,
+    pub record_id: String,
+    pub direction: WeightChangeDirection,
+    pub previous_weight: f64,
+    pub current_weight: f64,
+    pub change_percent: f64,
+    pub threshold_percent: f64,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub previous_recorded_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub recorded_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub detected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub url: String,
+    pub encrypted_secret: String, // see webhooks::SecretCipher
+    pub event_types: Vec<String>,
+    pub active: bool,
+    pub created_by: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PopulationSnapshot {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub month: String, // "YYYY-MM"
+    pub total_rodents: i64,
+    pub by_species: Vec<SnapshotCount>,
+    pub by_status: Vec<SnapshotCount>,
+    pub by_gender: Vec<SnapshotCount>,
+    pub trigger: String, // "scheduled", "manual" or "recompute"
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub taken_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotCount {
+    pub key: String,
+    pub count: i64,
+}
+
+/// Rebuild of the event-derived analytics for a date range, run inline or in the background
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecomputeJob {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub from_date: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub to_date: DateTime<Utc>,
+    pub status: RecomputeStatus,
+    pub requested_by: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub started_at: DateTime<Utc>,
+    #[serde(default, with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional")]
+    pub finished_at: Option<DateTime<Utc>>,
+    pub summary: Option<RecomputeSummary>,
+    pub error: Option<String>,
+}
+
+/// What a recompute rebuilt
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecomputeSummary {
+    pub weight_stats_rebuilt: i64, // rodents weighed in the range
+    pub snapshots_refreshed: Vec<String>, // months
+    pub cache_entries_cleared: i64,
+}
+
+// ============== Request DTOs ==============
+
+#[derive(Debug, Deserialize)]
+pub struct RecomputeRequest {
+    pub from_date: DateTime<Utc>,
+    pub to_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub secret: Option<String>,
+}
+
 // ============== Query Parameters ==============
 
 #[derive(Debug, Deserialize)]
 pub struct AnalyticsQueryParams {
     pub from_date: Option<DateTime<Utc>>,
     pub to_date: Option<DateTime<Utc>>,
+    pub days: Option<i64>,
     pub species: Option<String>,
     pub period: Option<TimePeriod>,
+    pub exclude_samples: Option<bool>, // sample rodents are included unless true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StaffActivityQueryParams {
+    #[serde(alias = "from")]
+    pub from_date: Option<DateTime<Utc>>,
+    #[serde(alias = "to")]
+    pub to_date: Option<DateTime<Utc>>,
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeedingAnalyticsQueryParams {
+    pub from_date: Option<DateTime<Utc>>,
+    pub to_date: Option<DateTime<Utc>>,
+    pub days: Option<i64>,
+    pub species: Option<String>,
+    pub exclude_samples: Option<bool>, // sample rodents are included unless true
+    pub exclude_water: Option<bool>,   // water counts towards total_food_grams unless true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityAnalyticsQueryParams {
+    pub from_date: Option<DateTime<Utc>>,
+    pub to_date: Option<DateTime<Utc>>,
+    pub days: Option<i64>,
+    pub species: Option<String>,
+    pub hour_from: Option<u32>,    // first local hour included, 0-23
+    pub hour_to: Option<u32>,      // local hour the window ends before, 1-24; may wrap past midnight
+    pub tz_offset: Option<String>, // e.g. "+02:00"; TIMEZONE when unset
+    pub exclude_samples: Option<bool>, // sample rodents are included unless true
 }
 
 #[derive(Debug, Deserialize)]
@@ -82,11 +258,119 @@ pub struct ExportQueryParams {
     pub format: ReportFormat,
     pub from_date: Option<DateTime<Utc>>,
     pub to_date: Option<DateTime<Utc>>,
+    pub days: Option<i64>,
     pub species: Option<String>,
+    pub exclude_samples: Option<bool>, // sample rodents are included unless true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CohortStatsRequest {
+    pub rodent_ids: Vec<String>,
+    pub from_date: Option<DateTime<Utc>>,
+    pub to_date: Option<DateTime<Utc>>,
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SnapshotQueryParams {
+    pub months: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WeightChangeQueryParams {
+    pub rodent_id: Option<String>,
+    pub direction: Option<WeightChangeDirection>,
+    pub from_date: Option<DateTime<Utc>>,
+    pub to_date: Option<DateTime<Utc>>,
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IntensityQueryParams {
+    pub from_date: Option<DateTime<Utc>>,
+    pub to_date: Option<DateTime<Utc>>,
+    pub days: Option<i64>,
+    pub species: Option<String>,
+    pub exclude_samples: Option<bool>, // sample rodents are included unless true
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityFeedingCorrelationQueryParams {
+    pub from_date: Option<DateTime<Utc>>,
+    pub to_date: Option<DateTime<Utc>>,
+    pub days: Option<i64>,
+    pub species: Option<String>,
+    pub exclude_samples: Option<bool>, // sample rodents are included unless true
+    pub threshold: Option<f64>,        // coefficients below this count as decoupled
+    pub min_days: Option<usize>,       // rodents with fewer recorded days are skipped
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WeightPercentilesQueryParams {
+    pub species: String,
+    pub rodent_id: Option<String>, // also report where this rodent falls
+    pub days: Option<i64>,         // how far back a rodent's latest weight may be
+    pub exclude_samples: Option<bool>, // sample rodents are included unless true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DashboardQueryParams {
+    pub exclude_samples: Option<bool>, // sample rodents are included unless true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StaleRodentsQueryParams {
+    pub days: Option<i64>,
+    pub exclude_samples: Option<bool>, // sample rodents are included unless true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecentMortalityQueryParams {
+    pub days: Option<i64>,
+    pub exclude_samples: Option<bool>, // sample rodents are included unless true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CapacityQueryParams {
+    pub exclude_samples: Option<bool>, // sample rodents are included unless true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OverdueCheckupsQueryParams {
+    pub months: Option<u32>,
+    pub any_record: Option<bool>, // any medical record counts as a check-up when true
+    pub exclude_samples: Option<bool>, // sample rodents are included unless true
 }
 
 // ============== Response DTOs ==============
 
+/// Paging metadata shared by list responses; flattened into the response body
+#[derive(Debug, Serialize)]
+pub struct Pagination {
+    pub total: u64,
+    pub page: u32,
+    pub limit: u32,
+    pub total_pages: u64,
+    pub has_next: bool,
+    pub has_prev: bool,
+}
+
+impl Pagination {
+    pub fn new(total: u64, page: u32, limit: u32) -> Self {
+        let total_pages = total.div_ceil(u64::from(limit.max(1)));
+        Self {
+            total,
+            page,
+            limit,
+            total_pages,
+            has_next: u64::from(page) < total_pages,
+            has_prev: page > 1,
+        }
+    }
+}
+
 // Population Statistics
 #[derive(Debug, Serialize)]
 pub struct PopulationStatsResponse {
@@ -131,6 +415,8 @@ pub struct AgeGroupCount {
 pub struct HealthAnalyticsResponse {
     pub success: bool,
     pub weight_trends: Vec<WeightTrendData>,
+    pub weight_trend_slope: Option<f64>, // grams per day
+    pub weight_trend_direction: String,   // "increasing", "decreasing", "stable", "insufficient_data"
     pub avg_weight_by_species: Vec<SpeciesWeightAvg>,
     pub energy_level_distribution: Vec<LevelDistribution>,
     pub mood_level_distribution: Vec<LevelDistribution>,
@@ -157,6 +443,41 @@ pub struct TreatmentTypeCount {
     pub count: i64,
 }
 
+// Medication usage
+#[derive(Debug, Serialize)]
+pub struct MedicationUsageResponse {
+    pub success: bool,
+    pub from_date: DateTime<Utc>,
+    pub to_date: DateTime<Utc>,
+    pub medications: Vec<MedicationUsage>, // most used first
+}
+
+#[derive(Debug, Serialize)]
+pub struct MedicationUsage {
+    pub name: String,
+    pub count: i64, // prescriptions across all records
+    pub rodent_count: i64,
+    pub record_types: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StaffActivityResponse {
+    pub success: bool,
+    pub from_date: DateTime<Utc>,
+    pub to_date: DateTime<Utc>,
+    pub staff: Vec<StaffActivity>, // most records first
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct StaffActivity {
+    pub user_id: String,
+    pub name: String,
+    pub activities: i64,
+    pub feeding_records: i64,
+    pub daily_records: i64,
+    pub total: i64,
+}
+
 #[derive(Debug, Serialize)]
 pub struct WeightTrendData {
     pub date: String,
@@ -166,6 +487,29 @@ pub struct WeightTrendData {
     pub rodent_count: i64,
 }
 
+#[derive(Debug, Serialize)]
+pub struct WeightPercentilesResponse {
+    pub success: bool,
+    pub species: String,
+    pub from_date: DateTime<Utc>,
+    pub to_date: DateTime<Utc>,
+    pub rodent_count: usize,
+    pub p10: Option<f64>,
+    pub p25: Option<f64>,
+    pub p50: Option<f64>,
+    pub p75: Option<f64>,
+    pub p90: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rodent: Option<RodentWeightPercentile>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RodentWeightPercentile {
+    pub rodent_id: String,
+    pub weight_grams: f64,
+    pub percentile: f64, // share of the species weighing less, 0-100
+}
+
 #[derive(Debug, Serialize)]
 pub struct SpeciesWeightAvg {
     pub species: String,
@@ -218,8 +562,71 @@ pub struct DayOfWeekActivity {
 pub struct RodentActivityStats {
     pub rodent_id: String,
     pub rodent_name: String,
+    pub species: String,
+    pub total_minutes: i64,
+    pub session_count: i64,
+}
+
+// Activity Intensity
+#[derive(Debug, Serialize)]
+pub struct IntensityScoresResponse {
+    pub success: bool,
+    pub from_date: DateTime<Utc>,
+    pub to_date: DateTime<Utc>,
+    pub weights: std::collections::HashMap<String, f64>,
+    pub default_weight: f64,
+    pub rodents: Vec<RodentIntensityScore>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RodentIntensityScore {
+    pub rank: usize,
+    pub rodent_id: String,
+    pub rodent_name: String,
+    pub score: f64,         // sum of minutes x intensity weight
     pub total_minutes: i64,
     pub session_count: i64,
+    pub avg_intensity: f64, // score per minute
+}
+
+// Activity / Feeding Correlation
+#[derive(Debug, Serialize)]
+pub struct ActivityFeedingCorrelationResponse {
+    pub success: bool,
+    pub from_date: DateTime<Utc>,
+    pub to_date: DateTime<Utc>,
+    pub threshold: f64,
+    pub min_days: usize,
+    pub rodents_analyzed: usize,
+    pub decoupled: Vec<RodentActivityFeedingCorrelation>, // lowest coefficient first
+    pub skipped: Vec<SkippedActivityFeedingCorrelation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RodentActivityFeedingCorrelation {
+    pub rodent_id: String,
+    pub rodent_name: String,
+    pub correlation: f64, // Pearson coefficient of daily minutes vs daily grams
+    pub days: usize,
+    pub total_activity_minutes: f64,
+    pub total_food_grams: f64,
+}
+
+/// A rodent with records in the range that no coefficient could be computed for
+#[derive(Debug, Serialize)]
+pub struct SkippedActivityFeedingCorrelation {
+    pub rodent_id: String,
+    pub rodent_name: String,
+    pub days: usize,
+    pub reason: CorrelationSkipReason,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CorrelationSkipReason {
+    TooFewDays,
+    ConstantActivity, // includes rodents with no activity logged at all
+    ConstantFeeding,
 }
 
 // Feeding Analytics
@@ -228,10 +635,24 @@ pub struct FeedingAnalyticsResponse {
     pub success: bool,
     pub total_food_grams: f64,
     pub avg_daily_food: f64,
+    pub total_water_grams: f64,
+    pub avg_daily_water: f64,
     pub by_food_type: Vec<FoodTypeStats>,
     pub feeding_by_hour: Vec<HourlyFeeding>,
-    pub consumption_rate: f64,
+    pub consumption_rate: f64, // % of meals with a recorded outcome that were fully consumed
+    pub unknown_outcome_count: i64,
     pub top_consumers: Vec<RodentFeedingStats>,
+    pub feeding_intervals: Vec<RodentFeedingInterval>, // longest average gap first
+    pub feeding_interval_outliers: Vec<RodentFeedingInterval>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RodentFeedingInterval {
+    pub rodent_id: String,
+    pub rodent_name: String,
+    pub feeding_count: i64,
+    pub avg_interval_hours: f64,
+    pub max_interval_hours: f64,
 }
 
 #[derive(Debug, Serialize)]
@@ -240,6 +661,7 @@ pub struct FoodTypeStats {
     pub total_grams: f64,
     pub feeding_count: i64,
     pub avg_quantity: f64,
+    pub consumption_rate: f64,
 }
 
 #[derive(Debug, Serialize)]
@@ -253,10 +675,50 @@ pub struct HourlyFeeding {
 pub struct RodentFeedingStats {
     pub rodent_id: String,
     pub rodent_name: String,
+    pub species: String,
     pub total_grams: f64,
     pub feeding_count: i64,
 }
 
+// Cohort Statistics
+#[derive(Debug, Serialize)]
+pub struct CohortStatsResponse {
+    pub success: bool,
+    pub rodent_ids: Vec<String>,
+    pub activity: CohortActivityStats,
+    pub feeding: CohortFeedingStats,
+    pub weight: CohortWeightStats,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CohortActivityStats {
+    pub total_minutes: i64,
+    pub session_count: i64,
+    pub avg_daily_activity: f64,
+    pub by_activity_type: Vec<ActivityTypeStats>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CohortFeedingStats {
+    pub total_grams: f64,
+    pub feeding_count: i64,
+    pub avg_daily_food: f64,
+    pub consumption_rate: f64,
+    pub unknown_outcome_count: i64,
+    pub by_food_type: Vec<FoodTypeStats>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CohortWeightStats {
+    pub avg_weight: Option<f64>,
+    pub min_weight: Option<f64>,
+    pub max_weight: Option<f64>,
+    pub sample_count: i64,
+    pub weight_trends: Vec<WeightTrendData>,
+    pub weight_trend_slope: Option<f64>, // grams per day
+    pub weight_trend_direction: String,
+}
+
 // Dashboard Summary
 #[derive(Debug, Serialize)]
 pub struct DashboardSummaryResponse {
@@ -314,6 +776,98 @@ pub struct TrendDataPoint {
     pub count: i64,
 }
 
+// Stale Rodents (no recent daily record)
+#[derive(Debug, Serialize)]
+pub struct StaleRodentsResponse {
+    pub success: bool,
+    pub days: i64,
+    pub total: usize,
+    pub rodents: Vec<StaleRodent>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StaleRodent {
+    pub id: String,
+    pub name: String,
+    pub species: String,
+    pub last_record_date: Option<DateTime<Utc>>,
+}
+
+// Overdue check-ups
+#[derive(Debug, Serialize)]
+pub struct OverdueCheckupsResponse {
+    pub success: bool,
+    pub months: u32,
+    pub total: usize,
+    pub rodents: Vec<OverdueCheckupRodent>, // never checked first, then longest overdue
+}
+
+#[derive(Debug, Serialize)]
+pub struct OverdueCheckupRodent {
+    pub id: String,
+    pub name: String,
+    pub species: String,
+    pub status: String,
+    pub last_checkup_date: Option<DateTime<Utc>>,
+}
+
+// Capacity utilization
+#[derive(Debug, Serialize)]
+pub struct CapacityResponse {
+    pub success: bool,
+    pub total_rodents: i64,     // in care, with or without an enclosure
+    pub unassigned_rodents: i64, // in care but not in any enclosure
+    pub total_capacity: u32,
+    pub utilization_percent: f64,
+    pub over_capacity: bool,
+    pub enclosures: Vec<EnclosureCapacity>, // fullest first
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnclosureCapacity {
+    pub enclosure: String,
+    pub occupants: i64,
+    pub capacity: u32,
+    pub utilization_percent: f64,
+    pub over_capacity: bool,
+}
+
+// Recent mortality
+#[derive(Debug, Serialize)]
+pub struct RecentMortalityResponse {
+    pub success: bool,
+    pub days: i64,
+    pub total: usize,
+    pub deaths: Vec<RecentDeath>, // newest first
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecentDeath {
+    pub rodent_id: String,
+    pub name: String,
+    pub species: String,
+    pub previous_status: String,
+    pub reason: Option<String>,
+    pub recorded_by: String,
+    pub died_at: DateTime<Utc>, // when the status changed to deceased
+}
+
+// Event log stats
+#[derive(Debug, Serialize)]
+pub struct EventLogStatsResponse {
+    pub success: bool,
+    pub total: i64,
+    pub retention_days: i64,
+    pub by_event_type: Vec<EventLogTypeCount>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventLogTypeCount {
+    pub event_type: String,
+    pub processed: i64,
+    pub unprocessed: i64,
+}
+
 // Report List Response
 #[derive(Debug, Serialize)]
 pub struct SavedReportResponse {
@@ -329,9 +883,8 @@ pub struct SavedReportResponse {
 pub struct ReportListResponse {
     pub success: bool,
     pub reports: Vec<SavedReportResponse>,
-    pub total: u64,
-    pub page: u32,
-    pub limit: u32,
+    #[serde(flatten)]
+    pub pagination: Pagination,
 }
 
 #[derive(Debug, Serialize)]
@@ -352,6 +905,96 @@ pub struct SavedReportWithData {
     pub generated_at: DateTime<Utc>,
 }
 
+// Population Snapshots
+#[derive(Debug, Serialize)]
+pub struct PopulationSnapshotResponse {
+    pub month: String,
+    pub total_rodents: i64,
+    pub by_species: Vec<SnapshotCount>,
+    pub by_status: Vec<SnapshotCount>,
+    pub by_gender: Vec<SnapshotCount>,
+    pub trigger: String,
+    pub taken_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SinglePopulationSnapshotResponse {
+    pub success: bool,
+    pub snapshot: PopulationSnapshotResponse,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PopulationSnapshotListResponse {
+    pub success: bool,
+    pub snapshots: Vec<PopulationSnapshotResponse>, // oldest month first
+}
+
+// Recompute jobs
+#[derive(Debug, Serialize)]
+pub struct RecomputeJobView {
+    pub id: String,
+    pub from_date: DateTime<Utc>,
+    pub to_date: DateTime<Utc>,
+    pub status: RecomputeStatus,
+    pub requested_by: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub summary: Option<RecomputeSummary>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecomputeJobResponse {
+    pub success: bool,
+    pub job: RecomputeJobView,
+}
+
+// Weight change alerts
+#[derive(Debug, Serialize)]
+pub struct WeightChangeEventResponse {
+    pub id: String,
+    pub rodent_id: String,
+    pub record_id: String,
+    pub direction: WeightChangeDirection,
+    pub previous_weight: f64,
+    pub current_weight: f64,
+    pub change_percent: f64,
+    pub previous_recorded_at: DateTime<Utc>,
+    pub recorded_at: DateTime<Utc>,
+    pub detected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WeightChangeListResponse {
+    pub success: bool,
+    pub events: Vec<WeightChangeEventResponse>, // newest first
+    #[serde(flatten)]
+    pub pagination: Pagination,
+}
+
+// Webhooks
+#[derive(Debug, Serialize)]
+pub struct WebhookResponse {
+    pub id: String,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebhookCreatedResponse {
+    pub success: bool,
+    pub webhook: WebhookResponse,
+    pub secret: String, // only ever returned here, on registration
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebhookListResponse {
+    pub success: bool,
+    pub webhooks: Vec<WebhookResponse>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct MessageResponse {
     pub success: bool,
@@ -401,6 +1044,65 @@ impl From<SavedReport> for SavedReportResponse {
     }
 }
 
+impl From<PopulationSnapshot> for PopulationSnapshotResponse {
+    fn from(snapshot: PopulationSnapshot) -> Self {
+        Self {
+            month: snapshot.month,
+            total_rodents: snapshot.total_rodents,
+            by_species: snapshot.by_species,
+            by_status: snapshot.by_status,
+            by_gender: snapshot.by_gender,
+            trigger: snapshot.trigger,
+            taken_at: snapshot.taken_at,
+        }
+    }
+}
+
+impl From<RecomputeJob> for RecomputeJobView {
+    fn from(job: RecomputeJob) -> Self {
+        Self {
+            id: job.id.map(|id| id.to_hex()).unwrap_or_default(),
+            from_date: job.from_date,
+            to_date: job.to_date,
+            status: job.status,
+            requested_by: job.requested_by,
+            started_at: job.started_at,
+            finished_at: job.finished_at,
+            summary: job.summary,
+            error: job.error,
+        }
+    }
+}
+
+impl From<WeightChangeEvent> for WeightChangeEventResponse {
+    fn from(event: WeightChangeEvent) -> Self {
+        Self {
+            id: event.id.map(|id| id.to_hex()).unwrap_or_default(),
+            rodent_id: event.rodent_id,
+            record_id: event.record_id,
+            direction: event.direction,
+            previous_weight: event.previous_weight,
+            current_weight: event.current_weight,
+            change_percent: event.change_percent,
+            previous_recorded_at: event.previous_recorded_at,
+            recorded_at: event.recorded_at,
+            detected_at: event.detected_at,
+        }
+    }
+}
+
+impl From<Webhook> for WebhookResponse {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            id: webhook.id.map(|id| id.to_hex()).unwrap_or_default(),
+            url: webhook.url,
+            event_types: webhook.event_types,
+            active: webhook.active,
+            created_at: webhook.created_at,
+        }
+    }
+}
+
 impl From<SavedReport> for SavedReportWithData {
     fn from(report: SavedReport) -> Self {
         Self {
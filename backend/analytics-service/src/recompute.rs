@@ -0,0 +1,170 @@
+use bson::{doc, Bson, Document};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use futures::TryStreamExt;
+use mongodb::{options::ReplaceOptions, Collection};
+use tracing::{error, info};
+
+use crate::db::MongoDB;
+use crate::models::{RecomputeJob, RecomputeStatus, RecomputeSummary, RodentWeightStats};
+use crate::snapshots;
+
+/// Ranges longer than this are rebuilt in the background
+pub const INLINE_RECOMPUTE_MAX_DAYS: i64 = 31;
+
+pub fn jobs(db: &MongoDB) -> Collection<RecomputeJob> {
+    db.analytics_db.collection("recompute_jobs")
+}
+
+/// Rebuild everything the event consumer maintains for `from..=to` from the
+/// source collections, so missed or duplicated events stop skewing the figures.
+///
+/// Weight stats are rebuilt over each affected rodent's whole history, since
+/// they're running totals. Snapshots count the current population, so only
+/// the current month's is refreshed, and only when the range reaches it.
+pub async fn recompute(
+    db: &MongoDB,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    tz: Tz,
+) -> Result<RecomputeSummary, mongodb::error::Error> {
+    let weight_stats_rebuilt = rebuild_weight_stats(db, from, to).await?;
+
+    let mut snapshots_refreshed = Vec::new();
+    let current_month = snapshots::month_label(Utc::now(), tz);
+    if snapshots::month_label(to, tz) >= current_month {
+        let snapshot = snapshots::take_population_snapshot(db, &current_month, "recompute").await?;
+        snapshots_refreshed.push(snapshot.month);
+    }
+
+    // Cached aggregates carry no date range, so drop them all and let them refill
+    let cache_entries_cleared = db
+        .analytics_db
+        .collection::<Document>("analytics_cache")
+        .delete_many(doc! {}, None)
+        .await?
+        .deleted_count as i64;
+
+    Ok(RecomputeSummary {
+        weight_stats_rebuilt,
+        snapshots_refreshed,
+        cache_entries_cleared,
+    })
+}
+
+/// Replace the running weight stats of every rodent weighed in the range with
+/// figures computed from all of its daily records
+async fn rebuild_weight_stats(
+    db: &MongoDB,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<i64, mongodb::error::Error> {
+    let daily_records = db.activity_db.collection::<Document>("daily_records");
+    let weighed = doc! { "$exists": true, "$ne": null };
+
+    let rodent_ids = daily_records
+        .distinct(
+            "rodent_id",
+            doc! { "date": { "$gte": from, "$lte": to }, "weight_grams": weighed.clone() },
+            None,
+        )
+        .await?;
+
+    if rodent_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let pipeline = vec![
+        doc! { "$match": { "rodent_id": { "$in": rodent_ids }, "weight_grams": weighed } },
+        doc! { "$sort": { "date": 1 } },
+        doc! {
+            "$group": {
+                "_id": "$rodent_id",
+                "avg_weight": { "$avg": "$weight_grams" },
+                "sample_count": { "$sum": 1_i64 },
+                "last_weight": { "$last": "$weight_grams" },
+                "last_weighed_at": { "$last": "$date" }
+            }
+        },
+    ];
+
+    let stats_collection: Collection<RodentWeightStats> = db.analytics_db.collection("rodent_weight_stats");
+    let mut cursor = daily_records.aggregate(pipeline, None).await?;
+    let now = Utc::now();
+    let mut rebuilt = 0;
+
+    while let Some(group) = cursor.try_next().await? {
+        // Events carry the rodent id as a hex string, so the stats are keyed the same way
+        let rodent_id = match group.get("_id") {
+            Some(Bson::ObjectId(id)) => id.to_hex(),
+            Some(Bson::String(id)) => id.clone(),
+            _ => continue,
+        };
+
+        let stats = RodentWeightStats {
+            rodent_id: rodent_id.clone(),
+            avg_weight: group.get_f64("avg_weight").unwrap_or(0.0),
+            sample_count: group.get_i64("sample_count").unwrap_or(0),
+            last_weight: group.get_f64("last_weight").ok(),
+            last_weighed_at: group.get_datetime("last_weighed_at").ok().map(|d| d.to_chrono()),
+            updated_at: now,
+        };
+
+        stats_collection
+            .replace_one(
+                doc! { "_id": &rodent_id },
+                &stats,
+                ReplaceOptions::builder().upsert(true).build(),
+            )
+            .await?;
+        rebuilt += 1;
+    }
+
+    Ok(rebuilt)
+}
+
+/// Run a stored job to completion and record its outcome
+pub async fn run_job(db: &MongoDB, mut job: RecomputeJob, tz: Tz) -> RecomputeJob {
+    match recompute(db, job.from_date, job.to_date, tz).await {
+        Ok(summary) => {
+            info!(
+                "Recomputed analytics for {} to {}: {} weight stats, {} cache entries cleared",
+                job.from_date, job.to_date, summary.weight_stats_rebuilt, summary.cache_entries_cleared
+            );
+            job.status = RecomputeStatus::Completed;
+            job.summary = Some(summary);
+        }
+        Err(e) => {
+            error!("Analytics recompute for {} to {} failed: {}", job.from_date, job.to_date, e);
+            job.status = RecomputeStatus::Failed;
+            job.error = Some(e.to_string());
+        }
+    }
+    job.finished_at = Some(Utc::now());
+
+    if let Some(id) = job.id {
+        if let Err(e) = jobs(db).replace_one(doc! { "_id": id }, &job, None).await {
+            error!("Failed to store outcome of recompute job {}: {}", id, e);
+        }
+    }
+
+    job
+}
+
+/// Fail jobs left `running` by a previous process. Background jobs run inside
+/// the service, so one still marked running at startup died with it.
+pub async fn fail_interrupted_jobs(db: &MongoDB) -> Result<u64, mongodb::error::Error> {
+    let now = bson::DateTime::from_chrono(Utc::now());
+    let result = jobs(db)
+        .update_many(
+            doc! { "status": "running" },
+            doc! { "$set": {
+                "status": "failed",
+                "error": "Interrupted by a service restart",
+                "finished_at": now,
+            } },
+            None,
+        )
+        .await?;
+    Ok(result.modified_count)
+}
@@ -0,0 +1,52 @@
+use bson::doc;
+use chrono::{Duration, SecondsFormat, Utc};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::db::MongoDB;
+
+/// How often old event logs are purged
+const EVENT_LOG_PURGE_INTERVAL_SECS: u64 = 3600;
+
+/// Delete processed event logs received more than `retention_days` ago.
+/// Unprocessed logs (failed or dead-lettered events) are kept so they can
+/// still be investigated.
+pub async fn purge_event_logs(db: &MongoDB, retention_days: i64) -> Result<u64, mongodb::error::Error> {
+    // received_at is stored as an RFC 3339 string, which sorts chronologically
+    let cutoff = (Utc::now() - Duration::days(retention_days)).to_rfc3339_opts(SecondsFormat::Secs, true);
+    let result = db
+        .db
+        .collection::<bson::Document>("event_logs")
+        .delete_many(
+            doc! {
+                "processed": true,
+                "received_at": { "$lt": cutoff },
+            },
+            None,
+        )
+        .await?;
+    Ok(result.deleted_count)
+}
+
+/// Periodically purge old processed event logs; a retention of 0 keeps them forever
+pub fn spawn_event_log_purge(db: Arc<MongoDB>, retention_days: i64) {
+    if retention_days <= 0 {
+        info!("Event log retention disabled; event logs are kept indefinitely");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(EVENT_LOG_PURGE_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            match purge_event_logs(&db, retention_days).await {
+                Ok(0) => {}
+                Ok(deleted) => info!(
+                    "Purged {} processed event logs older than {} days",
+                    deleted, retention_days
+                ),
+                Err(e) => error!("Failed to purge event logs: {}", e),
+            }
+        }
+    });
+}
@@ -1,5 +1,5 @@
 use axum::{
-    routing::get,
+    routing::{delete, get, post},
     Router,
 };
 use std::sync::Arc;
@@ -18,22 +18,63 @@ pub fn create_routes() -> Router<Arc<AppState>> {
         // Population analytics
         .route("/analytics/population", get(handlers::get_population_stats))
 
+        // Monthly population snapshots
+        .route("/analytics/snapshots", get(handlers::list_population_snapshots))
+        .route("/analytics/snapshots/monthly", post(handlers::create_population_snapshot))
+
+        // Rebuild event-derived analytics after an outage (admin only)
+        .route("/analytics/recompute", post(handlers::recompute_analytics))
+        .route("/analytics/recompute/:id", get(handlers::get_recompute_job))
+
         // Health analytics (rodent health data)
         .route("/analytics/health", get(handlers::get_health_analytics))
 
+        // Medication usage across medical records
+        .route("/analytics/medications", get(handlers::get_medication_usage))
+        .route("/analytics/staff-activity", get(handlers::get_staff_activity))
+
         // Activity analytics
         .route("/analytics/activity", get(handlers::get_activity_analytics))
 
+        // Weighted activity intensity ranking
+        .route("/analytics/intensity", get(handlers::get_intensity_scores))
+
+        // Rodents whose activity and feeding are decoupled
+        .route("/analytics/activity-feeding-correlation", get(handlers::get_activity_feeding_correlation))
+
         // Feeding analytics
         .route("/analytics/feeding", get(handlers::get_feeding_analytics))
 
+        // Cohort analytics (hand-picked set of rodents)
+        .route("/analytics/cohort", post(handlers::get_cohort_stats))
+
         // Trend data
         .route("/analytics/trends/weight", get(handlers::get_weight_trends))
         .route("/analytics/trends/activity", get(handlers::get_activity_trends))
         .route("/analytics/trends/feeding", get(handlers::get_feeding_trends))
 
+        // Species weight distribution
+        .route("/analytics/weight-percentiles", get(handlers::get_weight_percentiles))
+
+        // Alerts
+        .route("/analytics/alerts/weight-changes", get(handlers::list_weight_change_alerts))
+
+        // Welfare checks
+        .route("/analytics/rodents/stale", get(handlers::get_stale_rodents))
+        .route("/analytics/checkups/overdue", get(handlers::get_overdue_checkups))
+        .route("/analytics/mortality/recent", get(handlers::get_recent_mortality))
+        .route("/analytics/capacity", get(handlers::get_capacity_utilization))
+
+        // Event pipeline monitoring (admin only)
+        .route("/analytics/events/stats", get(handlers::get_event_log_stats))
+
+        // Webhook registrations (admin only)
+        .route("/analytics/webhooks", post(handlers::create_webhook).get(handlers::list_webhooks))
+        .route("/analytics/webhooks/:id", delete(handlers::delete_webhook))
+
         // Export endpoints
         .route("/analytics/export/population", get(handlers::export_population_csv))
         .route("/analytics/export/activity", get(handlers::export_activity_csv))
+        .route("/analytics/export/activities.ndjson", get(handlers::export_activities_ndjson))
         .route("/analytics/export/feeding", get(handlers::export_feeding_csv))
 }
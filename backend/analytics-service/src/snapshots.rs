@@ -0,0 +1,141 @@
+use bson::{doc, Document};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use chrono_tz::Tz;
+use futures::TryStreamExt;
+use mongodb::{options::ReplaceOptions, Collection};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::db::MongoDB;
+use crate::models::{PopulationSnapshot, SnapshotCount};
+
+/// How often the scheduler checks whether last month's snapshot is still missing
+const SNAPSHOT_CHECK_INTERVAL_SECS: u64 = 3600;
+
+/// `YYYY-MM` label used as the snapshot key, by the shelter's local calendar
+pub fn month_label(date: DateTime<Utc>, tz: Tz) -> String {
+    date.with_timezone(&tz).format("%Y-%m").to_string()
+}
+
+fn start_of_month(date: DateTime<Utc>, tz: Tz) -> DateTime<Utc> {
+    let local = date.with_timezone(&tz);
+    tz.with_ymd_and_hms(local.year(), local.month(), 1, 0, 0, 0)
+        .earliest()
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or(date)
+}
+
+fn counts_from(facet: &Document, key: &str) -> Vec<SnapshotCount> {
+    facet
+        .get_array(key)
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| entry.as_document())
+                .map(|entry| SnapshotCount {
+                    key: entry.get_str("_id").unwrap_or("unknown").to_string(),
+                    count: entry
+                        .get_i32("count")
+                        .map(i64::from)
+                        .or_else(|_| entry.get_i64("count"))
+                        .unwrap_or(0),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Count the current population by species, status and gender and store it
+/// under `month`, replacing any earlier snapshot for that month
+pub async fn take_population_snapshot(
+    db: &MongoDB,
+    month: &str,
+    trigger: &str,
+) -> Result<PopulationSnapshot, mongodb::error::Error> {
+    let rodents = db.rodent_db.collection::<Document>("rodents");
+
+    let pipeline = vec![doc! {
+        "$facet": {
+            "by_species": [
+                { "$group": { "_id": "$species", "count": { "$sum": 1 } } },
+                { "$sort": { "_id": 1 } }
+            ],
+            "by_status": [
+                { "$group": { "_id": "$status", "count": { "$sum": 1 } } },
+                { "$sort": { "_id": 1 } }
+            ],
+            "by_gender": [
+                { "$group": { "_id": "$gender", "count": { "$sum": 1 } } },
+                { "$sort": { "_id": 1 } }
+            ]
+        }
+    }];
+
+    let facet = rodents
+        .aggregate(pipeline, None)
+        .await?
+        .try_next()
+        .await?
+        .unwrap_or_default();
+
+    let by_species = counts_from(&facet, "by_species");
+    let snapshot = PopulationSnapshot {
+        id: None,
+        month: month.to_string(),
+        total_rodents: by_species.iter().map(|c| c.count).sum(),
+        by_species,
+        by_status: counts_from(&facet, "by_status"),
+        by_gender: counts_from(&facet, "by_gender"),
+        trigger: trigger.to_string(),
+        taken_at: Utc::now(),
+    };
+
+    let collection: Collection<PopulationSnapshot> = db.analytics_db.collection("population_snapshots");
+    collection
+        .replace_one(
+            doc! { "month": month },
+            &snapshot,
+            ReplaceOptions::builder().upsert(true).build(),
+        )
+        .await?;
+
+    Ok(snapshot)
+}
+
+/// Take last month's snapshot unless one was already taken after the month ended
+async fn ensure_previous_month_snapshot(db: &MongoDB, tz: Tz) -> Result<(), mongodb::error::Error> {
+    let this_month_start = start_of_month(Utc::now(), tz);
+    let previous_month = month_label(this_month_start - Duration::days(1), tz);
+
+    let existing = db
+        .analytics_db
+        .collection::<PopulationSnapshot>("population_snapshots")
+        .find_one(doc! { "month": &previous_month }, None)
+        .await?;
+
+    // A manual snapshot from mid-month doesn't count as the month-end figure
+    if existing.is_some_and(|s| s.taken_at >= this_month_start) {
+        return Ok(());
+    }
+
+    let snapshot = take_population_snapshot(db, &previous_month, "scheduled").await?;
+    info!(
+        "Stored month-end population snapshot for {} ({} rodents)",
+        snapshot.month, snapshot.total_rodents
+    );
+
+    Ok(())
+}
+
+/// Start the monthly snapshot scheduler in a background task
+pub fn spawn_monthly_snapshots(db: Arc<MongoDB>, tz: Tz) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(SNAPSHOT_CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            if let Err(e) = ensure_previous_month_snapshot(&db, tz).await {
+                error!("Failed to take monthly population snapshot: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,186 @@
+use aes_gcm::{
+    aead::{Aead, AeadCore, OsRng},
+    Aes256Gcm, Nonce,
+};
+use bson::doc;
+use futures::TryStreamExt;
+use hmac::{Hmac, Mac};
+use mongodb::Collection;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::db::MongoDB;
+use crate::models::Webhook;
+
+pub const SIGNATURE_HEADER: &str = "X-RodentCare-Signature";
+pub const EVENT_TYPE_HEADER: &str = "X-RodentCare-Event";
+
+/// Delay before the first retry; doubled after every further failure
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Hex-encoded HMAC-SHA256 of the request body, keyed with the webhook secret
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Encrypts webhook secrets at rest. They're needed in the clear to sign
+/// deliveries, so they can't be hashed like passwords.
+#[derive(Clone)]
+pub struct SecretCipher(Aes256Gcm);
+
+impl SecretCipher {
+    /// Keyed with the SHA-256 of `WEBHOOK_SECRET_KEY`
+    pub fn new(key: &str) -> Self {
+        Self(<Aes256Gcm as aes_gcm::KeyInit>::new(&Sha256::digest(key.as_bytes())))
+    }
+
+    /// Hex-encoded random nonce followed by the ciphertext
+    pub fn encrypt(&self, secret: &str) -> String {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .0
+            .encrypt(&nonce, secret.as_bytes())
+            .expect("AES-GCM encryption of an in-memory buffer cannot fail");
+        let mut stored = nonce.to_vec();
+        stored.extend(ciphertext);
+        hex::encode(stored)
+    }
+
+    /// `None` if `stored` wasn't encrypted with this key
+    pub fn decrypt(&self, stored: &str) -> Option<String> {
+        let bytes = hex::decode(stored).ok()?;
+        if bytes.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let secret = self.0.decrypt(Nonce::from_slice(nonce), ciphertext).ok()?;
+        String::from_utf8(secret).ok()
+    }
+}
+
+const NONCE_LEN: usize = 12;
+
+/// Delivers events received from RabbitMQ to registered webhook URLs
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    db: Arc<MongoDB>,
+    client: reqwest::Client,
+    cipher: SecretCipher,
+    max_attempts: u32,
+}
+
+impl WebhookDispatcher {
+    pub fn new(db: Arc<MongoDB>, cipher: SecretCipher, timeout_secs: u64, max_attempts: u32) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(timeout_secs))
+            .build()
+            .expect("Failed to build webhook HTTP client");
+
+        Self {
+            db,
+            client,
+            cipher,
+            max_attempts: max_attempts.max(1),
+        }
+    }
+
+    /// Fan an event out to every active webhook subscribed to its type.
+    /// Deliveries run in the background so retries never hold up the consumer.
+    pub async fn dispatch(&self, event_type: &str, payload: &str) -> Result<(), String> {
+        let collection: Collection<Webhook> = self.db.analytics_db.collection("webhooks");
+
+        let webhooks: Vec<Webhook> = collection
+            .find(doc! { "active": true, "event_types": event_type }, None)
+            .await
+            .map_err(|e| format!("Failed to load webhooks: {}", e))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("Failed to load webhooks: {}", e))?;
+
+        for webhook in webhooks {
+            let dispatcher = self.clone();
+            let event_type = event_type.to_string();
+            let payload = payload.to_string();
+            tokio::spawn(async move {
+                dispatcher.deliver(&webhook, &event_type, payload).await;
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn deliver(&self, webhook: &Webhook, event_type: &str, payload: String) {
+        let Some(secret) = self.cipher.decrypt(&webhook.encrypted_secret) else {
+            error!(
+                "Cannot decrypt the secret of webhook {}; was WEBHOOK_SECRET_KEY changed?",
+                webhook.url
+            );
+            return;
+        };
+        let signature = format!("sha256={}", sign_payload(&secret, payload.as_bytes()));
+        let mut delay = INITIAL_RETRY_DELAY;
+
+        for attempt in 1..=self.max_attempts {
+            let result = self
+                .client
+                .post(&webhook.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .header(EVENT_TYPE_HEADER, event_type)
+                .body(payload.clone())
+                .send()
+                .await;
+
+            match result {
+                Ok(response) if response.status().is_success() => {
+                    info!("Delivered {} to webhook {}", event_type, webhook.url);
+                    return;
+                }
+                Ok(response) => {
+                    warn!(
+                        "Webhook {} returned {} for {} (attempt {}/{})",
+                        webhook.url, response.status(), event_type, attempt, self.max_attempts
+                    );
+                }
+                Err(e) => {
+                    warn!(
+                        "Webhook {} failed for {}: {} (attempt {}/{})",
+                        webhook.url, event_type, e, attempt, self.max_attempts
+                    );
+                }
+            }
+
+            if attempt < self.max_attempts {
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+        }
+
+        error!(
+            "Giving up on delivering {} to webhook {} after {} attempts",
+            event_type, webhook.url, self.max_attempts
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrets_round_trip_only_under_the_same_key() {
+        let cipher = SecretCipher::new("server-key");
+        let stored = cipher.encrypt("whsec-0123456789abcdef");
+
+        assert!(!stored.contains("whsec"));
+        assert_ne!(stored, cipher.encrypt("whsec-0123456789abcdef"));
+        assert_eq!(cipher.decrypt(&stored).as_deref(), Some("whsec-0123456789abcdef"));
+        assert_eq!(SecretCipher::new("other-key").decrypt(&stored), None);
+        assert_eq!(cipher.decrypt("not hex"), None);
+    }
+}
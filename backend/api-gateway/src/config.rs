@@ -1,4 +1,5 @@
 use std::env;
+use std::net::IpAddr;
 
 #[derive(Clone)]
 pub struct Config {
@@ -9,6 +10,10 @@ pub struct Config {
     pub analytics_service_url: String,
     pub rate_limit_requests: u32,
     pub rate_limit_window_secs: u64,
+    pub rate_limit_overrides: Vec<(String, u32)>,
+    pub max_body_size_mb: usize,
+    pub internal_only: bool,
+    pub internal_allowlist: Vec<Cidr>,
 }
 
 impl Config {
@@ -34,6 +39,111 @@ impl Config {
                 .unwrap_or_else(|_| "60".to_string())
                 .parse()
                 .expect("RATE_LIMIT_WINDOW_SECS must be a number"),
+            rate_limit_overrides: parse_rate_limit_overrides(
+                &env::var("RATE_LIMIT_OVERRIDES")
+                    .unwrap_or_else(|_| "/analytics/export=20".to_string()),
+            ),
+            max_body_size_mb: env::var("MAX_BODY_SIZE_MB")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .expect("MAX_BODY_SIZE_MB must be a number"),
+            internal_only: env::var("INTERNAL_ONLY")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .expect("INTERNAL_ONLY must be true or false"),
+            internal_allowlist: parse_cidr_list(
+                &env::var("INTERNAL_ALLOWLIST")
+                    .unwrap_or_else(|_| "127.0.0.1/32,::1/128".to_string()),
+            ),
+        }
+    }
+}
+
+/// Parse `prefix=limit` pairs separated by commas, e.g. `/analytics/export=20,/auth/login=30`
+fn parse_rate_limit_overrides(value: &str) -> Vec<(String, u32)> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (prefix, limit) = entry
+                .split_once('=')
+                .expect("RATE_LIMIT_OVERRIDES entries must be in the form prefix=limit");
+            let limit = limit
+                .trim()
+                .parse()
+                .expect("RATE_LIMIT_OVERRIDES limits must be numbers");
+            (prefix.trim().trim_end_matches('/').to_string(), limit)
+        })
+        .collect()
+}
+
+/// IP network such as `10.0.0.0/8`; a bare address matches only itself
+#[derive(Clone, Debug)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix_len: u32,
+}
+
+impl Cidr {
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        // Dual-stack sockets report IPv4 peers as IPv4-mapped IPv6 addresses
+        let addr = match addr {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(addr),
+            v4 => v4,
+        };
+
+        match (self.network, addr) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                network_bits(u32::from(net).into(), 32, self.prefix_len)
+                    == network_bits(u32::from(ip).into(), 32, self.prefix_len)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                network_bits(net.into(), 128, self.prefix_len) == network_bits(ip.into(), 128, self.prefix_len)
+            }
+            _ => false,
         }
     }
 }
+
+/// The leading `prefix_len` bits of a `width`-bit address
+fn network_bits(bits: u128, width: u32, prefix_len: u32) -> u128 {
+    if prefix_len == 0 {
+        0
+    } else {
+        bits >> (width - prefix_len)
+    }
+}
+
+/// Parse comma-separated addresses or CIDR ranges, e.g. `127.0.0.1/32,10.0.0.0/8,::1`
+fn parse_cidr_list(value: &str) -> Vec<Cidr> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (addr, prefix) = match entry.split_once('/') {
+                Some((addr, prefix)) => (addr, Some(prefix)),
+                None => (entry, None),
+            };
+            let network: IpAddr = addr
+                .trim()
+                .parse()
+                .expect("INTERNAL_ALLOWLIST entries must be IP addresses or CIDR ranges");
+            let max_len = if network.is_ipv4() { 32 } else { 128 };
+            let prefix_len = prefix
+                .map(|p| {
+                    p.trim()
+                        .parse()
+                        .expect("INTERNAL_ALLOWLIST prefix lengths must be numbers")
+                })
+                .unwrap_or(max_len);
+            assert!(
+                prefix_len <= max_len,
+                "INTERNAL_ALLOWLIST prefix length out of range in {}",
+                entry
+            );
+            Cidr { network, prefix_len }
+        })
+        .collect()
+}
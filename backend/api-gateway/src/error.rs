@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -18,7 +18,7 @@ pub enum GatewayError {
     AccessDenied,
 
     #[error("Rate limit exceeded")]
-    RateLimitExceeded,
+    RateLimitExceeded { limit: u32, retry_after_secs: u64 },
 
     #[error("Service unavailable: {0}")]
     ServiceUnavailable(String),
@@ -28,6 +28,25 @@ pub enum GatewayError {
 
     #[error("Internal server error")]
     InternalError,
+
+    #[error("Request body too large")]
+    PayloadTooLarge,
+}
+
+impl GatewayError {
+    /// Sent as `code` next to the message
+    pub fn code(&self) -> &'static str {
+        match self {
+            GatewayError::InvalidToken => "INVALID_TOKEN",
+            GatewayError::TokenExpired => "TOKEN_EXPIRED",
+            GatewayError::AccessDenied => "ACCESS_DENIED",
+            GatewayError::RateLimitExceeded { .. } => "RATE_LIMIT_EXCEEDED",
+            GatewayError::ServiceUnavailable(_) => "SERVICE_UNAVAILABLE",
+            GatewayError::BadGateway(_) => "BAD_GATEWAY",
+            GatewayError::InternalError => "INTERNAL_ERROR",
+            GatewayError::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+        }
+    }
 }
 
 impl IntoResponse for GatewayError {
@@ -36,17 +55,28 @@ impl IntoResponse for GatewayError {
             GatewayError::InvalidToken => (StatusCode::UNAUTHORIZED, self.to_string()),
             GatewayError::TokenExpired => (StatusCode::UNAUTHORIZED, self.to_string()),
             GatewayError::AccessDenied => (StatusCode::FORBIDDEN, self.to_string()),
-            GatewayError::RateLimitExceeded => (StatusCode::TOO_MANY_REQUESTS, self.to_string()),
+            GatewayError::RateLimitExceeded { .. } => (StatusCode::TOO_MANY_REQUESTS, self.to_string()),
             GatewayError::ServiceUnavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
             GatewayError::BadGateway(_) => (StatusCode::BAD_GATEWAY, self.to_string()),
             GatewayError::InternalError => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+            GatewayError::PayloadTooLarge => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
         };
 
         let body = Json(json!({
             "success": false,
             "error": error_message,
+            "code": self.code(),
         }));
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+
+        if let GatewayError::RateLimitExceeded { limit, retry_after_secs } = self {
+            let headers = response.headers_mut();
+            headers.insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+            headers.insert("X-RateLimit-Limit", HeaderValue::from(limit));
+            headers.insert("X-RateLimit-Remaining", HeaderValue::from(0u32));
+        }
+
+        response
     }
 }
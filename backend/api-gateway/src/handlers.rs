@@ -11,7 +11,7 @@ use std::sync::Arc;
 
 use crate::{
     error::GatewayError,
-    middleware::{auth_middleware, rate_limit_middleware},
+    middleware::{auth_middleware, internal_only_middleware, rate_limit_middleware},
     proxy::proxy_request,
     AppState,
 };
@@ -34,7 +34,8 @@ pub async fn fallback_handler(request: Request<Body>) -> impl IntoResponse {
         axum::http::StatusCode::NOT_FOUND,
         Json(json!({
             "success": false,
-            "error": format!("Route not found: {} {}", method, uri)
+            "error": format!("Route not found: {} {}", method, uri),
+            "code": "ROUTE_NOT_FOUND"
         }))
     )
 }
@@ -125,15 +126,20 @@ pub async fn proxy_to_analytics_service(
 }
 
 pub fn create_routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
-    // Public routes (no authentication required)
+    // Public routes (no authentication required); /health stays open for load balancers
     let public_routes = Router::new()
         .route("/health", get(health_check))
-        .route("/services/health", get(services_health))
         // Auth routes that don't require token
         .route("/auth/register", any(proxy_to_user_service_public))
         .route("/auth/login", any(proxy_to_user_service_public))
         .route("/auth/refresh", any(proxy_to_user_service_public));
 
+    // Internal routes: no authentication so scrapers can reach them, but
+    // limited to INTERNAL_ALLOWLIST when INTERNAL_ONLY is set
+    let internal_routes = Router::new()
+        .route("/services/health", get(services_health))
+        .layer(middleware::from_fn_with_state(state.clone(), internal_only_middleware));
+
     // Protected User Service routes (authentication required)
     let protected_user_routes = Router::new()
         .route("/auth/logout", any(proxy_to_user_service_protected))
@@ -146,6 +152,8 @@ pub fn create_routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
     let rodent_routes = Router::new()
         .route("/rodents", any(proxy_to_rodent_registry_service))
         .route("/rodents/*path", any(proxy_to_rodent_registry_service))
+        .route("/medical-records", any(proxy_to_rodent_registry_service))
+        .route("/medical-records/*path", any(proxy_to_rodent_registry_service))
         .layer(middleware::from_fn_with_state(state.clone(), auth_middleware));
 
     // Activity Tracking Service routes (protected with authentication)
@@ -163,6 +171,7 @@ pub fn create_routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
     // Combine all routes with rate limiting
     Router::new()
         .merge(public_routes)
+        .merge(internal_routes)
         .merge(protected_user_routes)
         .merge(rodent_routes)
         .merge(activity_routes)
@@ -6,28 +6,35 @@ mod proxy;
 mod rate_limiter;
 
 use axum::Router;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::config::Config;
-use crate::rate_limiter::RateLimiter;
+use crate::rate_limiter::RouteRateLimiters;
 
 pub struct AppState {
     pub config: Config,
     pub http_client: reqwest::Client,
-    pub rate_limiter: RateLimiter,
+    pub rate_limiter: RouteRateLimiters,
 }
 
 #[tokio::main]
 async fn main() {
-    // Initialize tracing
+    // Initialize tracing (LOG_FORMAT=json for machine-readable output)
+    let json_logs = std::env::var("LOG_FORMAT")
+        .map(|format| format.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+
     tracing_subscriber::registry()
         .with(tracing_subscriber::EnvFilter::new(
             std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
         ))
-        .with(tracing_subscriber::fmt::layer())
+        .with(json_logs.then(|| tracing_subscriber::fmt::layer().json()))
+        .with((!json_logs).then(tracing_subscriber::fmt::layer))
         .init();
 
     // Load configuration
@@ -43,10 +50,11 @@ async fn main() {
         .build()
         .expect("Failed to create HTTP client");
 
-    // Create rate limiter
-    let rate_limiter = RateLimiter::new(
+    // Create rate limiters (global default plus per-route overrides)
+    let rate_limiter = RouteRateLimiters::new(
         config.rate_limit_requests,
         config.rate_limit_window_secs,
+        &config.rate_limit_overrides,
     );
 
     // Create app state
@@ -56,6 +64,18 @@ async fn main() {
         rate_limiter,
     });
 
+    // Periodically drop idle clients from the rate limiter
+    let cleanup_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+            cleanup_state.config.rate_limit_window_secs.max(1),
+        ));
+        loop {
+            interval.tick().await;
+            cleanup_state.rate_limiter.cleanup();
+        }
+    });
+
     // CORS configuration
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -65,6 +85,7 @@ async fn main() {
     // Build router
     let app = Router::new()
         .nest("/api", handlers::create_routes(state.clone()))
+        .layer(CompressionLayer::new())
         .layer(cors)
         .layer(TraceLayer::new_for_http())
         .with_state(state);
@@ -76,5 +97,8 @@ async fn main() {
 
     tracing::info!("API Gateway listening on {}", listener.local_addr().unwrap());
 
-    axum::serve(listener, app).await.unwrap();
+    // Peer addresses are needed for the internal endpoint allowlist
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .unwrap();
 }
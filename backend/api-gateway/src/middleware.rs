@@ -1,10 +1,11 @@
 use axum::{
-    extract::{Request, State},
-    http::header,
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderValue},
     middleware::Next,
     response::Response,
 };
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 use crate::{error::GatewayError, AppState};
@@ -37,9 +38,52 @@ pub async fn rate_limit_middleware(
         .unwrap_or("unknown")
         .to_string();
 
-    if !state.rate_limiter.check_rate_limit(&client_id) {
+    // Per-route overrides get their own budget, everything else shares the global one
+    let limiter = state.rate_limiter.for_path(request.uri().path());
+
+    if !limiter.check_rate_limit(&client_id) {
         tracing::warn!("Rate limit exceeded for client: {}", client_id);
-        return Err(GatewayError::RateLimitExceeded);
+        // Round up so clients never retry before a slot is actually free
+        let reset_after = limiter.get_reset_after(&client_id);
+        let retry_after_secs = reset_after.as_secs() + u64::from(reset_after.subsec_nanos() > 0);
+        return Err(GatewayError::RateLimitExceeded {
+            limit: limiter.max_requests(),
+            retry_after_secs: retry_after_secs.max(1),
+        });
+    }
+
+    let remaining = limiter.get_remaining(&client_id);
+    let mut response = next.run(request).await;
+
+    let headers = response.headers_mut();
+    headers.insert("X-RateLimit-Limit", HeaderValue::from(limiter.max_requests()));
+    headers.insert("X-RateLimit-Remaining", HeaderValue::from(remaining));
+
+    Ok(response)
+}
+
+/// Limit internal endpoints (deep health checks, metrics) to the configured
+/// allowlist when `INTERNAL_ONLY` is set. The connecting peer is checked, not
+/// `X-Forwarded-For`, which clients can forge.
+pub async fn internal_only_middleware(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Result<Response, GatewayError> {
+    if state.config.internal_only
+        && !state
+            .config
+            .internal_allowlist
+            .iter()
+            .any(|cidr| cidr.contains(peer.ip()))
+    {
+        tracing::warn!(
+            "Blocked {} from {}: not on the internal allowlist",
+            request.uri().path(),
+            peer.ip()
+        );
+        return Err(GatewayError::AccessDenied);
     }
 
     Ok(next.run(request).await)
@@ -99,16 +143,20 @@ pub async fn auth_middleware(
         return Err(GatewayError::InvalidToken);
     }
 
+    // A valid token must carry full identity; never forward a request without it
+    let (Some(user_id), Some(username), Some(role)) =
+        (validation.user_id, validation.username, validation.role)
+    else {
+        tracing::warn!("Token validation returned incomplete identity");
+        return Err(GatewayError::InvalidToken);
+    };
+
     // Add auth info to request extensions
-    if let (Some(user_id), Some(username), Some(role)) = 
-        (validation.user_id, validation.username, validation.role) 
-    {
-        request.extensions_mut().insert(AuthInfo {
-            user_id,
-            username,
-            role,
-        });
-    }
+    request.extensions_mut().insert(AuthInfo {
+        user_id,
+        username,
+        role,
+    });
 
     Ok(next.run(request).await)
 }
@@ -31,9 +31,10 @@ pub async fn proxy_request(
         .map_err(|_| GatewayError::InternalError)?;
     let mut builder = state.http_client.request(reqwest_method, &full_url);
 
-    // Forward headers (except host)
+    // Forward headers (except host and accept-encoding, since the gateway compresses
+    // responses itself and upstream bodies must arrive uncompressed)
     for (key, value) in req.headers().iter() {
-        if key != header::HOST {
+        if key != header::HOST && key != header::ACCEPT_ENCODING {
             if let Ok(v) = value.to_str() {
                 builder = builder.header(key.as_str(), v);
             }
@@ -42,9 +43,9 @@ pub async fn proxy_request(
 
     // Forward body for POST/PUT/PATCH
     if matches!(method.as_str(), "POST" | "PUT" | "PATCH") {
-        let body_bytes = axum::body::to_bytes(req.into_body(), usize::MAX)
+        let body_bytes = axum::body::to_bytes(req.into_body(), state.config.max_body_size_mb * 1024 * 1024)
             .await
-            .map_err(|_| GatewayError::InternalError)?;
+            .map_err(|_| GatewayError::PayloadTooLarge)?;
         builder = builder.body(body_bytes);
     }
 
@@ -67,6 +68,8 @@ pub async fn proxy_request(
 
     let mut response_builder = Response::builder().status(status.as_u16());
     
+    // Content-Encoding is kept if an upstream compressed anyway, which makes the
+    // compression layer pass the body through instead of encoding it twice
     for (key, value) in headers.iter() {
         if let Ok(v) = value.to_str() {
             response_builder = response_builder.header(key.as_str(), v);
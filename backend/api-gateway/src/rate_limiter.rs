@@ -1,8 +1,15 @@
 use dashmap::DashMap;
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
+/// Sliding-window rate limiter.
+///
+/// Each client keeps the timestamps of its accepted requests, oldest first.
+/// A request is allowed only if fewer than `max_requests` were accepted in the
+/// `window` immediately preceding it, so there is no fixed boundary at which a
+/// client can burst twice the limit.
 pub struct RateLimiter {
-    requests: DashMap<String, Vec<Instant>>,
+    requests: DashMap<String, VecDeque<Instant>>,
     max_requests: u32,
     window: Duration,
 }
@@ -17,28 +24,159 @@ impl RateLimiter {
     }
 
     pub fn check_rate_limit(&self, key: &str) -> bool {
-        let now = Instant::now();
-        let mut entry = self.requests.entry(key.to_string()).or_insert_with(Vec::new);
-        
-        // Remove old requests outside the window
-        entry.retain(|&time| now.duration_since(time) < self.window);
-        
+        self.check_rate_limit_at(key, Instant::now())
+    }
+
+    fn check_rate_limit_at(&self, key: &str, now: Instant) -> bool {
+        let mut entry = self.requests.entry(key.to_string()).or_default();
+
+        self.evict_expired(&mut entry, now);
+
         if entry.len() >= self.max_requests as usize {
             false
         } else {
-            entry.push(now);
+            entry.push_back(now);
             true
         }
     }
 
     pub fn get_remaining(&self, key: &str) -> u32 {
-        let now = Instant::now();
-        
+        self.get_remaining_at(key, Instant::now())
+    }
+
+    fn get_remaining_at(&self, key: &str, now: Instant) -> u32 {
         if let Some(mut entry) = self.requests.get_mut(key) {
-            entry.retain(|&time| now.duration_since(time) < self.window);
+            self.evict_expired(&mut entry, now);
             self.max_requests.saturating_sub(entry.len() as u32)
         } else {
             self.max_requests
         }
     }
+
+    pub fn max_requests(&self) -> u32 {
+        self.max_requests
+    }
+
+    /// Time until the oldest request in the window expires and frees a slot
+    pub fn get_reset_after(&self, key: &str) -> Duration {
+        let now = Instant::now();
+
+        self.requests
+            .get(key)
+            .and_then(|entry| entry.front().copied())
+            .map(|oldest| self.window.saturating_sub(now.duration_since(oldest)))
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Drop clients with no requests left in the window so the map doesn't grow unbounded
+    pub fn cleanup(&self) {
+        self.cleanup_at(Instant::now());
+    }
+
+    fn cleanup_at(&self, now: Instant) {
+        self.requests.retain(|_, entry| {
+            self.evict_expired(entry, now);
+            !entry.is_empty()
+        });
+    }
+
+    fn evict_expired(&self, entry: &mut VecDeque<Instant>, now: Instant) {
+        while let Some(&oldest) = entry.front() {
+            if now.duration_since(oldest) < self.window {
+                break;
+            }
+            entry.pop_front();
+        }
+    }
+}
+
+/// Global rate limiter plus independent limiters for configured path prefixes
+pub struct RouteRateLimiters {
+    default: RateLimiter,
+    overrides: Vec<(String, RateLimiter)>,
+}
+
+impl RouteRateLimiters {
+    pub fn new(max_requests: u32, window_secs: u64, overrides: &[(String, u32)]) -> Self {
+        let mut overrides: Vec<(String, RateLimiter)> = overrides
+            .iter()
+            .map(|(prefix, limit)| (prefix.clone(), RateLimiter::new(*limit, window_secs)))
+            .collect();
+
+        // Longest prefix first so the most specific override wins
+        overrides.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+        Self {
+            default: RateLimiter::new(max_requests, window_secs),
+            overrides,
+        }
+    }
+
+    /// Pick the limiter for a request path, falling back to the global one
+    pub fn for_path(&self, path: &str) -> &RateLimiter {
+        self.overrides
+            .iter()
+            .find(|(prefix, _)| {
+                path.strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .map(|(_, limiter)| limiter)
+            .unwrap_or(&self.default)
+    }
+
+    pub fn cleanup(&self) {
+        self.default.cleanup();
+        for (_, limiter) in &self.overrides {
+            limiter.cleanup();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn burst_at_window_boundary_is_still_limited() {
+        let limiter = RateLimiter::new(3, 1);
+        let start = Instant::now();
+
+        assert!(limiter.check_rate_limit_at("client", start));
+        assert!(limiter.check_rate_limit_at("client", start + ms(900)));
+        assert!(limiter.check_rate_limit_at("client", start + ms(900)));
+        assert!(!limiter.check_rate_limit_at("client", start + ms(999)));
+
+        // A fixed window would reset here and allow three more; the sliding
+        // window only frees the slot of the request from `start`
+        assert!(limiter.check_rate_limit_at("client", start + ms(1000)));
+        assert!(!limiter.check_rate_limit_at("client", start + ms(1000)));
+        assert!(!limiter.check_rate_limit_at("client", start + ms(1899)));
+
+        assert!(limiter.check_rate_limit_at("client", start + ms(1900)));
+        assert!(limiter.check_rate_limit_at("client", start + ms(1900)));
+        assert!(!limiter.check_rate_limit_at("client", start + ms(1900)));
+    }
+
+    #[test]
+    fn requests_are_evicted_after_the_window() {
+        let limiter = RateLimiter::new(2, 1);
+        let start = Instant::now();
+
+        assert!(limiter.check_rate_limit_at("client", start));
+        assert!(limiter.check_rate_limit_at("client", start + ms(100)));
+        assert_eq!(limiter.get_remaining_at("client", start + ms(500)), 0);
+        assert_eq!(limiter.get_remaining_at("client", start + ms(1000)), 1);
+        assert_eq!(limiter.get_remaining_at("client", start + ms(1100)), 2);
+
+        // Other clients have their own window
+        assert!(limiter.check_rate_limit_at("other", start + ms(1100)));
+
+        limiter.cleanup_at(start + ms(2100));
+        assert!(!limiter.requests.contains_key("client"));
+        assert!(!limiter.requests.contains_key("other"));
+    }
 }
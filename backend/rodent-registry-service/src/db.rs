@@ -53,12 +53,18 @@ impl MongoDB {
             .keys(doc! { "species": 1, "status": 1, "created_at": -1 })
             .build();
 
+        // Index for volunteer assignments
+        let assigned_to_index = IndexModel::builder()
+            .keys(doc! { "assigned_to": 1 })
+            .build();
+
         rodents.create_indexes(vec![
             chip_id_index,
             species_index,
             status_index,
             name_index,
             compound_index,
+            assigned_to_index,
         ], None).await?;
 
         // Medical records collection indexes
@@ -84,11 +90,17 @@ impl MongoDB {
             .keys(doc! { "rodent_id": 1, "date": -1 })
             .build();
 
+        // Full-text search over the free-text fields
+        let medical_text_index = IndexModel::builder()
+            .keys(doc! { "description": "text", "diagnosis": "text", "test_results": "text" })
+            .build();
+
         medical_records.create_indexes(vec![
             rodent_id_index,
             record_type_index,
             date_index,
             medical_compound_index,
+            medical_text_index,
         ], None).await?;
 
         // Status history collection indexes
@@ -100,8 +112,66 @@ impl MongoDB {
 
         status_history.create_indexes(vec![status_rodent_index], None).await?;
 
+        // Enclosure history collection indexes
+        let enclosure_history = self.db.collection::<bson::Document>("enclosure_history");
+
+        let enclosure_rodent_index = IndexModel::builder()
+            .keys(doc! { "rodent_id": 1, "moved_at": -1 })
+            .build();
+
+        enclosure_history.create_indexes(vec![enclosure_rodent_index], None).await?;
+
+        // Intake date audit trail indexes
+        let intake_date_changes = self.db.collection::<bson::Document>("intake_date_changes");
+
+        let intake_rodent_index = IndexModel::builder()
+            .keys(doc! { "rodent_id": 1, "changed_at": -1 })
+            .build();
+
+        intake_date_changes.create_indexes(vec![intake_rodent_index], None).await?;
+
+        // Outbox of unpublished events, relayed oldest first
+        let outbox = self.db.collection::<bson::Document>("outbox");
+
+        let outbox_created_index = IndexModel::builder()
+            .keys(doc! { "created_at": 1 })
+            .build();
+
+        outbox.create_index(outbox_created_index, None).await?;
+
         info!("MongoDB indexes created successfully");
 
         Ok(())
     }
+
+    /// Turn notes written before notes_history existed into the first history entry
+    pub async fn migrate_notes_history(&self) -> Result<(), mongodb::error::Error> {
+        let rodents = self.db.collection::<bson::Document>("rodents");
+
+        let result = rodents
+            .update_many(
+                doc! {
+                    "notes_history": { "$exists": false },
+                    "notes": { "$type": "string", "$ne": "" },
+                },
+                vec![doc! {
+                    "$set": {
+                        "notes_history": [{
+                            "text": "$notes",
+                            "author_id": "$updated_by",
+                            "author_name": "unknown",
+                            "created_at": "$updated_at",
+                        }]
+                    }
+                }],
+                None,
+            )
+            .await?;
+
+        if result.modified_count > 0 {
+            info!("Migrated notes of {} rodents into notes history", result.modified_count);
+        }
+
+        Ok(())
+    }
 }
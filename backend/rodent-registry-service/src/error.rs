@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -14,6 +14,9 @@ pub enum AppError {
     #[error("Medical record not found")]
     MedicalRecordNotFound,
 
+    #[error("Rodent has no images")]
+    NoImages,
+
     #[error("Invalid rodent ID format")]
     InvalidRodentId,
 
@@ -35,6 +38,9 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     ValidationError(String),
 
+    #[error("Invalid date range")]
+    InvalidDateRange,
+
     #[error("Image too large: max size is {0}MB")]
     ImageTooLarge(usize),
 
@@ -49,6 +55,37 @@ pub enum AppError {
 
     #[error("Service unavailable: {0}")]
     ServiceUnavailable(String),
+
+    #[error("Request body too large")]
+    PayloadTooLarge,
+
+    #[error("Not implemented: {0}")]
+    NotImplemented(String),
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::RodentNotFound => "RODENT_NOT_FOUND",
+            AppError::MedicalRecordNotFound => "MEDICAL_RECORD_NOT_FOUND",
+            AppError::NoImages => "NO_IMAGES",
+            AppError::InvalidRodentId => "INVALID_RODENT_ID",
+            AppError::InvalidMedicalRecordId => "INVALID_MEDICAL_RECORD_ID",
+            AppError::ChipIdAlreadyExists => "CHIP_ID_ALREADY_EXISTS",
+            AppError::InvalidToken => "INVALID_TOKEN",
+            AppError::TokenExpired => "TOKEN_EXPIRED",
+            AppError::AccessDenied(_) => "ACCESS_DENIED",
+            AppError::ValidationError(_) => "VALIDATION_ERROR",
+            AppError::InvalidDateRange => "INVALID_DATE_RANGE",
+            AppError::ImageTooLarge(_) => "IMAGE_TOO_LARGE",
+            AppError::InvalidImageFormat(_) => "INVALID_IMAGE_FORMAT",
+            AppError::DatabaseError(_) => "DATABASE_ERROR",
+            AppError::InternalError => "INTERNAL_ERROR",
+            AppError::ServiceUnavailable(_) => "SERVICE_UNAVAILABLE",
+            AppError::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+            AppError::NotImplemented(_) => "NOT_IMPLEMENTED",
+        }
+    }
 }
 
 impl IntoResponse for AppError {
@@ -56,6 +93,7 @@ impl IntoResponse for AppError {
         let (status, error_message) = match &self {
             AppError::RodentNotFound => (StatusCode::NOT_FOUND, self.to_string()),
             AppError::MedicalRecordNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::NoImages => (StatusCode::NOT_FOUND, self.to_string()),
             AppError::InvalidRodentId => (StatusCode::BAD_REQUEST, self.to_string()),
             AppError::InvalidMedicalRecordId => (StatusCode::BAD_REQUEST, self.to_string()),
             AppError::ChipIdAlreadyExists => (StatusCode::CONFLICT, self.to_string()),
@@ -63,6 +101,7 @@ impl IntoResponse for AppError {
             AppError::TokenExpired => (StatusCode::UNAUTHORIZED, self.to_string()),
             AppError::AccessDenied(_) => (StatusCode::FORBIDDEN, self.to_string()),
             AppError::ValidationError(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            AppError::InvalidDateRange => (StatusCode::BAD_REQUEST, self.to_string()),
             AppError::ImageTooLarge(_) => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
             AppError::InvalidImageFormat(_) => (StatusCode::BAD_REQUEST, self.to_string()),
             AppError::DatabaseError(_) => {
@@ -74,17 +113,34 @@ impl IntoResponse for AppError {
                 (StatusCode::INTERNAL_SERVER_ERROR, self.to_string())
             }
             AppError::ServiceUnavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
+            AppError::PayloadTooLarge => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
+            AppError::NotImplemented(_) => (StatusCode::NOT_IMPLEMENTED, self.to_string()),
         };
 
         let body = Json(json!({
             "success": false,
-            "error": error_message
+            "error": error_message,
+            "code": self.code()
         }));
 
         (status, body).into_response()
     }
 }
 
+/// Replace axum's plain-text 413 (from `DefaultBodyLimit`) with the JSON error envelope
+pub async fn json_payload_too_large(response: Response) -> Response {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE && !is_json {
+        return AppError::PayloadTooLarge.into_response();
+    }
+
+    response
+}
+
 impl From<mongodb::error::Error> for AppError {
     fn from(err: mongodb::error::Error) -> Self {
         tracing::error!("MongoDB error: {:?}", err);
@@ -8,11 +8,21 @@ pub const RODENT_REGISTERED_ROUTING_KEY: &str = "registry.rodent_registered";
 pub const RODENT_STATUS_CHANGED_ROUTING_KEY: &str = "registry.rodent_status_changed";
 pub const MEDICAL_TREATMENT_ADDED_ROUTING_KEY: &str = "registry.medical_treatment";
 
+/// Version of the event payload layout; bump when a payload changes shape
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// Events published before versioning was introduced have the version 1 layout
+fn legacy_schema_version() -> u32 {
+    1
+}
+
 /// Event published when a new rodent is registered
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RodentRegisteredEvent {
     pub event_type: String,
     pub event_id: String,
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub timestamp: DateTime<Utc>,
     pub payload: RodentRegisteredPayload,
 }
@@ -35,6 +45,7 @@ impl RodentRegisteredEvent {
         Self {
             event_type: "RodentRegistered".to_string(),
             event_id: uuid::Uuid::new_v4().to_string(),
+            schema_version: EVENT_SCHEMA_VERSION,
             timestamp: Utc::now(),
             payload,
         }
@@ -46,6 +57,8 @@ impl RodentRegisteredEvent {
 pub struct RodentStatusChangedEvent {
     pub event_type: String,
     pub event_id: String,
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub timestamp: DateTime<Utc>,
     pub payload: RodentStatusChangedPayload,
 }
@@ -65,6 +78,7 @@ impl RodentStatusChangedEvent {
         Self {
             event_type: "RodentStatusChanged".to_string(),
             event_id: uuid::Uuid::new_v4().to_string(),
+            schema_version: EVENT_SCHEMA_VERSION,
             timestamp: Utc::now(),
             payload,
         }
@@ -76,6 +90,8 @@ impl RodentStatusChangedEvent {
 pub struct MedicalTreatmentAddedEvent {
     pub event_type: String,
     pub event_id: String,
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub timestamp: DateTime<Utc>,
     pub payload: MedicalTreatmentPayload,
 }
@@ -99,6 +115,7 @@ impl MedicalTreatmentAddedEvent {
         Self {
             event_type: "MedicalTreatmentAdded".to_string(),
             event_id: uuid::Uuid::new_v4().to_string(),
+            schema_version: EVENT_SCHEMA_VERSION,
             timestamp: Utc::now(),
             payload,
         }
@@ -1,26 +1,36 @@
 use axum::{
     extract::{Multipart, Path, Query, State},
     http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use base64::Engine;
 use bson::{doc, oid::ObjectId, Document};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
-use jsonwebtoken::{decode, DecodingKey, Validation};
-use mongodb::options::FindOptions;
+use jsonwebtoken::{decode, DecodingKey};
+use mongodb::{
+    options::{FindOneOptions, FindOptions, UpdateModifications},
+    Collection,
+};
+use std::io::Write;
 use std::sync::Arc;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
+    config::{normalize_species, parse_sort_order, rodent_sort_field, Config},
     error::AppError,
     events::{
         MedicalTreatmentAddedEvent, MedicalTreatmentPayload, RodentRegisteredEvent,
         RodentRegisteredPayload, RodentStatusChangedEvent, RodentStatusChangedPayload,
     },
-    middleware::{can_manage_medical_records, can_manage_rodents, can_view},
+    middleware::{
+        can_manage_assignments, can_manage_medical_records, can_manage_rodents, can_merge_rodents,
+        can_purge_sample_data, can_view, rodent_visibility_filter,
+    },
     models::*,
+    timing::QueryTimer,
     AppState,
 };
 
@@ -40,7 +50,7 @@ fn extract_auth_info(state: &AppState, headers: &HeaderMap) -> Result<AuthInfo,
     let claims = decode::<Claims>(
         token,
         &DecodingKey::from_secret(state.config.jwt_secret.as_bytes()),
-        &Validation::default(),
+        &state.config.jwt_validation(),
     )
     .map_err(|e| {
         tracing::debug!("Token validation failed: {:?}", e);
@@ -54,15 +64,242 @@ fn extract_auth_info(state: &AppState, headers: &HeaderMap) -> Result<AuthInfo,
     Ok(AuthInfo::from(claims))
 }
 
+/// Page size for listings, bounded by `max_page_limit`
+fn page_limit(config: &Config, requested: Option<u32>, default: u32) -> Result<u32, AppError> {
+    let limit = requested.unwrap_or(default.min(config.max_page_limit));
+    if limit <= config.max_page_limit {
+        return Ok(limit);
+    }
+    if config.clamp_page_limit {
+        return Ok(config.max_page_limit);
+    }
+    Err(AppError::ValidationError(format!(
+        "limit must be at most {}",
+        config.max_page_limit
+    )))
+}
+
+/// Reject reversed ranges instead of silently returning an empty result
+fn validate_date_range(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Result<(), AppError> {
+    match (from, to) {
+        (Some(from), Some(to)) if from > to => Err(AppError::InvalidDateRange),
+        _ => Ok(()),
+    }
+}
+
+/// Intake dates record when a rodent arrived, so they can't lie in the future
+fn validate_intake_date(intake_date: Option<DateTime<Utc>>) -> Result<(), AppError> {
+    match intake_date {
+        Some(date) if date > Utc::now() => Err(AppError::ValidationError(
+            "Intake date cannot be in the future".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Treatments happen while the rodent is in our care, so a record can't be
+/// dated before its intake or in the future
+fn validate_medical_record_date(date: DateTime<Utc>, intake_date: DateTime<Utc>) -> Result<(), AppError> {
+    if date < intake_date {
+        return Err(AppError::ValidationError(
+            "Medical record date cannot be before the rodent's intake date".to_string(),
+        ));
+    }
+    if date > Utc::now() {
+        return Err(AppError::ValidationError(
+            "Medical record date cannot be in the future".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Built-in species pass through; others must be on the configured allowlist and
+/// are stored in normalized form so analytics group them consistently
+fn resolve_species(config: &Config, species: Species) -> Result<Species, AppError> {
+    match species {
+        Species::Other(name) => {
+            let normalized = normalize_species(&name);
+            if config.additional_species.contains(&normalized) {
+                Ok(Species::Other(normalized))
+            } else {
+                Err(AppError::ValidationError(format!("Unknown species: {}", name)))
+            }
+        }
+        known => Ok(known),
+    }
+}
+
 // ============== Health Check ==============
 
-pub async fn health_check() -> Json<MessageResponse> {
-    Json(MessageResponse {
+/// Service status, with the number of events waiting in the outbox (null if
+/// it couldn't be counted) as a measure of event lag
+pub async fn health_check(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
+    Json(HealthResponse {
         success: true,
         message: "Rodent Registry Service is healthy".to_string(),
+        outbox_backlog: state.publisher.outbox_backlog().await.ok(),
+    })
+}
+
+/// Weak ETag over the serialized response body. Hashing the content rather than
+/// `updated_at` also catches derived fields such as `age_months`.
+fn compute_etag(body: &[u8]) -> String {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    body.hash(&mut hasher);
+    format!("W/\"{:016x}\"", hasher.finish())
+}
+
+/// Whether an `If-None-Match` header matches the current ETag (weak comparison)
+fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    let Some(if_none_match) = headers.get(header::IF_NONE_MATCH).and_then(|h| h.to_str().ok()) else {
+        return false;
+    };
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+
+    if_none_match
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == opaque(etag))
+}
+
+/// History entry for a submitted note; blank notes add nothing
+fn new_note(text: Option<&str>, auth_info: &AuthInfo, now: DateTime<Utc>) -> Option<RodentNote> {
+    let text = text.map(str::trim).filter(|text| !text.is_empty())?;
+    Some(RodentNote {
+        text: text.to_string(),
+        author_id: auth_info.user_id.clone(),
+        author_name: auth_info.username.clone(),
+        created_at: now,
     })
 }
 
+/// IDs of the rodents the caller may see, or `None` when they may see all of them
+async fn visible_rodent_ids(
+    rodents: &Collection<Document>,
+    auth_info: &AuthInfo,
+) -> Result<Option<Vec<ObjectId>>, AppError> {
+    let Some(visibility) = rodent_visibility_filter(auth_info) else {
+        return Ok(None);
+    };
+
+    let ids = rodents
+        .find(visibility, FindOptions::builder().projection(doc! { "_id": 1 }).build())
+        .await?
+        .try_collect::<Vec<Document>>()
+        .await?
+        .iter()
+        .filter_map(|doc| doc.get_object_id("_id").ok())
+        .collect();
+
+    Ok(Some(ids))
+}
+
+/// Look up the names of the given rodents in one query
+async fn rodent_names(
+    rodents: &Collection<Document>,
+    ids: impl Iterator<Item = ObjectId>,
+) -> Result<std::collections::HashMap<ObjectId, String>, AppError> {
+    let mut ids: Vec<ObjectId> = ids.collect();
+    ids.sort();
+    ids.dedup();
+
+    let name_options = FindOptions::builder().projection(doc! { "name": 1 }).build();
+    let mut names = std::collections::HashMap::new();
+    let mut cursor = rodents.find(doc! { "_id": { "$in": ids } }, name_options).await?;
+    while let Some(doc) = cursor.try_next().await? {
+        if let (Ok(id), Ok(name)) = (doc.get_object_id("_id"), doc.get_str("name")) {
+            names.insert(id, name.to_string());
+        }
+    }
+
+    Ok(names)
+}
+
+/// Projection for list endpoints that leaves out base64 image payloads
+fn without_image_data() -> Document {
+    doc! { "images.data": 0 }
+}
+
+/// Response fields a rodent listing may be trimmed to with `fields`
+const RODENT_LIST_FIELDS: [&str; 17] = [
+    "id",
+    "species",
+    "name",
+    "gender",
+    "date_of_birth",
+    "date_of_birth_estimated",
+    "age_months",
+    "chip_id",
+    "status",
+    "notes",
+    "enclosure",
+    "assigned_to",
+    "is_sample",
+    "images",
+    "intake_date",
+    "created_at",
+    "updated_at",
+];
+
+/// Stored fields that may be left out of a listing query, with the response
+/// fields that need them. The rest are required to deserialize a rodent.
+const OPTIONAL_RODENT_FIELDS: [(&str, &[&str]); 7] = [
+    ("date_of_birth", &["date_of_birth", "age_months"]),
+    ("chip_id", &["chip_id"]),
+    ("notes", &["notes"]),
+    ("enclosure", &["enclosure"]),
+    ("assigned_to", &["assigned_to"]),
+    ("is_sample", &["is_sample"]),
+    ("images", &["images"]),
+];
+
+/// Parse a `fields` list against the allowlist. `id` is always included.
+fn parse_list_fields(fields: Option<&str>) -> Result<Option<Vec<String>>, AppError> {
+    let Some(fields) = fields else {
+        return Ok(None);
+    };
+
+    let mut selected = vec!["id".to_string()];
+    for field in fields.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+        if !RODENT_LIST_FIELDS.contains(&field) {
+            return Err(AppError::ValidationError(format!(
+                "Unknown field '{}'. Allowed fields: {}",
+                field,
+                RODENT_LIST_FIELDS.join(", ")
+            )));
+        }
+        if !selected.iter().any(|s| s == field) {
+            selected.push(field.to_string());
+        }
+    }
+
+    Ok(Some(selected))
+}
+
+/// Projection for a rodent listing: the notes history never, and optional
+/// fields only when a requested response field needs them
+fn list_projection(fields: Option<&[String]>) -> Document {
+    let mut projection = doc! { "notes_history": 0 };
+    if let Some(fields) = fields {
+        for (stored, needed_by) in OPTIONAL_RODENT_FIELDS {
+            if !needed_by.iter().any(|needed| fields.iter().any(|f| f == needed)) {
+                projection.insert(stored, 0);
+            }
+        }
+    }
+    projection
+}
+
+/// Serialize a rodent, keeping only the requested response fields
+fn trim_rodent_response(rodent: RodentResponse, fields: Option<&[String]>) -> Result<serde_json::Value, AppError> {
+    let mut value = serde_json::to_value(rodent).map_err(|_| AppError::InternalError)?;
+    if let (Some(fields), Some(object)) = (fields, value.as_object_mut()) {
+        object.retain(|key, _| fields.iter().any(|f| f == key));
+    }
+    Ok(value)
+}
+
 // ============== Rodent Handlers ==============
 
 /// List all rodents with filtering and pagination
@@ -70,17 +307,20 @@ pub async fn list_rodents(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Query(params): Query<RodentQueryParams>,
-) -> Result<Json<RodentListResponse>, AppError> {
+) -> Result<Json<RodentListResponse<serde_json::Value>>, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "list_rodents");
     can_view(&auth_info)?;
 
+    let fields = parse_list_fields(params.fields.as_deref())?;
+
     let collection = state.db.db.collection::<Rodent>("rodents");
 
     // Build filter
     let mut filter = Document::new();
 
     if let Some(species) = &params.species {
-        filter.insert("species", species.as_str());
+        filter.insert("species", normalize_species(species.as_str()));
     }
 
     if let Some(status) = &params.status {
@@ -96,24 +336,36 @@ pub async fn list_rodents(
         filter.insert("chip_id", chip_id);
     }
 
+    if params.exclude_samples.unwrap_or(false) {
+        filter.insert("is_sample", doc! { "$ne": true });
+    }
+
+    if let Some(visibility) = rodent_visibility_filter(&auth_info) {
+        filter.extend(visibility);
+    }
+
     // Pagination
     let page = params.page.unwrap_or(1).max(1);
-    let limit = params.limit.unwrap_or(20).min(100);
+    let limit = page_limit(&state.config, params.limit, 20)?;
     let skip = (page - 1) * limit;
 
     // Sorting
-    let sort_field = match params.sort_by.as_deref() {
-        Some("age") => "date_of_birth",
-        Some("intake_date") => "intake_date",
-        Some("name") => "name",
-        _ => "created_at",
-    };
-    let sort_order = if params.sort_order.as_deref() == Some("asc") { 1 } else { -1 };
+    let sort_field = params
+        .sort_by
+        .as_deref()
+        .and_then(rodent_sort_field)
+        .unwrap_or(state.config.default_sort_field);
+    let sort_order = params
+        .sort_order
+        .as_deref()
+        .and_then(parse_sort_order)
+        .unwrap_or(state.config.default_sort_order);
 
     let find_options = FindOptions::builder()
         .skip(Some(skip as u64))
         .limit(Some(limit as i64))
         .sort(doc! { sort_field: sort_order })
+        .projection(list_projection(fields.as_deref()))
         .build();
 
     // Get total count
@@ -126,7 +378,7 @@ pub async fn list_rodents(
 
     while let Some(doc) = cursor.try_next().await? {
         match bson::from_document::<Rodent>(doc.clone()) {
-            Ok(rodent) => rodents.push(RodentResponse::from(rodent)),
+            Ok(rodent) => rodents.push(trim_rodent_response(RodentResponse::from(rodent), fields.as_deref())?),
             Err(e) => {
                 tracing::error!("Failed to deserialize rodent: {:?}", e);
                 tracing::error!("Document was: {:?}", doc);
@@ -140,9 +392,346 @@ pub async fn list_rodents(
     Ok(Json(RodentListResponse {
         success: true,
         rodents,
-        total,
-        page,
-        limit,
+        pagination: Pagination::new(total, page, limit),
+    }))
+}
+
+/// List rodents that arrived in the last N days, newest first
+pub async fn list_recent_intakes(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<RecentIntakesQueryParams>,
+) -> Result<Json<RodentListResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "list_recent_intakes");
+    can_view(&auth_info)?;
+
+    let days = params.days.unwrap_or(30).clamp(1, 365);
+    let since = Utc::now() - chrono::Duration::days(days);
+    let mut filter = doc! { "intake_date": { "$gte": bson::DateTime::from_chrono(since) } };
+    if let Some(visibility) = rodent_visibility_filter(&auth_info) {
+        filter.extend(visibility);
+    }
+
+    // Pagination
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = page_limit(&state.config, params.limit, 20)?;
+    let skip = (page - 1) * limit;
+
+    let find_options = FindOptions::builder()
+        .skip(Some(skip as u64))
+        .limit(Some(limit as i64))
+        .sort(doc! { "intake_date": -1 })
+        .projection(without_image_data())
+        .build();
+
+    let collection = state.db.db.collection::<Rodent>("rodents");
+    let total = collection.count_documents(filter.clone(), None).await?;
+
+    let mut cursor = collection.find(filter, find_options).await?;
+    let mut rodents = Vec::new();
+    while let Some(rodent) = cursor.try_next().await? {
+        rodents.push(RodentResponse::from(rodent));
+    }
+
+    Ok(Json(RodentListResponse {
+        success: true,
+        rodents,
+        pagination: Pagination::new(total, page, limit),
+    }))
+}
+
+const MAX_BATCH_IDS: usize = 100;
+
+/// Fetch several rodents by ID in one query. Malformed or unknown IDs are
+/// reported in `missing_ids` rather than failing the request.
+pub async fn batch_get_rodents(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<BatchRodentsRequest>,
+) -> Result<Json<BatchRodentsResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_view(&auth_info)?;
+
+    if payload.ids.len() > MAX_BATCH_IDS {
+        return Err(AppError::ValidationError(format!(
+            "At most {} IDs can be fetched at once",
+            MAX_BATCH_IDS
+        )));
+    }
+
+    let object_ids: Vec<ObjectId> = payload
+        .ids
+        .iter()
+        .filter_map(|id| ObjectId::parse_str(id).ok())
+        .collect();
+
+    let mut filter = doc! { "_id": { "$in": &object_ids } };
+    if let Some(visibility) = rodent_visibility_filter(&auth_info) {
+        filter.extend(visibility);
+    }
+
+    let find_options = FindOptions::builder().projection(without_image_data()).build();
+
+    let collection = state.db.db.collection::<Rodent>("rodents");
+    let mut found = std::collections::HashMap::new();
+    let mut cursor = collection.find(filter, find_options).await?;
+    while let Some(rodent) = cursor.try_next().await? {
+        if let Some(id) = rodent.id {
+            found.insert(id.to_hex(), rodent);
+        }
+    }
+
+    let mut rodents = Vec::new();
+    let mut missing_ids = Vec::new();
+    for id in payload.ids {
+        match ObjectId::parse_str(&id).ok().and_then(|oid| found.get(&oid.to_hex()).cloned()) {
+            Some(rodent) => rodents.push(RodentResponse::from(rodent)),
+            None => missing_ids.push(id),
+        }
+    }
+
+    Ok(Json(BatchRodentsResponse {
+        success: true,
+        rodents,
+        missing_ids,
+    }))
+}
+
+/// Upper bound on IDs per existence check; only `_id` is read, so this can be
+/// well above `MAX_BATCH_IDS`
+const MAX_EXISTS_IDS: usize = 1000;
+
+/// Check which of the given IDs belong to a rodent, e.g. before a bulk import.
+/// Malformed IDs and rodents outside the caller's visibility report `false`.
+pub async fn check_rodents_exist(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<RodentsExistRequest>,
+) -> Result<Json<RodentsExistResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_view(&auth_info)?;
+
+    if payload.ids.len() > MAX_EXISTS_IDS {
+        return Err(AppError::ValidationError(format!(
+            "At most {} IDs can be checked at once",
+            MAX_EXISTS_IDS
+        )));
+    }
+
+    let object_ids: Vec<ObjectId> = payload
+        .ids
+        .iter()
+        .filter_map(|id| ObjectId::parse_str(id).ok())
+        .collect();
+
+    let mut filter = doc! { "_id": { "$in": &object_ids } };
+    if let Some(visibility) = rodent_visibility_filter(&auth_info) {
+        filter.extend(visibility);
+    }
+
+    let find_options = FindOptions::builder().projection(doc! { "_id": 1 }).build();
+
+    let mut found = std::collections::HashSet::new();
+    let mut cursor = state
+        .db
+        .db
+        .collection::<Document>("rodents")
+        .find(filter, find_options)
+        .await?;
+    while let Some(rodent) = cursor.try_next().await? {
+        if let Ok(id) = rodent.get_object_id("_id") {
+            found.insert(id);
+        }
+    }
+
+    let exists = payload
+        .ids
+        .into_iter()
+        .map(|id| {
+            let present = ObjectId::parse_str(&id).is_ok_and(|oid| found.contains(&oid));
+            (id, present)
+        })
+        .collect();
+
+    Ok(Json(RodentsExistResponse { success: true, exists }))
+}
+
+/// How long facet values are served from memory before being recomputed
+const FACETS_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Sorted distinct string values of `field` among rodents matching `filter`
+async fn distinct_strings(state: &AppState, field: &str, filter: Document) -> Result<Vec<String>, AppError> {
+    let mut values: Vec<String> = state
+        .db
+        .db
+        .collection::<Rodent>("rodents")
+        .distinct(field, filter, None)
+        .await?
+        .into_iter()
+        .filter_map(|value| value.as_str().map(str::to_string))
+        .collect();
+    values.sort();
+    Ok(values)
+}
+
+async fn load_rodent_facets(state: &AppState, filter: Document) -> Result<RodentFacets, AppError> {
+    Ok(RodentFacets {
+        species: distinct_strings(state, "species", filter.clone()).await?,
+        statuses: distinct_strings(state, "status", filter.clone()).await?,
+        enclosures: distinct_strings(state, "enclosure", filter).await?,
+    })
+}
+
+/// Distinct species, statuses and enclosures in use
+pub async fn get_rodent_facets(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<RodentFacetsResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_view(&auth_info)?;
+
+    // Only the unrestricted view is shared through the cache
+    if let Some(visibility) = rodent_visibility_filter(&auth_info) {
+        let facets = load_rodent_facets(&state, visibility).await?;
+        return Ok(Json(RodentFacetsResponse { success: true, facets }));
+    }
+
+    let cached = state
+        .facets_cache
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|(loaded_at, _)| loaded_at.elapsed() < FACETS_CACHE_TTL)
+        .map(|(_, facets)| facets.clone());
+
+    let facets = match cached {
+        Some(facets) => facets,
+        None => {
+            let facets = load_rodent_facets(&state, doc! {}).await?;
+            *state.facets_cache.lock().unwrap() = Some((std::time::Instant::now(), facets.clone()));
+            facets
+        }
+    };
+
+    Ok(Json(RodentFacetsResponse { success: true, facets }))
+}
+
+/// Activity-service counts from purging a set of rodents' tracking data
+#[derive(Debug, Default, serde::Deserialize)]
+struct ActivityPurgeCounts {
+    daily_records_deleted: u64,
+    activities_deleted: u64,
+    feeding_records_deleted: u64,
+}
+
+/// Ask the Activity Tracking Service to delete the tracking data of `rodent_ids`,
+/// acting on behalf of the calling admin
+async fn purge_activity_data(
+    state: &AppState,
+    headers: &HeaderMap,
+    rodent_ids: &[ObjectId],
+) -> Result<ActivityPurgeCounts, AppError> {
+    let url = format!("{}/api/activities/purge", state.config.activity_service_url);
+    let body = serde_json::json!({
+        "rodent_ids": rodent_ids.iter().map(|id| id.to_hex()).collect::<Vec<_>>(),
+    });
+
+    let mut request = state.http_client.post(&url).json(&body);
+    if let Some(auth) = headers.get(header::AUTHORIZATION).and_then(|h| h.to_str().ok()) {
+        request = request.header("Authorization", auth);
+    }
+
+    let response = request.send().await.map_err(|e| {
+        tracing::error!("Failed to connect to Activity Tracking Service: {}", e);
+        AppError::ServiceUnavailable("Activity Tracking Service unavailable".to_string())
+    })?;
+
+    if !response.status().is_success() {
+        tracing::error!("Activity Tracking Service refused purge: {}", response.status());
+        return Err(AppError::ServiceUnavailable(
+            "Activity Tracking Service could not purge sample data".to_string(),
+        ));
+    }
+
+    response.json().await.map_err(|e| {
+        tracing::error!("Failed to parse activity purge response: {}", e);
+        AppError::InternalError
+    })
+}
+
+/// Delete every rodent flagged as sample data, with its records here and its
+/// tracking data in the Activity Tracking Service (admin only)
+pub async fn purge_sample_data(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<PurgeSampleDataResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_purge_sample_data(&auth_info)?;
+
+    let rodent_collection = state.db.db.collection::<Document>("rodents");
+    let options = FindOptions::builder().projection(doc! { "_id": 1 }).build();
+    let sample_ids: Vec<ObjectId> = rodent_collection
+        .find(doc! { "is_sample": true }, options)
+        .await?
+        .try_collect::<Vec<Document>>()
+        .await?
+        .iter()
+        .filter_map(|d| d.get_object_id("_id").ok())
+        .collect();
+
+    // Tracking data goes first so a failure there leaves nothing half-deleted here
+    let activity_counts = if sample_ids.is_empty() {
+        ActivityPurgeCounts::default()
+    } else {
+        purge_activity_data(&state, &headers, &sample_ids).await?
+    };
+
+    let by_rodent = doc! { "rodent_id": { "$in": &sample_ids } };
+    let medical = state
+        .db
+        .db
+        .collection::<MedicalRecord>("medical_records")
+        .delete_many(by_rodent.clone(), None)
+        .await?;
+    let status_history = state
+        .db
+        .db
+        .collection::<StatusHistory>("status_history")
+        .delete_many(by_rodent.clone(), None)
+        .await?;
+    let enclosure_history = state
+        .db
+        .db
+        .collection::<EnclosureHistory>("enclosure_history")
+        .delete_many(by_rodent.clone(), None)
+        .await?;
+    let intake_changes = state
+        .db
+        .db
+        .collection::<IntakeDateChange>("intake_date_changes")
+        .delete_many(by_rodent, None)
+        .await?;
+    let rodents = rodent_collection
+        .delete_many(doc! { "_id": { "$in": &sample_ids } }, None)
+        .await?;
+
+    tracing::info!(
+        "{} sample rodents purged by user {}",
+        rodents.deleted_count,
+        auth_info.username
+    );
+
+    Ok(Json(PurgeSampleDataResponse {
+        success: true,
+        rodents_deleted: rodents.deleted_count,
+        medical_records_deleted: medical.deleted_count,
+        status_history_deleted: status_history.deleted_count,
+        enclosure_history_deleted: enclosure_history.deleted_count,
+        intake_date_changes_deleted: intake_changes.deleted_count,
+        daily_records_deleted: activity_counts.daily_records_deleted,
+        activities_deleted: activity_counts.activities_deleted,
+        feeding_records_deleted: activity_counts.feeding_records_deleted,
     }))
 }
 
@@ -151,22 +740,47 @@ pub async fn get_rodent(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Path(id): Path<String>,
-) -> Result<Json<SingleRodentResponse>, AppError> {
+) -> Result<Response, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
     can_view(&auth_info)?;
 
     let object_id = ObjectId::parse_str(&id).map_err(|_| AppError::InvalidRodentId)?;
     let collection = state.db.db.collection::<Rodent>("rodents");
 
+    // Rodents outside the caller's visibility look the same as missing ones
+    let mut filter = doc! { "_id": object_id };
+    if let Some(visibility) = rodent_visibility_filter(&auth_info) {
+        filter.extend(visibility);
+    }
+
     let rodent = collection
-        .find_one(doc! { "_id": object_id }, None)
+        .find_one(filter, None)
         .await?
         .ok_or(AppError::RodentNotFound)?;
 
-    Ok(Json(SingleRodentResponse {
+    let body = serde_json::to_vec(&SingleRodentResponse {
         success: true,
         rodent: RodentResponse::from(rodent),
-    }))
+    })
+    .map_err(|_| AppError::InternalError)?;
+
+    // Clients polling a profile revalidate with If-None-Match instead of re-downloading images
+    let etag = compute_etag(&body);
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        (header::CACHE_CONTROL, "private, no-cache".to_string()),
+    ];
+
+    if etag_matches(&headers, &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
+    Ok((
+        cache_headers,
+        [(header::CONTENT_TYPE, "application/json".to_string())],
+        body,
+    )
+        .into_response())
 }
 
 /// Create a new rodent
@@ -178,20 +792,27 @@ pub async fn create_rodent(
     let auth_info = extract_auth_info(&state, &headers)?;
     can_manage_rodents(&auth_info)?;
     payload.validate()?;
+    validate_intake_date(payload.intake_date)?;
+    let species = resolve_species(&state.config, payload.species)?;
 
     let now = Utc::now();
     let collection = state.db.db.collection::<Rodent>("rodents");
+    let first_note = new_note(payload.notes.as_deref(), &auth_info, now);
 
     let rodent = Rodent {
         id: None,
-        species: payload.species,
+        species,
         name: payload.name,
         gender: payload.gender,
         date_of_birth: payload.date_of_birth,
         date_of_birth_estimated: payload.date_of_birth_estimated,
         chip_id: payload.chip_id,
         status: payload.status,
-        notes: payload.notes,
+        notes: first_note.as_ref().map(|note| note.text.clone()),
+        notes_history: first_note.into_iter().collect(),
+        enclosure: payload.enclosure,
+        assigned_to: None,
+        is_sample: payload.is_sample,
         images: Vec::new(),
         intake_date: payload.intake_date.unwrap_or(now),
         created_at: now,
@@ -214,7 +835,7 @@ pub async fn create_rodent(
         rodent_id: inserted_id.to_hex(),
         name: created_rodent.name.clone(),
         species: created_rodent.species.as_str().to_string(),
-        gender: format!("{:?}", created_rodent.gender),
+        gender: created_rodent.gender.as_str().to_string(),
         date_of_birth: created_rodent.date_of_birth,
         intake_date: created_rodent.intake_date,
         status: created_rodent.status.as_str().to_string(),
@@ -234,6 +855,106 @@ pub async fn create_rodent(
     ))
 }
 
+/// Maximum number of litter-mates created by a single clone request
+const MAX_CLONE_COUNT: u32 = 20;
+
+/// Clone a rodent profile for litter-mates (never copies chip ID or images)
+pub async fn clone_rodent(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Query(params): Query<CloneRodentQueryParams>,
+) -> Result<(StatusCode, Json<CloneRodentResponse>), AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_manage_rodents(&auth_info)?;
+
+    let count = params.count.unwrap_or(1);
+    if count == 0 || count > MAX_CLONE_COUNT {
+        return Err(AppError::ValidationError(format!(
+            "Count must be between 1 and {}",
+            MAX_CLONE_COUNT
+        )));
+    }
+
+    let object_id = ObjectId::parse_str(&id).map_err(|_| AppError::InvalidRodentId)?;
+    let collection = state.db.db.collection::<Rodent>("rodents");
+
+    let source = collection
+        .find_one(doc! { "_id": object_id }, None)
+        .await?
+        .ok_or(AppError::RodentNotFound)?;
+
+    let now = Utc::now();
+    let clones: Vec<Rodent> = (1..=count)
+        .map(|n| Rodent {
+            id: None,
+            species: source.species.clone(),
+            name: format!("{} {}", source.name, n + 1),
+            gender: source.gender.clone(),
+            date_of_birth: source.date_of_birth,
+            date_of_birth_estimated: source.date_of_birth_estimated,
+            chip_id: None,
+            status: source.status.clone(),
+            notes: source.notes.clone(),
+            notes_history: source.notes_history.clone(),
+            enclosure: source.enclosure.clone(),
+            assigned_to: source.assigned_to.clone(),
+            is_sample: source.is_sample,
+            images: Vec::new(),
+            intake_date: source.intake_date,
+            created_at: now,
+            updated_at: now,
+            created_by: auth_info.user_id.clone(),
+            updated_by: auth_info.user_id.clone(),
+        })
+        .collect();
+
+    let result = collection.insert_many(&clones, None).await?;
+
+    let mut created_ids = Vec::with_capacity(clones.len());
+    for (index, rodent) in clones.iter().enumerate() {
+        let inserted_id = result
+            .inserted_ids
+            .get(&index)
+            .and_then(|id| id.as_object_id())
+            .ok_or(AppError::InternalError)?;
+
+        // Publish RodentRegistered event for each litter-mate
+        let event = RodentRegisteredEvent::new(RodentRegisteredPayload {
+            rodent_id: inserted_id.to_hex(),
+            name: rodent.name.clone(),
+            species: rodent.species.as_str().to_string(),
+            gender: rodent.gender.as_str().to_string(),
+            date_of_birth: rodent.date_of_birth,
+            intake_date: rodent.intake_date,
+            status: rodent.status.as_str().to_string(),
+            registered_by: auth_info.user_id.clone(),
+            registered_by_name: auth_info.username.clone(),
+        });
+        if let Err(e) = state.publisher.publish_rodent_registered(&event).await {
+            tracing::warn!("Failed to publish RodentRegistered event: {}", e);
+        }
+
+        created_ids.push(inserted_id.to_hex());
+    }
+
+    tracing::info!(
+        "Rodent {} cloned {} times by user {}",
+        id,
+        count,
+        auth_info.username
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CloneRodentResponse {
+            success: true,
+            source_id: id,
+            created_ids,
+        }),
+    ))
+}
+
 /// Update a rodent
 pub async fn update_rodent(
     State(state): State<Arc<AppState>>,
@@ -244,6 +965,7 @@ pub async fn update_rodent(
     let auth_info = extract_auth_info(&state, &headers)?;
     can_manage_rodents(&auth_info)?;
     payload.validate()?;
+    validate_intake_date(payload.intake_date)?;
 
     let object_id = ObjectId::parse_str(&id).map_err(|_| AppError::InvalidRodentId)?;
     let collection = state.db.db.collection::<Rodent>("rodents");
@@ -254,20 +976,32 @@ pub async fn update_rodent(
         .await?
         .ok_or(AppError::RodentNotFound)?;
 
+    let now = Utc::now();
+
     // Build update document
     let mut update_doc = doc! {
-        "updated_at": Utc::now(),
+        "updated_at": now,
         "updated_by": &auth_info.user_id,
     };
 
-    if let Some(species) = &payload.species {
-        update_doc.insert("species", species.as_str());
+    if let Some(species) = payload.species.clone() {
+        update_doc.insert("species", resolve_species(&state.config, species)?.as_str());
     }
     if let Some(name) = &payload.name {
         update_doc.insert("name", name);
     }
     if let Some(gender) = &payload.gender {
-        update_doc.insert("gender", bson::to_bson(gender).map_err(|_| AppError::InternalError)?);
+        // Correcting a recorded sex is rare enough to leave a trace
+        if existing.gender != Gender::Unknown && *gender != existing.gender {
+            tracing::warn!(
+                "Gender of rodent {} changed from {} to {} by user {}",
+                id,
+                existing.gender.as_str(),
+                gender.as_str(),
+                auth_info.username
+            );
+        }
+        update_doc.insert("gender", gender.as_str());
     }
     if let Some(date_of_birth) = payload.date_of_birth {
         update_doc.insert("date_of_birth", date_of_birth);
@@ -275,18 +1009,56 @@ pub async fn update_rodent(
     if let Some(estimated) = payload.date_of_birth_estimated {
         update_doc.insert("date_of_birth_estimated", estimated);
     }
+    if let Some(is_sample) = payload.is_sample {
+        update_doc.insert("is_sample", is_sample);
+    }
     if payload.chip_id.is_some() || existing.chip_id.is_some() {
         update_doc.insert("chip_id", &payload.chip_id);
     }
-    if payload.notes.is_some() || existing.notes.is_some() {
-        update_doc.insert("notes", &payload.notes);
+
+    // Notes are append-only: a changed note joins the history and becomes the latest
+    let note = new_note(payload.notes.as_deref(), &auth_info, now)
+        .filter(|note| existing.notes.as_deref() != Some(note.text.as_str()));
+    if let Some(note) = &note {
+        update_doc.insert("notes", &note.text);
+    }
+
+    // Intake date feeds population analytics, so keep an audit trail of corrections
+    let intake_change = payload
+        .intake_date
+        .filter(|new_date| *new_date != existing.intake_date)
+        .map(|new_date| IntakeDateChange {
+            id: None,
+            rodent_id: object_id,
+            old_intake_date: existing.intake_date,
+            new_intake_date: new_date,
+            changed_by: auth_info.user_id.clone(),
+            changed_by_name: auth_info.username.clone(),
+            changed_at: now,
+        });
+    if let Some(change) = &intake_change {
+        update_doc.insert("intake_date", change.new_intake_date);
+    }
+
+    let mut update = doc! { "$set": update_doc };
+    if let Some(note) = note {
+        update.insert("$push", doc! { "notes_history": bson::to_bson(&note).map_err(|_| AppError::InternalError)? });
     }
 
     collection
-        .update_one(doc! { "_id": object_id }, doc! { "$set": update_doc }, None)
+        .update_one(doc! { "_id": object_id }, update, None)
         .await?;
 
-    // Fetch updated rodent
+    if let Some(change) = intake_change {
+        state
+            .db
+            .db
+            .collection::<IntakeDateChange>("intake_date_changes")
+            .insert_one(&change, None)
+            .await?;
+    }
+
+    // Fetch updated rodent
     let updated_rodent = collection
         .find_one(doc! { "_id": object_id }, None)
         .await?
@@ -298,18 +1070,14 @@ pub async fn update_rodent(
     }))
 }
 
-/// Update rodent status
-pub async fn update_rodent_status(
-    State(state): State<Arc<AppState>>,
-    headers: HeaderMap,
-    Path(id): Path<String>,
-    Json(payload): Json<UpdateRodentStatusRequest>,
-) -> Result<Json<SingleRodentResponse>, AppError> {
-    let auth_info = extract_auth_info(&state, &headers)?;
-    can_manage_rodents(&auth_info)?;
-    payload.validate()?;
-
-    let object_id = ObjectId::parse_str(&id).map_err(|_| AppError::InvalidRodentId)?;
+/// Set a rodent's status, record the change in its history and publish the event
+async fn apply_status_change(
+    state: &AppState,
+    auth_info: &AuthInfo,
+    object_id: ObjectId,
+    status: RodentStatus,
+    reason: Option<String>,
+) -> Result<Rodent, AppError> {
     let rodent_collection = state.db.db.collection::<Rodent>("rodents");
     let history_collection = state.db.db.collection::<StatusHistory>("status_history");
 
@@ -327,8 +1095,8 @@ pub async fn update_rodent_status(
         id: None,
         rodent_id: object_id,
         old_status,
-        new_status: payload.status.clone(),
-        reason: payload.reason,
+        new_status: status.clone(),
+        reason,
         changed_by: auth_info.user_id.clone(),
         changed_by_name: auth_info.username.clone(),
         changed_at: now,
@@ -342,7 +1110,7 @@ pub async fn update_rodent_status(
             doc! { "_id": object_id },
             doc! {
                 "$set": {
-                    "status": payload.status.as_str(),
+                    "status": status.as_str(),
                     "updated_at": now,
                     "updated_by": &auth_info.user_id,
                 }
@@ -370,12 +1138,84 @@ pub async fn update_rodent_status(
         tracing::warn!("Failed to publish RodentStatusChanged event: {}", e);
     }
 
+    Ok(updated_rodent)
+}
+
+/// Update rodent status
+pub async fn update_rodent_status(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateRodentStatusRequest>,
+) -> Result<Json<SingleRodentResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_manage_rodents(&auth_info)?;
+    payload.validate()?;
+
+    let object_id = ObjectId::parse_str(&id).map_err(|_| AppError::InvalidRodentId)?;
+    let updated_rodent =
+        apply_status_change(&state, &auth_info, object_id, payload.status, payload.reason).await?;
+
     Ok(Json(SingleRodentResponse {
         success: true,
         rodent: RodentResponse::from(updated_rodent),
     }))
 }
 
+/// Set the same status on several rodents. Each rodent gets its own history
+/// entry and event; a failure on one ID doesn't stop the rest.
+pub async fn bulk_update_rodent_status(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<BulkUpdateStatusRequest>,
+) -> Result<Json<BulkUpdateStatusResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_manage_rodents(&auth_info)?;
+    payload.validate()?;
+
+    if payload.rodent_ids.len() > MAX_BATCH_IDS {
+        return Err(AppError::ValidationError(format!(
+            "At most {} rodents can be updated at once",
+            MAX_BATCH_IDS
+        )));
+    }
+
+    let mut results = Vec::with_capacity(payload.rodent_ids.len());
+    for rodent_id in payload.rodent_ids {
+        let outcome = match ObjectId::parse_str(&rodent_id) {
+            Ok(object_id) => {
+                apply_status_change(&state, &auth_info, object_id, payload.status.clone(), payload.reason.clone())
+                    .await
+                    .map(|_| ())
+            }
+            Err(_) => Err(AppError::InvalidRodentId),
+        };
+
+        let error = match outcome {
+            Ok(()) => None,
+            Err(AppError::DatabaseError(e)) => {
+                tracing::error!("Database error updating status of rodent {}: {}", rodent_id, e);
+                Some("Database error".to_string())
+            }
+            Err(e) => Some(e.to_string()),
+        };
+
+        results.push(BulkStatusResult {
+            success: error.is_none(),
+            rodent_id,
+            error,
+        });
+    }
+
+    let updated = results.iter().filter(|r| r.success).count();
+    Ok(Json(BulkUpdateStatusResponse {
+        success: true,
+        updated,
+        failed: results.len() - updated,
+        results,
+    }))
+}
+
 /// Delete a rodent
 pub async fn delete_rodent(
     State(state): State<Arc<AppState>>,
@@ -397,6 +1237,8 @@ pub async fn delete_rodent(
     // Also delete related medical records and status history
     let medical_collection = state.db.db.collection::<MedicalRecord>("medical_records");
     let history_collection = state.db.db.collection::<StatusHistory>("status_history");
+    let enclosure_history_collection = state.db.db.collection::<EnclosureHistory>("enclosure_history");
+    let intake_changes_collection = state.db.db.collection::<IntakeDateChange>("intake_date_changes");
 
     medical_collection
         .delete_many(doc! { "rodent_id": object_id }, None)
@@ -404,6 +1246,12 @@ pub async fn delete_rodent(
     history_collection
         .delete_many(doc! { "rodent_id": object_id }, None)
         .await?;
+    enclosure_history_collection
+        .delete_many(doc! { "rodent_id": object_id }, None)
+        .await?;
+    intake_changes_collection
+        .delete_many(doc! { "rodent_id": object_id }, None)
+        .await?;
 
     tracing::info!(
         "Rodent {} deleted by user {}",
@@ -422,42 +1270,469 @@ pub async fn get_rodent_status_history(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Path(id): Path<String>,
+    Query(params): Query<StatusHistoryQueryParams>,
 ) -> Result<Json<StatusHistoryListResponse>, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
     can_view(&auth_info)?;
 
     let object_id = ObjectId::parse_str(&id).map_err(|_| AppError::InvalidRodentId)?;
 
-    // Verify rodent exists
+    // Verify rodent exists; rodents outside the caller's visibility look missing
+    let mut rodent_filter = doc! { "_id": object_id };
+    if let Some(visibility) = rodent_visibility_filter(&auth_info) {
+        rodent_filter.extend(visibility);
+    }
     let rodent_collection = state.db.db.collection::<Rodent>("rodents");
     rodent_collection
-        .find_one(doc! { "_id": object_id }, None)
+        .find_one(rodent_filter, None)
         .await?
         .ok_or(AppError::RodentNotFound)?;
 
     let collection = state.db.db.collection::<StatusHistory>("status_history");
+    let filter = doc! { "rodent_id": object_id };
+
+    // Pagination
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = page_limit(&state.config, params.limit, 20)?;
+    let skip = (page - 1) * limit;
 
     let find_options = FindOptions::builder()
+        .skip(Some(skip as u64))
+        .limit(Some(limit as i64))
         .sort(doc! { "changed_at": -1 })
         .build();
 
-    let mut cursor = collection
-        .find(doc! { "rodent_id": object_id }, find_options)
-        .await?;
+    let total = collection.count_documents(filter.clone(), None).await?;
+
+    let mut cursor = collection.find(filter, find_options).await?;
+
+    let mut history = Vec::new();
+    while let Some(record) = cursor.try_next().await? {
+        history.push(StatusHistoryResponse::from(record));
+    }
+
+    Ok(Json(StatusHistoryListResponse {
+        success: true,
+        history,
+        pagination: Pagination::new(total, page, limit),
+    }))
+}
+
+// ============== Assignment Handlers ==============
+
+/// Assign a rodent to a volunteer, or clear the assignment with `null`
+pub async fn assign_rodent(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(payload): Json<AssignRodentRequest>,
+) -> Result<Json<SingleRodentResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_manage_assignments(&auth_info)?;
+    payload.validate()?;
+
+    let object_id = ObjectId::parse_str(&id).map_err(|_| AppError::InvalidRodentId)?;
+    let collection = state.db.db.collection::<Rodent>("rodents");
+
+    let result = collection
+        .update_one(
+            doc! { "_id": object_id },
+            doc! {
+                "$set": {
+                    "assigned_to": &payload.assigned_to,
+                    "updated_at": Utc::now(),
+                    "updated_by": &auth_info.user_id,
+                }
+            },
+            None,
+        )
+        .await?;
+
+    if result.matched_count == 0 {
+        return Err(AppError::RodentNotFound);
+    }
+
+    let updated_rodent = collection
+        .find_one(doc! { "_id": object_id }, None)
+        .await?
+        .ok_or(AppError::InternalError)?;
+
+    tracing::info!(
+        "Rodent {} assigned to {:?} by user {}",
+        id,
+        payload.assigned_to,
+        auth_info.username
+    );
+
+    Ok(Json(SingleRodentResponse {
+        success: true,
+        rodent: RodentResponse::from(updated_rodent),
+    }))
+}
+
+/// Version of the export bundle layout, bumped on incompatible changes
+const EXPORT_BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Export a rodent with its medical records and status history as one JSON
+/// document for transfer to another shelter
+pub async fn export_rodent_bundle(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Query(params): Query<ExportBundleQueryParams>,
+) -> Result<Response, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_manage_rodents(&auth_info)?;
+
+    let object_id = ObjectId::parse_str(&id).map_err(|_| AppError::InvalidRodentId)?;
+
+    let rodent_options = if params.include_images.unwrap_or(false) {
+        None
+    } else {
+        Some(FindOneOptions::builder().projection(without_image_data()).build())
+    };
+
+    let rodent = state
+        .db
+        .db
+        .collection::<Rodent>("rodents")
+        .find_one(doc! { "_id": object_id }, rodent_options)
+        .await?
+        .ok_or(AppError::RodentNotFound)?;
+
+    let medical_records: Vec<MedicalRecord> = state
+        .db
+        .db
+        .collection::<MedicalRecord>("medical_records")
+        .find(
+            doc! { "rodent_id": object_id },
+            FindOptions::builder().sort(doc! { "date": 1 }).build(),
+        )
+        .await?
+        .try_collect()
+        .await?;
+
+    let status_history: Vec<StatusHistory> = state
+        .db
+        .db
+        .collection::<StatusHistory>("status_history")
+        .find(
+            doc! { "rodent_id": object_id },
+            FindOptions::builder().sort(doc! { "changed_at": 1 }).build(),
+        )
+        .await?
+        .try_collect()
+        .await?;
+
+    let bundle = RodentExportBundle {
+        format_version: EXPORT_BUNDLE_FORMAT_VERSION,
+        exported_at: Utc::now(),
+        exported_by: auth_info.username.clone(),
+        rodent: RodentResponse::from(rodent),
+        medical_records: medical_records.into_iter().map(MedicalRecordResponse::from).collect(),
+        status_history: status_history.into_iter().map(StatusHistoryResponse::from).collect(),
+    };
+
+    tracing::info!("Rodent {} exported as bundle by user {}", id, auth_info.username);
+
+    let disposition = format!("attachment; filename=\"rodent-{}.json\"", id);
+
+    Ok(([(header::CONTENT_DISPOSITION, disposition)], Json(bundle)).into_response())
+}
+
+/// Import a rodent from an export bundle (not supported yet)
+pub async fn import_rodent_bundle(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<MessageResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_manage_rodents(&auth_info)?;
+
+    Err(AppError::NotImplemented(
+        "Importing rodent bundles is not supported yet".to_string(),
+    ))
+}
+
+/// Merge a duplicate rodent profile into the primary one. Medical records,
+/// status history, enclosure history and intake date changes are reassigned
+/// and images appended before the duplicate is deleted.
+pub async fn merge_rodents(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<MergeRodentsRequest>,
+) -> Result<Json<MergeRodentsResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_merge_rodents(&auth_info)?;
+
+    let primary_id = ObjectId::parse_str(&payload.primary_id).map_err(|_| AppError::InvalidRodentId)?;
+    let duplicate_id = ObjectId::parse_str(&payload.duplicate_id).map_err(|_| AppError::InvalidRodentId)?;
+    if primary_id == duplicate_id {
+        return Err(AppError::ValidationError(
+            "Primary and duplicate must be different rodents".to_string(),
+        ));
+    }
+
+    let collection = state.db.db.collection::<Rodent>("rodents");
+
+    let primary = collection
+        .find_one(doc! { "_id": primary_id }, None)
+        .await?
+        .ok_or(AppError::RodentNotFound)?;
+    let duplicate = collection
+        .find_one(doc! { "_id": duplicate_id }, None)
+        .await?
+        .ok_or(AppError::RodentNotFound)?;
+
+    // Reassign everything that references the duplicate before it goes away
+    let reassign_filter = doc! { "rodent_id": duplicate_id };
+    let reassign_update = doc! { "$set": { "rodent_id": primary_id } };
+
+    let medical_records_moved = state
+        .db
+        .db
+        .collection::<MedicalRecord>("medical_records")
+        .update_many(reassign_filter.clone(), reassign_update.clone(), None)
+        .await?
+        .modified_count;
+    let status_history_moved = state
+        .db
+        .db
+        .collection::<StatusHistory>("status_history")
+        .update_many(reassign_filter.clone(), reassign_update.clone(), None)
+        .await?
+        .modified_count;
+    let enclosure_history_moved = state
+        .db
+        .db
+        .collection::<EnclosureHistory>("enclosure_history")
+        .update_many(reassign_filter.clone(), reassign_update.clone(), None)
+        .await?
+        .modified_count;
+    let intake_date_changes_moved = state
+        .db
+        .db
+        .collection::<IntakeDateChange>("intake_date_changes")
+        .update_many(reassign_filter, reassign_update, None)
+        .await?
+        .modified_count;
+
+    // The primary keeps its own primary image; one is only carried over if it has none
+    let mut has_primary_image = primary.images.iter().any(|img| img.is_primary);
+    let images: Vec<RodentImage> = duplicate
+        .images
+        .into_iter()
+        .map(|mut img| {
+            img.is_primary = img.is_primary && !has_primary_image;
+            has_primary_image |= img.is_primary;
+            img
+        })
+        .collect();
+    let images_moved = images.len();
+
+    collection
+        .update_one(
+            doc! { "_id": primary_id },
+            doc! {
+                "$push": { "images": { "$each": bson::to_bson(&images).map_err(|_| AppError::InternalError)? } },
+                "$set": { "updated_at": Utc::now(), "updated_by": &auth_info.user_id }
+            },
+            None,
+        )
+        .await?;
+
+    collection.delete_one(doc! { "_id": duplicate_id }, None).await?;
+
+    tracing::info!(
+        "Rodent {} merged into {} by user {} ({} medical records, {} status changes, {} images moved)",
+        payload.duplicate_id,
+        payload.primary_id,
+        auth_info.username,
+        medical_records_moved,
+        status_history_moved,
+        images_moved
+    );
+
+    Ok(Json(MergeRodentsResponse {
+        success: true,
+        primary_id: payload.primary_id,
+        deleted_id: payload.duplicate_id,
+        medical_records_moved,
+        status_history_moved,
+        enclosure_history_moved,
+        intake_date_changes_moved,
+        images_moved,
+    }))
+}
+
+// ============== Enclosure Handlers ==============
+
+/// Move a rodent to another enclosure
+pub async fn move_rodent(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(payload): Json<MoveRodentRequest>,
+) -> Result<Json<SingleRodentResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_manage_rodents(&auth_info)?;
+    payload.validate()?;
+
+    let object_id = ObjectId::parse_str(&id).map_err(|_| AppError::InvalidRodentId)?;
+    let rodent_collection = state.db.db.collection::<Rodent>("rodents");
+    let history_collection = state.db.db.collection::<EnclosureHistory>("enclosure_history");
+
+    // Get current rodent
+    let rodent = rodent_collection
+        .find_one(doc! { "_id": object_id }, None)
+        .await?
+        .ok_or(AppError::RodentNotFound)?;
+
+    if rodent.enclosure.as_deref() == Some(payload.to_enclosure.as_str()) {
+        return Err(AppError::ValidationError(
+            "Rodent is already in this enclosure".to_string(),
+        ));
+    }
+
+    let now = Utc::now();
+
+    // Record the move in history
+    let history = EnclosureHistory {
+        id: None,
+        rodent_id: object_id,
+        from_enclosure: rodent.enclosure,
+        to_enclosure: payload.to_enclosure.clone(),
+        reason: payload.reason,
+        moved_by: auth_info.user_id.clone(),
+        moved_by_name: auth_info.username.clone(),
+        moved_at: now,
+    };
+
+    history_collection.insert_one(&history, None).await?;
+
+    // Update rodent enclosure
+    rodent_collection
+        .update_one(
+            doc! { "_id": object_id },
+            doc! {
+                "$set": {
+                    "enclosure": &payload.to_enclosure,
+                    "updated_at": now,
+                    "updated_by": &auth_info.user_id,
+                }
+            },
+            None,
+        )
+        .await?;
+
+    // Fetch updated rodent
+    let updated_rodent = rodent_collection
+        .find_one(doc! { "_id": object_id }, None)
+        .await?
+        .ok_or(AppError::InternalError)?;
+
+    tracing::info!(
+        "Rodent {} moved from {:?} to {} by user {}",
+        id,
+        history.from_enclosure,
+        history.to_enclosure,
+        auth_info.username
+    );
+
+    Ok(Json(SingleRodentResponse {
+        success: true,
+        rodent: RodentResponse::from(updated_rodent),
+    }))
+}
+
+/// Get rodent enclosure history
+pub async fn get_rodent_enclosure_history(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<EnclosureHistoryListResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_view(&auth_info)?;
+
+    let object_id = ObjectId::parse_str(&id).map_err(|_| AppError::InvalidRodentId)?;
+
+    // Verify rodent exists; rodents outside the caller's visibility look missing
+    let mut rodent_filter = doc! { "_id": object_id };
+    if let Some(visibility) = rodent_visibility_filter(&auth_info) {
+        rodent_filter.extend(visibility);
+    }
+    let rodent_collection = state.db.db.collection::<Rodent>("rodents");
+    rodent_collection
+        .find_one(rodent_filter, None)
+        .await?
+        .ok_or(AppError::RodentNotFound)?;
+
+    let collection = state.db.db.collection::<EnclosureHistory>("enclosure_history");
+
+    let find_options = FindOptions::builder()
+        .sort(doc! { "moved_at": -1 })
+        .build();
+
+    let mut cursor = collection
+        .find(doc! { "rodent_id": object_id }, find_options)
+        .await?;
+
+    let mut history = Vec::new();
+    while let Some(record) = cursor.try_next().await? {
+        history.push(EnclosureHistoryResponse::from(record));
+    }
+
+    Ok(Json(EnclosureHistoryListResponse {
+        success: true,
+        history,
+    }))
+}
+
+/// List a rodent's notes, newest first
+pub async fn get_rodent_notes(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<RodentNotesResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_view(&auth_info)?;
+
+    let object_id = ObjectId::parse_str(&id).map_err(|_| AppError::InvalidRodentId)?;
 
-    let mut history = Vec::new();
-    while let Some(record) = cursor.try_next().await? {
-        history.push(StatusHistoryResponse::from(record));
+    let mut filter = doc! { "_id": object_id };
+    if let Some(visibility) = rodent_visibility_filter(&auth_info) {
+        filter.extend(visibility);
     }
 
-    Ok(Json(StatusHistoryListResponse {
+    let rodent = state
+        .db
+        .db
+        .collection::<Rodent>("rodents")
+        .find_one(filter, FindOneOptions::builder().projection(without_image_data()).build())
+        .await?
+        .ok_or(AppError::RodentNotFound)?;
+
+    Ok(Json(RodentNotesResponse {
         success: true,
-        history,
+        rodent_id: id,
+        notes: rodent
+            .notes_history
+            .into_iter()
+            .rev()
+            .map(RodentNoteResponse::from)
+            .collect(),
     }))
 }
 
 // ============== Image Handlers ==============
 
+/// Hitting the upload body limit mid-stream is a 413, anything else a bad request
+fn multipart_error(e: axum::extract::multipart::MultipartError, context: &str) -> AppError {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        return AppError::PayloadTooLarge;
+    }
+    AppError::ValidationError(format!("{}: {}", context, e))
+}
+
 /// Upload an image for a rodent
 pub async fn upload_rodent_image(
     State(state): State<Arc<AppState>>,
@@ -481,9 +1756,11 @@ pub async fn upload_rodent_image(
     let mut image_data: Option<(String, String, Vec<u8>)> = None;
     let mut is_primary = rodent.images.is_empty(); // First image is primary by default
 
-    while let Some(field) = multipart.next_field().await.map_err(|e| {
-        AppError::ValidationError(format!("Failed to read multipart field: {}", e))
-    })? {
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| multipart_error(e, "Failed to read multipart field"))?
+    {
         let name = field.name().unwrap_or("").to_string();
 
         if name == "image" {
@@ -498,23 +1775,28 @@ pub async fn upload_rodent_image(
                 .unwrap_or_else(|| "image/jpeg".to_string());
 
             // Validate content type
-            let valid_types = ["image/jpeg", "image/png", "image/gif", "image/webp"];
-            if !valid_types.contains(&content_type.as_str()) {
+            let valid_types = &state.config.allowed_image_types;
+            if !valid_types.contains(&content_type.to_lowercase()) {
                 return Err(AppError::InvalidImageFormat(format!(
                     "Supported formats: {}",
                     valid_types.join(", ")
                 )));
             }
 
-            let data = field.bytes().await.map_err(|e| {
-                AppError::ValidationError(format!("Failed to read image data: {}", e))
-            })?;
-
-            if data.len() > max_size {
-                return Err(AppError::ImageTooLarge(state.config.max_image_size_mb));
+            // Check the size while reading so an oversized image is dropped early
+            let mut data = Vec::new();
+            while let Some(chunk) = field
+                .chunk()
+                .await
+                .map_err(|e| multipart_error(e, "Failed to read image data"))?
+            {
+                if data.len() + chunk.len() > max_size {
+                    return Err(AppError::ImageTooLarge(state.config.max_image_size_mb));
+                }
+                data.extend_from_slice(&chunk);
             }
 
-            image_data = Some((filename, content_type, data.to_vec()));
+            image_data = Some((filename, content_type, data));
         } else if name == "is_primary" {
             let value = field.text().await.unwrap_or_default();
             is_primary = value == "true" || value == "1";
@@ -599,43 +1881,133 @@ pub async fn delete_rodent_image(
     }))
 }
 
-/// Set an image as primary
-pub async fn set_primary_image(
+/// Set the display order of a rodent's images. The IDs must be exactly the
+/// rodent's current images, each listed once.
+pub async fn reorder_rodent_images(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-    Path((rodent_id, image_id)): Path<(String, String)>,
+    Path(id): Path<String>,
+    Json(payload): Json<ReorderImagesRequest>,
 ) -> Result<Json<MessageResponse>, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
     can_manage_rodents(&auth_info)?;
 
-    let object_id = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+    let object_id = ObjectId::parse_str(&id).map_err(|_| AppError::InvalidRodentId)?;
     let collection = state.db.db.collection::<Rodent>("rodents");
 
-    // First, unset all primary flags
-    collection
+    let options = FindOneOptions::builder().projection(without_image_data()).build();
+    let rodent = collection
+        .find_one(doc! { "_id": object_id }, options)
+        .await?
+        .ok_or(AppError::RodentNotFound)?;
+
+    let mut requested: Vec<&str> = payload.image_ids.iter().map(String::as_str).collect();
+    let mut existing: Vec<&str> = rodent.images.iter().map(|img| img.id.as_str()).collect();
+    requested.sort_unstable();
+    existing.sort_unstable();
+    if requested != existing {
+        return Err(AppError::ValidationError(
+            "image_ids must list each of the rodent's images exactly once".to_string(),
+        ));
+    }
+
+    if payload.image_ids.is_empty() {
+        return Ok(Json(MessageResponse {
+            success: true,
+            message: "Image order updated successfully".to_string(),
+        }));
+    }
+
+    // Rebuild the array server-side so the base64 payloads never leave the database.
+    // The filter re-checks the image set in case an upload or delete slipped in.
+    let reorder = vec![doc! {
+        "$set": {
+            "images": {
+                "$map": {
+                    "input": &payload.image_ids,
+                    "as": "image_id",
+                    "in": {
+                        "$arrayElemAt": [
+                            { "$filter": { "input": "$images", "cond": { "$eq": ["$$this.id", "$$image_id"] } } },
+                            0
+                        ]
+                    }
+                }
+            },
+            "updated_at": bson::DateTime::from_chrono(Utc::now()),
+            "updated_by": &auth_info.user_id,
+        }
+    }];
+
+    let result = collection
         .update_one(
-            doc! { "_id": object_id },
-            doc! { "$set": { "images.$[].is_primary": false } },
+            doc! {
+                "_id": object_id,
+                "images": { "$size": payload.image_ids.len() as i64 },
+                "images.id": { "$all": &payload.image_ids },
+            },
+            UpdateModifications::Pipeline(reorder),
             None,
         )
         .await?;
 
-    // Then set the specified image as primary
+    if result.matched_count == 0 {
+        return Err(AppError::ValidationError(
+            "The rodent's images changed while reordering; reload and try again".to_string(),
+        ));
+    }
+
+    Ok(Json(MessageResponse {
+        success: true,
+        message: "Image order updated successfully".to_string(),
+    }))
+}
+
+/// Flip every image's flag in one pipeline update, so concurrent switches
+/// can't interleave and leave zero or two primaries. Each flag depends only on
+/// the image's id, never on the previous flags, so the last switch wins outright.
+fn primary_image_update(image_id: &str, user_id: &str) -> Vec<Document> {
+    vec![doc! {
+        "$set": {
+            "images": {
+                "$map": {
+                    "input": "$images",
+                    "as": "image",
+                    "in": {
+                        "$mergeObjects": [
+                            "$$image",
+                            { "is_primary": { "$eq": ["$$image.id", image_id] } }
+                        ]
+                    }
+                }
+            },
+            "updated_at": Utc::now(),
+            "updated_by": user_id
+        }
+    }]
+}
+
+/// Set an image as primary
+pub async fn set_primary_image(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((rodent_id, image_id)): Path<(String, String)>,
+) -> Result<Json<MessageResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_manage_rodents(&auth_info)?;
+
+    let object_id = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+    let collection = state.db.db.collection::<Rodent>("rodents");
+
     let result = collection
         .update_one(
             doc! { "_id": object_id, "images.id": &image_id },
-            doc! {
-                "$set": {
-                    "images.$.is_primary": true,
-                    "updated_at": Utc::now(),
-                    "updated_by": &auth_info.user_id
-                }
-            },
+            primary_image_update(&image_id, &auth_info.user_id),
             None,
         )
         .await?;
 
-    if result.modified_count == 0 {
+    if result.matched_count == 0 {
         return Err(AppError::RodentNotFound);
     }
 
@@ -645,8 +2017,143 @@ pub async fn set_primary_image(
     }))
 }
 
+/// Zip entry name for an uploaded image. The filename comes from the client,
+/// so only its last path component is kept; otherwise `../x` or an absolute
+/// path would escape the directory the archive is extracted into.
+fn archive_entry_name(filename: &str, image_id: &str) -> String {
+    let name = filename.rsplit(['/', '\\', ':']).next().unwrap_or("").trim();
+    match name {
+        "" | "." | ".." => format!("image-{}", image_id),
+        name => name.to_string(),
+    }
+}
+
+/// Download all images of a rodent as a zip archive. The archive is built in
+/// memory rather than streamed; the images are embedded in the rodent document,
+/// so it is bounded by MongoDB's document size limit.
+pub async fn download_rodent_images(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(rodent_id): Path<String>,
+) -> Result<Response, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_view(&auth_info)?;
+
+    let object_id = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+
+    let mut filter = doc! { "_id": object_id };
+    if let Some(visibility) = rodent_visibility_filter(&auth_info) {
+        filter.extend(visibility);
+    }
+
+    let rodent = state
+        .db
+        .db
+        .collection::<Rodent>("rodents")
+        .find_one(filter, None)
+        .await?
+        .ok_or(AppError::RodentNotFound)?;
+
+    if rodent.images.is_empty() {
+        return Err(AppError::NoImages);
+    }
+
+    let mut archive = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    // Images are already compressed formats, so they are stored as-is
+    let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let mut used_names = std::collections::HashSet::new();
+
+    for image in &rodent.images {
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(&image.data)
+            .map_err(|e| {
+                tracing::error!("Failed to decode image {} of rodent {}: {}", image.id, rodent_id, e);
+                AppError::InternalError
+            })?;
+
+        // Two uploads can share a filename; prefix duplicates with the image id
+        let name = archive_entry_name(&image.filename, &image.id);
+        let filename = if used_names.insert(name.clone()) {
+            name
+        } else {
+            format!("{}-{}", image.id, name)
+        };
+
+        archive.start_file(filename, options).map_err(zip_error)?;
+        archive.write_all(&data).map_err(|e| {
+            tracing::error!("Failed to write image archive: {}", e);
+            AppError::InternalError
+        })?;
+    }
+
+    let bytes = archive.finish().map_err(zip_error)?.into_inner();
+    let disposition = format!("attachment; filename=\"rodent-{}-images.zip\"", rodent_id);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+fn zip_error(e: zip::result::ZipError) -> AppError {
+    tracing::error!("Failed to build image archive: {}", e);
+    AppError::InternalError
+}
+
 // ============== Medical Record Handlers ==============
 
+/// Export a rodent's profile and full medical history as a PDF
+pub async fn export_medical_history_pdf(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(rodent_id): Path<String>,
+) -> Result<Response, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_view(&auth_info)?;
+
+    let object_id = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+
+    let mut rodent_filter = doc! { "_id": object_id };
+    if let Some(visibility) = rodent_visibility_filter(&auth_info) {
+        rodent_filter.extend(visibility);
+    }
+
+    let rodent = state
+        .db
+        .db
+        .collection::<Rodent>("rodents")
+        .find_one(rodent_filter, FindOneOptions::builder().projection(without_image_data()).build())
+        .await?
+        .ok_or(AppError::RodentNotFound)?;
+
+    let find_options = FindOptions::builder().sort(doc! { "date": 1 }).build();
+    let records: Vec<MedicalRecord> = state
+        .db
+        .db
+        .collection::<MedicalRecord>("medical_records")
+        .find(doc! { "rodent_id": object_id }, find_options)
+        .await?
+        .try_collect()
+        .await?;
+
+    let pdf = crate::pdf::render_medical_history(&rodent, &records, &auth_info.username, Utc::now())?;
+
+    let disposition = format!("attachment; filename=\"medical-history-{}.pdf\"", rodent_id);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        pdf,
+    )
+        .into_response())
+}
+
 /// List medical records for a rodent
 pub async fn list_medical_records(
     State(state): State<Arc<AppState>>,
@@ -655,14 +2162,20 @@ pub async fn list_medical_records(
     Query(params): Query<MedicalRecordQueryParams>,
 ) -> Result<Json<MedicalRecordListResponse>, AppError> {
     let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "list_medical_records");
     can_view(&auth_info)?;
 
     let object_id = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
+    validate_date_range(params.from_date, params.to_date)?;
 
-    // Verify rodent exists
+    // Verify rodent exists; rodents outside the caller's visibility look missing
+    let mut rodent_filter = doc! { "_id": object_id };
+    if let Some(visibility) = rodent_visibility_filter(&auth_info) {
+        rodent_filter.extend(visibility);
+    }
     let rodent_collection = state.db.db.collection::<Rodent>("rodents");
     rodent_collection
-        .find_one(doc! { "_id": object_id }, None)
+        .find_one(rodent_filter, None)
         .await?
         .ok_or(AppError::RodentNotFound)?;
 
@@ -690,7 +2203,7 @@ pub async fn list_medical_records(
 
     // Pagination
     let page = params.page.unwrap_or(1).max(1);
-    let limit = params.limit.unwrap_or(20).min(100);
+    let limit = page_limit(&state.config, params.limit, 20)?;
     let skip = (page - 1) * limit;
 
     let find_options = FindOptions::builder()
@@ -711,9 +2224,152 @@ pub async fn list_medical_records(
     Ok(Json(MedicalRecordListResponse {
         success: true,
         medical_records: records,
-        total,
-        page,
-        limit,
+        pagination: Pagination::new(total, page, limit),
+    }))
+}
+
+/// List medical records across all rodents, with each rodent's name joined in
+pub async fn list_all_medical_records(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<AllMedicalRecordsQueryParams>,
+) -> Result<Json<AllMedicalRecordsListResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "list_all_medical_records");
+    can_view(&auth_info)?;
+
+    validate_date_range(params.from_date, params.to_date)?;
+
+    let rodent_collection = state.db.db.collection::<Document>("rodents");
+    let collection = state.db.db.collection::<MedicalRecord>("medical_records");
+
+    // Build filter
+    let mut filter = doc! {};
+
+    // Volunteers only see records for rodents assigned to them
+    if let Some(visible_ids) = visible_rodent_ids(&rodent_collection, &auth_info).await? {
+        filter.insert("rodent_id", doc! { "$in": visible_ids });
+    }
+
+    if let Some(record_type) = &params.record_type {
+        filter.insert("record_type", record_type.as_str());
+    }
+
+    if let Some(veterinarian_id) = &params.veterinarian_id {
+        filter.insert("veterinarian_id", veterinarian_id);
+    }
+
+    let mut date_filter = doc! {};
+    if let Some(from_date) = params.from_date {
+        date_filter.insert("$gte", from_date);
+    }
+    if let Some(to_date) = params.to_date {
+        date_filter.insert("$lte", to_date);
+    }
+    if !date_filter.is_empty() {
+        filter.insert("date", date_filter);
+    }
+
+    // Pagination
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = page_limit(&state.config, params.limit, 20)?;
+    let skip = (page - 1) * limit;
+
+    let find_options = FindOptions::builder()
+        .skip(Some(skip as u64))
+        .limit(Some(limit as i64))
+        .sort(doc! { "date": -1 })
+        .build();
+
+    let total = collection.count_documents(filter.clone(), None).await?;
+
+    let records: Vec<MedicalRecord> = collection.find(filter, find_options).await?.try_collect().await?;
+
+    let names = rodent_names(&rodent_collection, records.iter().map(|r| r.rodent_id)).await?;
+
+    let medical_records = records
+        .into_iter()
+        .map(|record| MedicalRecordWithRodentResponse {
+            rodent_name: names.get(&record.rodent_id).cloned(),
+            record: MedicalRecordResponse::from(record),
+        })
+        .collect();
+
+    Ok(Json(AllMedicalRecordsListResponse {
+        success: true,
+        medical_records,
+        pagination: Pagination::new(total, page, limit),
+    }))
+}
+
+/// Longest accepted medical record search query
+const MAX_SEARCH_QUERY_LENGTH: usize = 200;
+
+/// Full-text search over description, diagnosis and test results, best matches first
+pub async fn search_medical_records(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<MedicalRecordSearchQueryParams>,
+) -> Result<Json<MedicalRecordSearchResponse>, AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    let _timer = QueryTimer::start(&state.config, "search_medical_records");
+    can_view(&auth_info)?;
+
+    let query = params.q.trim().to_string();
+    if query.is_empty() || query.chars().count() > MAX_SEARCH_QUERY_LENGTH {
+        return Err(AppError::ValidationError(format!(
+            "Search query must be between 1 and {} characters",
+            MAX_SEARCH_QUERY_LENGTH
+        )));
+    }
+
+    let rodent_collection = state.db.db.collection::<Document>("rodents");
+    let collection = state.db.db.collection::<Document>("medical_records");
+
+    let mut filter = doc! { "$text": { "$search": &query } };
+    if let Some(visible_ids) = visible_rodent_ids(&rodent_collection, &auth_info).await? {
+        filter.insert("rodent_id", doc! { "$in": visible_ids });
+    }
+
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = page_limit(&state.config, params.limit, 20)?;
+
+    let find_options = FindOptions::builder()
+        .projection(doc! { "score": { "$meta": "textScore" } })
+        .sort(doc! { "score": { "$meta": "textScore" }, "date": -1 })
+        .skip(Some(((page - 1) * limit) as u64))
+        .limit(Some(limit as i64))
+        .build();
+
+    let total = collection.count_documents(filter.clone(), None).await?;
+
+    let mut matches = Vec::new();
+    let mut cursor = collection.find(filter, find_options).await?;
+    while let Some(doc) = cursor.try_next().await? {
+        let score = doc.get_f64("score").unwrap_or_default();
+        let record: MedicalRecord = bson::from_document(doc).map_err(|e| {
+            tracing::error!("Failed to decode medical record: {}", e);
+            AppError::InternalError
+        })?;
+        matches.push((record, score));
+    }
+
+    let names = rodent_names(&rodent_collection, matches.iter().map(|(r, _)| r.rodent_id)).await?;
+
+    let results = matches
+        .into_iter()
+        .map(|(record, score)| MedicalRecordSearchResult {
+            rodent_name: names.get(&record.rodent_id).cloned(),
+            record: MedicalRecordResponse::from(record),
+            score,
+        })
+        .collect();
+
+    Ok(Json(MedicalRecordSearchResponse {
+        success: true,
+        query,
+        results,
+        pagination: Pagination::new(total, page, limit),
     }))
 }
 
@@ -729,6 +2385,19 @@ pub async fn get_medical_record(
     let rodent_oid = ObjectId::parse_str(&rodent_id).map_err(|_| AppError::InvalidRodentId)?;
     let record_oid = ObjectId::parse_str(&record_id).map_err(|_| AppError::InvalidMedicalRecordId)?;
 
+    // Rodents outside the caller's visibility look the same as missing ones
+    let mut rodent_filter = doc! { "_id": rodent_oid };
+    if let Some(visibility) = rodent_visibility_filter(&auth_info) {
+        rodent_filter.extend(visibility);
+    }
+    state
+        .db
+        .db
+        .collection::<Rodent>("rodents")
+        .find_one(rodent_filter, None)
+        .await?
+        .ok_or(AppError::RodentNotFound)?;
+
     let collection = state.db.db.collection::<MedicalRecord>("medical_records");
 
     let record = collection
@@ -765,11 +2434,14 @@ pub async fn create_medical_record(
     let collection = state.db.db.collection::<MedicalRecord>("medical_records");
     let now = Utc::now();
 
+    let date = payload.date.unwrap_or(now);
+    validate_medical_record_date(date, rodent.intake_date)?;
+
     let record = MedicalRecord {
         id: None,
         rodent_id: object_id,
         record_type: payload.record_type,
-        date: payload.date.unwrap_or(now),
+        date,
         description: payload.description,
         diagnosis: payload.diagnosis,
         medications: payload.medications.into_iter().map(Medication::from).collect(),
@@ -815,6 +2487,137 @@ pub async fn create_medical_record(
     ))
 }
 
+/// Create the same medical record for every listed rodent. Nothing is created
+/// unless all rodents exist and the date is valid for each of them.
+pub async fn bulk_create_medical_records(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<BulkCreateMedicalRecordsRequest>,
+) -> Result<(StatusCode, Json<BulkCreateMedicalRecordsResponse>), AppError> {
+    let auth_info = extract_auth_info(&state, &headers)?;
+    can_manage_medical_records(&auth_info)?;
+    payload.validate()?;
+
+    let mut object_ids = Vec::with_capacity(payload.rodent_ids.len());
+    for id in &payload.rodent_ids {
+        let object_id = ObjectId::parse_str(id).map_err(|_| AppError::InvalidRodentId)?;
+        if !object_ids.contains(&object_id) {
+            object_ids.push(object_id);
+        }
+    }
+
+    if object_ids.is_empty() {
+        return Err(AppError::ValidationError("At least one rodent ID is required".to_string()));
+    }
+    if object_ids.len() > MAX_BATCH_IDS {
+        return Err(AppError::ValidationError(format!(
+            "At most {} rodents can be given a record at once",
+            MAX_BATCH_IDS
+        )));
+    }
+
+    let rodents: Vec<Rodent> = state
+        .db
+        .db
+        .collection::<Rodent>("rodents")
+        .find(
+            doc! { "_id": { "$in": &object_ids } },
+            FindOptions::builder().projection(without_image_data()).build(),
+        )
+        .await?
+        .try_collect()
+        .await?;
+
+    let missing: Vec<String> = object_ids
+        .iter()
+        .filter(|oid| !rodents.iter().any(|rodent| rodent.id.as_ref() == Some(*oid)))
+        .map(|oid| oid.to_hex())
+        .collect();
+    if !missing.is_empty() {
+        return Err(AppError::ValidationError(format!(
+            "Rodents not found: {}",
+            missing.join(", ")
+        )));
+    }
+
+    let now = Utc::now();
+    let template = payload.record_template;
+    let date = template.date.unwrap_or(now);
+    for rodent in &rodents {
+        validate_medical_record_date(date, rodent.intake_date)?;
+    }
+
+    let medications: Vec<Medication> = template.medications.into_iter().map(Medication::from).collect();
+    let records: Vec<MedicalRecord> = rodents
+        .iter()
+        .filter_map(|rodent| rodent.id)
+        .map(|rodent_id| MedicalRecord {
+            id: None,
+            rodent_id,
+            record_type: template.record_type.clone(),
+            date,
+            description: template.description.clone(),
+            diagnosis: template.diagnosis.clone(),
+            medications: medications.clone(),
+            test_results: template.test_results.clone(),
+            next_appointment: template.next_appointment,
+            veterinarian_id: auth_info.user_id.clone(),
+            veterinarian_name: auth_info.username.clone(),
+            created_at: now,
+            updated_at: now,
+        })
+        .collect();
+
+    let result = state
+        .db
+        .db
+        .collection::<MedicalRecord>("medical_records")
+        .insert_many(&records, None)
+        .await?;
+
+    let mut created = std::collections::HashMap::with_capacity(records.len());
+    for (index, (record, rodent)) in records.iter().zip(&rodents).enumerate() {
+        let inserted_id = result
+            .inserted_ids
+            .get(&index)
+            .and_then(|id| id.as_object_id())
+            .ok_or(AppError::InternalError)?;
+        let rodent_id = record.rodent_id.to_hex();
+
+        let event = MedicalTreatmentAddedEvent::new(MedicalTreatmentPayload {
+            record_id: inserted_id.to_hex(),
+            rodent_id: rodent_id.clone(),
+            rodent_name: rodent.name.clone(),
+            record_type: record.record_type.as_str().to_string(),
+            description: record.description.clone(),
+            diagnosis: record.diagnosis.clone(),
+            treatment_date: record.date,
+            veterinarian_name: record.veterinarian_name.clone(),
+            added_by: auth_info.user_id.clone(),
+            added_by_name: auth_info.username.clone(),
+        });
+        if let Err(e) = state.publisher.publish_medical_treatment(&event).await {
+            tracing::warn!("Failed to publish MedicalTreatmentAdded event: {}", e);
+        }
+
+        created.insert(rodent_id, inserted_id.to_hex());
+    }
+
+    tracing::info!(
+        "{} medical records created in bulk by user {}",
+        created.len(),
+        auth_info.username
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(BulkCreateMedicalRecordsResponse {
+            success: true,
+            created,
+        }),
+    ))
+}
+
 /// Update a medical record
 pub async fn update_medical_record(
     State(state): State<Arc<AppState>>,
@@ -837,6 +2640,17 @@ pub async fn update_medical_record(
         .await?
         .ok_or(AppError::MedicalRecordNotFound)?;
 
+    if let Some(date) = payload.date {
+        let rodent = state
+            .db
+            .db
+            .collection::<Rodent>("rodents")
+            .find_one(doc! { "_id": rodent_oid }, None)
+            .await?
+            .ok_or(AppError::RodentNotFound)?;
+        validate_medical_record_date(date, rodent.intake_date)?;
+    }
+
     // Build update document
     let mut update_doc = doc! { "updated_at": Utc::now() };
 
@@ -916,3 +2730,41 @@ pub async fn delete_medical_record(
         message: "Medical record deleted successfully".to_string(),
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bson::Bson;
+
+    #[test]
+    fn archive_entry_names_keep_only_the_file_name() {
+        assert_eq!(archive_entry_name("whiskers.jpg", "1"), "whiskers.jpg");
+        assert_eq!(archive_entry_name("../../etc/cron.d/x", "1"), "x");
+        assert_eq!(archive_entry_name("/tmp/abs.png", "1"), "abs.png");
+        assert_eq!(archive_entry_name("C:\\Users\\me\\pic.gif", "1"), "pic.gif");
+        assert_eq!(archive_entry_name("photos/..", "2"), "image-2");
+        assert_eq!(archive_entry_name(".", "3"), "image-3");
+        assert_eq!(archive_entry_name("dir/", "4"), "image-4");
+    }
+
+    #[test]
+    fn primary_image_update_is_one_stage_keyed_on_image_id() {
+        let update = primary_image_update("img-2", "user-1");
+        assert_eq!(update.len(), 1, "the switch must be a single atomic stage");
+
+        let set = update[0].get_document("$set").unwrap();
+        assert_eq!(set.get_str("updated_by").unwrap(), "user-1");
+
+        let map = set.get_document("images").unwrap().get_document("$map").unwrap();
+        assert_eq!(map.get_str("input").unwrap(), "$images");
+        assert_eq!(map.get_str("as").unwrap(), "image");
+
+        let merged = map.get_document("in").unwrap().get_array("$mergeObjects").unwrap();
+        assert_eq!(merged[0], Bson::String("$$image".to_string()));
+        // Each flag is computed from the image id alone, never from the old flags
+        assert_eq!(
+            merged[1],
+            Bson::Document(doc! { "is_primary": { "$eq": ["$$image.id", "img-2"] } })
+        );
+    }
+}
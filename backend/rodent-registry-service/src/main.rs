@@ -1,5 +1,6 @@
-use axum::Router;
-use std::sync::Arc;
+use axum::{extract::DefaultBodyLimit, Router};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::net::TcpListener;
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::info;
@@ -13,7 +14,9 @@ mod handlers;
 mod messaging;
 mod middleware;
 mod models;
+mod pdf;
 mod routes;
+mod timing;
 
 use config::Config;
 use db::MongoDB;
@@ -23,7 +26,8 @@ pub struct AppState {
     pub db: MongoDB,
     pub config: Config,
     pub http_client: reqwest::Client,
-    pub publisher: MessagePublisher,
+    pub publisher: Arc<MessagePublisher>,
+    pub facets_cache: Mutex<Option<(Instant, models::RodentFacets)>>,
 }
 
 #[tokio::main]
@@ -31,13 +35,18 @@ async fn main() {
     // Load environment variables
     dotenvy::dotenv().ok();
 
-    // Initialize tracing
+    // Initialize tracing (LOG_FORMAT=json for machine-readable output)
+    let json_logs = std::env::var("LOG_FORMAT")
+        .map(|format| format.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "rodent_registry_service=debug,tower_http=debug".into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with(json_logs.then(|| tracing_subscriber::fmt::layer().json()))
+        .with((!json_logs).then(tracing_subscriber::fmt::layer))
         .init();
 
     // Load configuration
@@ -54,16 +63,27 @@ async fn main() {
         .await
         .expect("Failed to create database indexes");
 
-    // Create HTTP client for communicating with User Service
+    db.migrate_notes_history()
+        .await
+        .expect("Failed to migrate rodent notes");
+
+    // Create HTTP client for communicating with the User and Activity services
     let http_client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(30))
         .build()
         .expect("Failed to create HTTP client");
 
-    // Initialize RabbitMQ publisher
-    let publisher = MessagePublisher::new(&config.rabbitmq_url)
-        .await
-        .expect("Failed to create message publisher");
+    // Initialize RabbitMQ publisher; events it can't deliver go to the outbox
+    let outbox = config.event_outbox_enabled.then(|| db.db.collection("outbox"));
+    let publisher = Arc::new(
+        MessagePublisher::new(&config.rabbitmq_url, outbox)
+            .await
+            .expect("Failed to create message publisher"),
+    );
+
+    if config.event_outbox_enabled {
+        messaging::spawn_outbox_relay(publisher.clone(), config.outbox_retry_interval_secs);
+    }
 
     // Create application state
     let state = Arc::new(AppState {
@@ -71,11 +91,14 @@ async fn main() {
         config: config.clone(),
         http_client,
         publisher,
+        facets_cache: Mutex::new(None),
     });
 
     // Build router with middleware
     let app = Router::new()
-        .nest("/api", routes::create_routes())
+        .nest("/api", routes::create_routes(&config))
+        .layer(DefaultBodyLimit::max(config.max_body_size_mb * 1024 * 1024))
+        .layer(axum::middleware::map_response(error::json_payload_too_large))
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
         .with_state(state);
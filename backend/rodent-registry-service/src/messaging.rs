@@ -3,6 +3,10 @@ use lapin::{
     types::FieldTable,
     BasicProperties, Channel, Connection, ConnectionProperties, ExchangeKind,
 };
+use bson::doc;
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::{options::FindOptions, Collection};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
@@ -12,18 +16,25 @@ use crate::events::{
     EXCHANGE_NAME, MEDICAL_TREATMENT_ADDED_ROUTING_KEY, RODENT_REGISTERED_ROUTING_KEY,
     RODENT_STATUS_CHANGED_ROUTING_KEY,
 };
+use crate::models::OutboxEvent;
 
-/// RabbitMQ message publisher
+/// How many stored events the outbox relay delivers per round
+const OUTBOX_BATCH_SIZE: i64 = 100;
+
+/// RabbitMQ message publisher. With the outbox enabled, events that can't be
+/// published are stored and delivered later by the outbox relay.
 pub struct MessagePublisher {
     channel: Arc<RwLock<Option<Channel>>>,
     rabbitmq_url: String,
+    outbox: Option<Collection<OutboxEvent>>,
 }
 
 impl MessagePublisher {
-    pub async fn new(rabbitmq_url: &str) -> Result<Self, lapin::Error> {
+    pub async fn new(rabbitmq_url: &str, outbox: Option<Collection<OutboxEvent>>) -> Result<Self, lapin::Error> {
         let publisher = Self {
             channel: Arc::new(RwLock::new(None)),
             rabbitmq_url: rabbitmq_url.to_string(),
+            outbox,
         };
 
         // Try to connect, but don't fail if RabbitMQ is not available
@@ -70,108 +81,197 @@ impl MessagePublisher {
         Ok(())
     }
 
-    pub async fn publish_rodent_registered(&self, event: &RodentRegisteredEvent) -> Result<(), String> {
+    /// Publish a serialized event. A failed publish drops the channel so the
+    /// next attempt reconnects.
+    async fn publish(&self, routing_key: &str, payload: &[u8]) -> Result<(), String> {
         if let Err(e) = self.ensure_connected().await {
             error!("Failed to connect to RabbitMQ: {}", e);
             return Err(format!("RabbitMQ connection failed: {}", e));
         }
 
-        let payload = serde_json::to_vec(event).map_err(|e| e.to_string())?;
+        let result = match self.channel.read().await.as_ref() {
+            Some(ch) => ch
+                .basic_publish(
+                    EXCHANGE_NAME,
+                    routing_key,
+                    BasicPublishOptions::default(),
+                    payload,
+                    BasicProperties::default()
+                        .with_content_type("application/json".into())
+                        .with_delivery_mode(2), // Persistent
+                )
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            None => Err("No RabbitMQ channel available".to_string()),
+        };
 
-        let channel = self.channel.read().await;
-        if let Some(ch) = channel.as_ref() {
-            ch.basic_publish(
-                EXCHANGE_NAME,
-                RODENT_REGISTERED_ROUTING_KEY,
-                BasicPublishOptions::default(),
-                &payload,
-                BasicProperties::default()
-                    .with_content_type("application/json".into())
-                    .with_delivery_mode(2), // Persistent
-            )
+        if result.is_err() {
+            *self.channel.write().await = None;
+        }
+        result
+    }
+
+    /// Publish an event, storing it in the outbox if RabbitMQ is unavailable
+    /// or older events are still waiting there, so it can't overtake them.
+    /// Returns whether it was published right away; fails only if the event
+    /// could be neither published nor stored.
+    async fn publish_or_store(&self, routing_key: &str, event_id: &str, payload: Vec<u8>) -> Result<bool, String> {
+        let Some(outbox) = &self.outbox else {
+            return self.publish(routing_key, &payload).await.map(|_| true);
+        };
+
+        let backlog = match outbox.find_one(doc! {}, None).await {
+            Ok(entry) => entry.is_some(),
+            Err(e) => {
+                warn!("Failed to check outbox backlog, publishing directly: {}", e);
+                false
+            }
+        };
+
+        let (attempts, error) = if backlog {
+            (0, "Queued behind earlier undelivered events".to_string())
+        } else {
+            match self.publish(routing_key, &payload).await {
+                Ok(()) => return Ok(true),
+                Err(e) => (1, e),
+            }
+        };
+
+        let entry = OutboxEvent {
+            id: None,
+            event_id: event_id.to_string(),
+            routing_key: routing_key.to_string(),
+            payload: String::from_utf8(payload).map_err(|e| e.to_string())?,
+            attempts,
+            last_error: error.clone(),
+            created_at: Utc::now(),
+        };
+
+        outbox
+            .insert_one(&entry, None)
+            .await
+            .map_err(|e| format!("{} (storing in outbox also failed: {})", error, e))?;
+
+        warn!(
+            "Event {} ({}) stored in outbox for retry: {}",
+            event_id, routing_key, error
+        );
+        Ok(false)
+    }
+
+    /// Deliver stored events oldest first, stopping at the first failure so
+    /// they keep their order. Delivery is at-least-once: an event published
+    /// just before its removal fails is sent again.
+    async fn relay_outbox(&self) -> Result<u64, String> {
+        let Some(outbox) = &self.outbox else {
+            return Ok(0);
+        };
+
+        let options = FindOptions::builder()
+            .sort(doc! { "created_at": 1, "_id": 1 })
+            .limit(OUTBOX_BATCH_SIZE)
+            .build();
+
+        let entries: Vec<OutboxEvent> = outbox
+            .find(doc! {}, options)
             .await
-            .map_err(|e| {
-                error!("Failed to publish RodentRegistered event: {}", e);
-                e.to_string()
-            })?;
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut delivered = 0;
+        for entry in entries {
+            let Some(id) = entry.id else { continue };
+
+            if let Err(e) = self.publish(&entry.routing_key, entry.payload.as_bytes()).await {
+                outbox
+                    .update_one(
+                        doc! { "_id": id },
+                        doc! { "$inc": { "attempts": 1 }, "$set": { "last_error": &e } },
+                        None,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+                break;
+            }
+
+            outbox
+                .delete_one(doc! { "_id": id }, None)
+                .await
+                .map_err(|e| e.to_string())?;
+            delivered += 1;
+        }
+
+        Ok(delivered)
+    }
+
+    /// Number of events waiting in the outbox
+    pub async fn outbox_backlog(&self) -> Result<u64, mongodb::error::Error> {
+        match &self.outbox {
+            Some(outbox) => outbox.count_documents(doc! {}, None).await,
+            None => Ok(0),
+        }
+    }
+
+    pub async fn publish_rodent_registered(&self, event: &RodentRegisteredEvent) -> Result<(), String> {
+        let payload = serde_json::to_vec(event).map_err(|e| e.to_string())?;
 
+        if self
+            .publish_or_store(RODENT_REGISTERED_ROUTING_KEY, &event.event_id, payload)
+            .await?
+        {
             info!(
                 "Published RodentRegistered event: {} for rodent {}",
                 event.event_id, event.payload.rodent_id
             );
-            Ok(())
-        } else {
-            Err("No RabbitMQ channel available".to_string())
         }
+        Ok(())
     }
 
     pub async fn publish_status_changed(&self, event: &RodentStatusChangedEvent) -> Result<(), String> {
-        if let Err(e) = self.ensure_connected().await {
-            error!("Failed to connect to RabbitMQ: {}", e);
-            return Err(format!("RabbitMQ connection failed: {}", e));
-        }
-
         let payload = serde_json::to_vec(event).map_err(|e| e.to_string())?;
 
-        let channel = self.channel.read().await;
-        if let Some(ch) = channel.as_ref() {
-            ch.basic_publish(
-                EXCHANGE_NAME,
-                RODENT_STATUS_CHANGED_ROUTING_KEY,
-                BasicPublishOptions::default(),
-                &payload,
-                BasicProperties::default()
-                    .with_content_type("application/json".into())
-                    .with_delivery_mode(2), // Persistent
-            )
-            .await
-            .map_err(|e| {
-                error!("Failed to publish RodentStatusChanged event: {}", e);
-                e.to_string()
-            })?;
-
+        if self
+            .publish_or_store(RODENT_STATUS_CHANGED_ROUTING_KEY, &event.event_id, payload)
+            .await?
+        {
             info!(
                 "Published RodentStatusChanged event: {} for rodent {} ({} -> {})",
                 event.event_id, event.payload.rodent_id, event.payload.old_status, event.payload.new_status
             );
-            Ok(())
-        } else {
-            Err("No RabbitMQ channel available".to_string())
         }
+        Ok(())
     }
 
     pub async fn publish_medical_treatment(&self, event: &MedicalTreatmentAddedEvent) -> Result<(), String> {
-        if let Err(e) = self.ensure_connected().await {
-            error!("Failed to connect to RabbitMQ: {}", e);
-            return Err(format!("RabbitMQ connection failed: {}", e));
-        }
-
         let payload = serde_json::to_vec(event).map_err(|e| e.to_string())?;
 
-        let channel = self.channel.read().await;
-        if let Some(ch) = channel.as_ref() {
-            ch.basic_publish(
-                EXCHANGE_NAME,
-                MEDICAL_TREATMENT_ADDED_ROUTING_KEY,
-                BasicPublishOptions::default(),
-                &payload,
-                BasicProperties::default()
-                    .with_content_type("application/json".into())
-                    .with_delivery_mode(2), // Persistent
-            )
-            .await
-            .map_err(|e| {
-                error!("Failed to publish MedicalTreatmentAdded event: {}", e);
-                e.to_string()
-            })?;
-
+        if self
+            .publish_or_store(MEDICAL_TREATMENT_ADDED_ROUTING_KEY, &event.event_id, payload)
+            .await?
+        {
             info!(
                 "Published MedicalTreatmentAdded event: {} for rodent {}",
                 event.event_id, event.payload.rodent_id
             );
-            Ok(())
-        } else {
-            Err("No RabbitMQ channel available".to_string())
         }
+        Ok(())
     }
 }
+
+/// Start the outbox relay in a background task
+pub fn spawn_outbox_relay(publisher: Arc<MessagePublisher>, interval_secs: u64) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            match publisher.relay_outbox().await {
+                Ok(0) => {}
+                Ok(delivered) => info!("Delivered {} events from the outbox", delivered),
+                Err(e) => error!("Failed to relay outbox events: {}", e),
+            }
+        }
+    });
+}
@@ -4,6 +4,7 @@ use axum::{
     middleware::Next,
     response::Response,
 };
+use bson::{doc, Document};
 use std::sync::Arc;
 
 use crate::{error::AppError, models::AuthInfo, AppState};
@@ -103,6 +104,27 @@ pub fn can_manage_medical_records(auth_info: &AuthInfo) -> Result<(), AppError>
     check_role(auth_info, &["admin", "veterinarian"])
 }
 
+/// Check if user can assign rodents to volunteers (Admin only)
+pub fn can_manage_assignments(auth_info: &AuthInfo) -> Result<(), AppError> {
+    check_role(auth_info, &["admin"])
+}
+
+/// Check if user can merge duplicate rodent profiles (Admin only)
+pub fn can_merge_rodents(auth_info: &AuthInfo) -> Result<(), AppError> {
+    check_role(auth_info, &["admin"])
+}
+
+/// Check if user can purge sample data (Admin only)
+pub fn can_purge_sample_data(auth_info: &AuthInfo) -> Result<(), AppError> {
+    check_role(auth_info, &["admin"])
+}
+
+/// Extra rodent filter for roles that only see what is assigned to them.
+/// Volunteers are limited to their own rodents; every other role sees all.
+pub fn rodent_visibility_filter(auth_info: &AuthInfo) -> Option<Document> {
+    (auth_info.role == "volunteer").then(|| doc! { "assigned_to": &auth_info.user_id })
+}
+
 /// Check if user can view (all authenticated users)
 pub fn can_view(_auth_info: &AuthInfo) -> Result<(), AppError> {
     // All authenticated users can view
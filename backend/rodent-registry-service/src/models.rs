@@ -16,10 +16,13 @@ pub enum Species {
     Hamster,
     PrairieDog,
     Rabbit,
+    /// Any species outside the built-in list; must appear in `ADDITIONAL_SPECIES`
+    #[serde(untagged)]
+    Other(String),
 }
 
 impl Species {
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Species::Beaver => "beaver",
             Species::Capybara => "capybara",
@@ -29,6 +32,7 @@ impl Species {
             Species::Hamster => "hamster",
             Species::PrairieDog => "prairie_dog",
             Species::Rabbit => "rabbit",
+            Species::Other(name) => name,
         }
     }
 }
@@ -41,6 +45,17 @@ pub enum Gender {
     Unknown,
 }
 
+impl Gender {
+    /// Stored form, also used by analytics when grouping by gender
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Gender::Male => "male",
+            Gender::Female => "female",
+            Gender::Unknown => "unknown",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum RodentStatus {
@@ -99,7 +114,16 @@ pub struct Rodent {
     pub date_of_birth_estimated: bool,
     pub chip_id: Option<String>,
     pub status: RodentStatus,
-    pub notes: Option<String>,
+    pub notes: Option<String>, // latest entry of notes_history
+    #[serde(default)]
+    pub notes_history: Vec<RodentNote>,
+    #[serde(default)]
+    pub enclosure: Option<String>,
+    #[serde(default)]
+    pub assigned_to: Option<String>, // user ID of the responsible volunteer
+    #[serde(default)]
+    pub is_sample: bool, // demo data, excludable from listings and analytics
+    #[serde(default)] // Omitted when listing with a `fields` projection
     pub images: Vec<RodentImage>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub intake_date: DateTime<Utc>,
@@ -111,11 +135,22 @@ pub struct Rodent {
     pub updated_by: String,
 }
 
+/// Append-only observation about a rodent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RodentNote {
+    pub text: String,
+    pub author_id: String,
+    pub author_name: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RodentImage {
     pub id: String,
     pub filename: String,
     pub content_type: String,
+    #[serde(default)] // Omitted when listing with the image-data projection
     pub data: String, // Base64 encoded image data
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub uploaded_at: DateTime<Utc>,
@@ -167,6 +202,49 @@ pub struct StatusHistory {
     pub changed_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnclosureHistory {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub rodent_id: ObjectId,
+    pub from_enclosure: Option<String>,
+    pub to_enclosure: String,
+    pub reason: Option<String>,
+    pub moved_by: String,
+    pub moved_by_name: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub moved_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntakeDateChange {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub rodent_id: ObjectId,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub old_intake_date: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub new_intake_date: DateTime<Utc>,
+    pub changed_by: String,
+    pub changed_by_name: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Event that couldn't be published, kept until the outbox relay delivers it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEvent {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub event_id: String,
+    pub routing_key: String,
+    pub payload: String, // serialized event JSON
+    pub attempts: i32,
+    pub last_error: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
 // ============== Request DTOs ==============
 
 #[derive(Debug, Deserialize, Validate)]
@@ -183,7 +261,11 @@ pub struct CreateRodentRequest {
     pub status: RodentStatus,
     #[validate(length(max = 2000, message = "Notes must be at most 2000 characters"))]
     pub notes: Option<String>,
+    #[validate(length(min = 1, max = 100, message = "Enclosure must be between 1 and 100 characters"))]
+    pub enclosure: Option<String>,
     pub intake_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub is_sample: bool,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -198,6 +280,8 @@ pub struct UpdateRodentRequest {
     pub chip_id: Option<String>,
     #[validate(length(max = 2000, message = "Notes must be at most 2000 characters"))]
     pub notes: Option<String>,
+    pub intake_date: Option<DateTime<Utc>>,
+    pub is_sample: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -207,6 +291,49 @@ pub struct UpdateRodentStatusRequest {
     pub reason: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+pub struct BulkUpdateStatusRequest {
+    pub rodent_ids: Vec<String>,
+    pub status: RodentStatus,
+    #[validate(length(max = 500, message = "Reason must be at most 500 characters"))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct AssignRodentRequest {
+    #[validate(length(min = 1, max = 100, message = "User ID must be between 1 and 100 characters"))]
+    pub assigned_to: Option<String>, // null clears the assignment
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchRodentsRequest {
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RodentsExistRequest {
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReorderImagesRequest {
+    pub image_ids: Vec<String>, // every image of the rodent, in display order
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergeRodentsRequest {
+    pub primary_id: String,
+    pub duplicate_id: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct MoveRodentRequest {
+    #[validate(length(min = 1, max = 100, message = "Enclosure must be between 1 and 100 characters"))]
+    pub to_enclosure: String,
+    #[validate(length(max = 500, message = "Reason must be at most 500 characters"))]
+    pub reason: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct CreateMedicalRecordRequest {
     pub record_type: MedicalRecordType,
@@ -222,6 +349,14 @@ pub struct CreateMedicalRecordRequest {
     pub next_appointment: Option<DateTime<Utc>>,
 }
 
+/// One medical record applied to many rodents, e.g. a cohort vaccination
+#[derive(Debug, Deserialize, Validate)]
+pub struct BulkCreateMedicalRecordsRequest {
+    pub rodent_ids: Vec<String>,
+    #[validate]
+    pub record_template: CreateMedicalRecordRequest,
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct MedicationRequest {
     #[validate(length(min = 1, max = 200, message = "Medication name must be between 1 and 200 characters"))]
@@ -259,6 +394,25 @@ pub struct RodentQueryParams {
     pub chip_id: Option<String>,
     pub sort_by: Option<String>,  // "age", "intake_date", "name", "created_at"
     pub sort_order: Option<String>, // "asc", "desc"
+    pub exclude_samples: Option<bool>, // sample rodents are included unless true
+    pub fields: Option<String>, // comma-separated response fields; all when absent
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CloneRodentQueryParams {
+    pub count: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportBundleQueryParams {
+    pub include_images: Option<bool>, // image data is left out unless true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecentIntakesQueryParams {
+    pub days: Option<i64>,
     pub page: Option<u32>,
     pub limit: Option<u32>,
 }
@@ -272,8 +426,56 @@ pub struct MedicalRecordQueryParams {
     pub limit: Option<u32>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct AllMedicalRecordsQueryParams {
+    pub record_type: Option<MedicalRecordType>,
+    pub veterinarian_id: Option<String>,
+    pub from_date: Option<DateTime<Utc>>,
+    pub to_date: Option<DateTime<Utc>>,
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MedicalRecordSearchQueryParams {
+    pub q: String,
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatusHistoryQueryParams {
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
 // ============== Response DTOs ==============
 
+/// Paging metadata shared by list responses; flattened into the response body
+#[derive(Debug, Serialize)]
+pub struct Pagination {
+    pub total: u64,
+    pub page: u32,
+    pub limit: u32,
+    pub total_pages: u64,
+    pub has_next: bool,
+    pub has_prev: bool,
+}
+
+impl Pagination {
+    pub fn new(total: u64, page: u32, limit: u32) -> Self {
+        let total_pages = total.div_ceil(u64::from(limit.max(1)));
+        Self {
+            total,
+            page,
+            limit,
+            total_pages,
+            has_next: u64::from(page) < total_pages,
+            has_prev: page > 1,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct RodentResponse {
     pub id: String,
@@ -286,6 +488,9 @@ pub struct RodentResponse {
     pub chip_id: Option<String>,
     pub status: RodentStatus,
     pub notes: Option<String>,
+    pub enclosure: Option<String>,
+    pub assigned_to: Option<String>,
+    pub is_sample: bool,
     pub images: Vec<RodentImageResponse>,
     pub intake_date: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
@@ -303,12 +508,11 @@ pub struct RodentImageResponse {
 }
 
 #[derive(Debug, Serialize)]
-pub struct RodentListResponse {
+pub struct RodentListResponse<T = RodentResponse> {
     pub success: bool,
-    pub rodents: Vec<RodentResponse>,
-    pub total: u64,
-    pub page: u32,
-    pub limit: u32,
+    pub rodents: Vec<T>,
+    #[serde(flatten)]
+    pub pagination: Pagination,
 }
 
 #[derive(Debug, Serialize)]
@@ -338,9 +542,40 @@ pub struct MedicalRecordResponse {
 pub struct MedicalRecordListResponse {
     pub success: bool,
     pub medical_records: Vec<MedicalRecordResponse>,
-    pub total: u64,
-    pub page: u32,
-    pub limit: u32,
+    #[serde(flatten)]
+    pub pagination: Pagination,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MedicalRecordWithRodentResponse {
+    #[serde(flatten)]
+    pub record: MedicalRecordResponse,
+    pub rodent_name: Option<String>, // None if the rodent no longer exists
+}
+
+#[derive(Debug, Serialize)]
+pub struct AllMedicalRecordsListResponse {
+    pub success: bool,
+    pub medical_records: Vec<MedicalRecordWithRodentResponse>,
+    #[serde(flatten)]
+    pub pagination: Pagination,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MedicalRecordSearchResult {
+    #[serde(flatten)]
+    pub record: MedicalRecordResponse,
+    pub rodent_name: Option<String>,
+    pub score: f64, // MongoDB text score, higher is more relevant
+}
+
+#[derive(Debug, Serialize)]
+pub struct MedicalRecordSearchResponse {
+    pub success: bool,
+    pub query: String,
+    pub results: Vec<MedicalRecordSearchResult>,
+    #[serde(flatten)]
+    pub pagination: Pagination,
 }
 
 #[derive(Debug, Serialize)]
@@ -365,6 +600,48 @@ pub struct StatusHistoryResponse {
 pub struct StatusHistoryListResponse {
     pub success: bool,
     pub history: Vec<StatusHistoryResponse>,
+    #[serde(flatten)]
+    pub pagination: Pagination,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnclosureHistoryResponse {
+    pub id: String,
+    pub rodent_id: String,
+    pub from_enclosure: Option<String>,
+    pub to_enclosure: String,
+    pub reason: Option<String>,
+    pub moved_by: String,
+    pub moved_by_name: String,
+    pub moved_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnclosureHistoryListResponse {
+    pub success: bool,
+    pub history: Vec<EnclosureHistoryResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RodentNoteResponse {
+    pub text: String,
+    pub author_id: String,
+    pub author_name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RodentNotesResponse {
+    pub success: bool,
+    pub rodent_id: String,
+    pub notes: Vec<RodentNoteResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthResponse {
+    pub success: bool,
+    pub message: String,
+    pub outbox_backlog: Option<u64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -373,6 +650,99 @@ pub struct MessageResponse {
     pub message: String,
 }
 
+/// Distinct values currently present, for building filter dropdowns
+#[derive(Debug, Clone, Serialize)]
+pub struct RodentFacets {
+    pub species: Vec<String>,
+    pub statuses: Vec<String>,
+    pub enclosures: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RodentFacetsResponse {
+    pub success: bool,
+    #[serde(flatten)]
+    pub facets: RodentFacets,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkStatusResult {
+    pub rodent_id: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkUpdateStatusResponse {
+    pub success: bool,
+    pub updated: usize,
+    pub failed: usize,
+    pub results: Vec<BulkStatusResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PurgeSampleDataResponse {
+    pub success: bool,
+    pub rodents_deleted: u64,
+    pub medical_records_deleted: u64,
+    pub status_history_deleted: u64,
+    pub enclosure_history_deleted: u64,
+    pub intake_date_changes_deleted: u64,
+    pub daily_records_deleted: u64,
+    pub activities_deleted: u64,
+    pub feeding_records_deleted: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchRodentsResponse {
+    pub success: bool,
+    pub rodents: Vec<RodentResponse>, // in request order
+    pub missing_ids: Vec<String>,     // malformed, unknown or not visible to the caller
+}
+
+#[derive(Debug, Serialize)]
+pub struct RodentsExistResponse {
+    pub success: bool,
+    pub exists: std::collections::HashMap<String, bool>, // keyed by the requested ID
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkCreateMedicalRecordsResponse {
+    pub success: bool,
+    pub created: std::collections::HashMap<String, String>, // rodent ID -> new record ID
+}
+
+/// Portable snapshot of one rodent for rehoming to a partner shelter
+#[derive(Debug, Serialize)]
+pub struct RodentExportBundle {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub exported_by: String,
+    pub rodent: RodentResponse,
+    pub medical_records: Vec<MedicalRecordResponse>,
+    pub status_history: Vec<StatusHistoryResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MergeRodentsResponse {
+    pub success: bool,
+    pub primary_id: String,
+    pub deleted_id: String,
+    pub medical_records_moved: u64,
+    pub status_history_moved: u64,
+    pub enclosure_history_moved: u64,
+    pub intake_date_changes_moved: u64,
+    pub images_moved: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CloneRodentResponse {
+    pub success: bool,
+    pub source_id: String,
+    pub created_ids: Vec<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ImageUploadResponse {
     pub success: bool,
@@ -430,6 +800,9 @@ impl From<Rodent> for RodentResponse {
             chip_id: rodent.chip_id,
             status: rodent.status,
             notes: rodent.notes,
+            enclosure: rodent.enclosure,
+            assigned_to: rodent.assigned_to,
+            is_sample: rodent.is_sample,
             images: rodent.images.into_iter().map(|img| RodentImageResponse {
                 id: img.id,
                 filename: img.filename,
@@ -480,6 +853,32 @@ impl From<StatusHistory> for StatusHistoryResponse {
     }
 }
 
+impl From<EnclosureHistory> for EnclosureHistoryResponse {
+    fn from(history: EnclosureHistory) -> Self {
+        Self {
+            id: history.id.map(|id| id.to_hex()).unwrap_or_default(),
+            rodent_id: history.rodent_id.to_hex(),
+            from_enclosure: history.from_enclosure,
+            to_enclosure: history.to_enclosure,
+            reason: history.reason,
+            moved_by: history.moved_by,
+            moved_by_name: history.moved_by_name,
+            moved_at: history.moved_at,
+        }
+    }
+}
+
+impl From<RodentNote> for RodentNoteResponse {
+    fn from(note: RodentNote) -> Self {
+        Self {
+            text: note.text,
+            author_id: note.author_id,
+            author_name: note.author_name,
+            created_at: note.created_at,
+        }
+    }
+}
+
 impl From<MedicationRequest> for Medication {
     fn from(req: MedicationRequest) -> Self {
         Self {
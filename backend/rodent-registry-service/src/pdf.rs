@@ -0,0 +1,195 @@
+use chrono::{DateTime, Utc};
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+
+use crate::error::AppError;
+use crate::models::{MedicalRecord, Rodent};
+
+// A4 portrait
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const FOOTER_Y: f32 = 10.0;
+
+const BODY_SIZE: f32 = 10.0;
+const LINE_HEIGHT: f32 = 5.0;
+// Helvetica at 10pt fits roughly this many characters across the text width
+const WRAP_COLUMNS: usize = 95;
+
+/// Writes lines top to bottom, starting a new page (with footer) when one fills up
+struct PdfWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    footer: String,
+    y: f32,
+}
+
+impl PdfWriter {
+    fn new(title: &str, footer: String) -> Result<Self, AppError> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(pdf_error)?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(pdf_error)?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        let writer = Self {
+            doc,
+            layer,
+            regular,
+            bold,
+            footer,
+            y: PAGE_HEIGHT - MARGIN,
+        };
+        writer.write_footer();
+        Ok(writer)
+    }
+
+    fn write_footer(&self) {
+        self.layer.use_text(&self.footer, 8.0, Mm(MARGIN), Mm(FOOTER_Y), &self.regular);
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT - MARGIN;
+            self.write_footer();
+        }
+    }
+
+    fn heading(&mut self, text: &str, size: f32) {
+        self.ensure_space(size * 0.6);
+        self.layer.use_text(text, size, Mm(MARGIN), Mm(self.y), &self.bold);
+        self.y -= size * 0.6;
+    }
+
+    fn line(&mut self, text: &str) {
+        for chunk in wrap(text, WRAP_COLUMNS) {
+            self.ensure_space(LINE_HEIGHT);
+            self.layer.use_text(chunk, BODY_SIZE, Mm(MARGIN), Mm(self.y), &self.regular);
+            self.y -= LINE_HEIGHT;
+        }
+    }
+
+    fn field(&mut self, label: &str, value: &str) {
+        self.line(&format!("{}: {}", label, value));
+    }
+
+    fn gap(&mut self) {
+        self.y -= LINE_HEIGHT;
+    }
+
+    fn finish(self) -> Result<Vec<u8>, AppError> {
+        self.doc.save_to_bytes().map_err(pdf_error)
+    }
+}
+
+fn pdf_error(e: printpdf::Error) -> AppError {
+    tracing::error!("Failed to render PDF: {}", e);
+    AppError::InternalError
+}
+
+/// Greedy word wrap; words longer than a line are split
+fn wrap(text: &str, columns: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut current = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            while word.len() > columns {
+                if !current.is_empty() {
+                    lines.push(std::mem::take(&mut current));
+                }
+                lines.push(word.drain(..columns).collect());
+            }
+            let word: String = word.into_iter().collect();
+
+            if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > columns {
+                lines.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(&word);
+        }
+        lines.push(current);
+    }
+
+    lines
+}
+
+fn format_date(date: DateTime<Utc>) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Render a rodent's profile followed by its full medical history
+pub fn render_medical_history(
+    rodent: &Rodent,
+    records: &[MedicalRecord],
+    generated_by: &str,
+    generated_at: DateTime<Utc>,
+) -> Result<Vec<u8>, AppError> {
+    let footer = format!(
+        "Generated by {} on {}",
+        generated_by,
+        generated_at.format("%Y-%m-%d %H:%M UTC")
+    );
+    let mut pdf = PdfWriter::new(&format!("Medical history - {}", rodent.name), footer)?;
+
+    pdf.heading(&format!("Medical history: {}", rodent.name), 16.0);
+    pdf.gap();
+
+    pdf.heading("Profile", 12.0);
+    pdf.field("Species", rodent.species.as_str());
+    pdf.field("Gender", rodent.gender.as_str());
+    if let Some(dob) = rodent.date_of_birth {
+        let estimated = if rodent.date_of_birth_estimated { " (estimated)" } else { "" };
+        pdf.field("Date of birth", &format!("{}{}", format_date(dob), estimated));
+    }
+    if let Some(chip_id) = &rodent.chip_id {
+        pdf.field("Chip ID", chip_id);
+    }
+    pdf.field("Status", rodent.status.as_str());
+    pdf.field("Intake date", &format_date(rodent.intake_date));
+    if let Some(notes) = &rodent.notes {
+        pdf.field("Notes", notes);
+    }
+    pdf.gap();
+
+    pdf.heading(&format!("Medical records ({})", records.len()), 12.0);
+    if records.is_empty() {
+        pdf.line("No medical records on file.");
+    }
+
+    for record in records {
+        pdf.gap();
+        pdf.heading(
+            &format!("{} - {}", format_date(record.date), record.record_type.as_str()),
+            10.5,
+        );
+        pdf.field("Veterinarian", &record.veterinarian_name);
+        pdf.field("Description", &record.description);
+        if let Some(diagnosis) = &record.diagnosis {
+            pdf.field("Diagnosis", diagnosis);
+        }
+        if !record.medications.is_empty() {
+            pdf.line("Medications:");
+            for med in &record.medications {
+                let mut line = format!("  - {} {}, {}", med.name, med.dosage, med.frequency);
+                if let Some(duration) = &med.duration {
+                    line.push_str(&format!(", for {}", duration));
+                }
+                pdf.line(&line);
+            }
+        }
+        if let Some(test_results) = &record.test_results {
+            pdf.field("Test results", test_results);
+        }
+        if let Some(next) = record.next_appointment {
+            pdf.field("Next appointment", &format_date(next));
+        }
+    }
+
+    pdf.finish()
+}
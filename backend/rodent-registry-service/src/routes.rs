@@ -1,30 +1,59 @@
 use axum::{
+    extract::DefaultBodyLimit,
     routing::{delete, get, post, put},
     Router,
 };
 use std::sync::Arc;
 
-use crate::{handlers, AppState};
+use crate::{config::Config, handlers, AppState};
+
+pub fn create_routes(config: &Config) -> Router<Arc<AppState>> {
+    // Image uploads get their own cap on the whole multipart request, rejected before it is buffered
+    let image_body_limit = DefaultBodyLimit::max(config.max_upload_size_mb * 1024 * 1024);
 
-pub fn create_routes() -> Router<Arc<AppState>> {
     Router::new()
         // Public routes
         .route("/health", get(handlers::health_check))
         // Rodent routes (authentication handled by API Gateway)
         .route("/rodents", get(handlers::list_rodents))
         .route("/rodents", post(handlers::create_rodent))
+        .route("/rodents/recent-intakes", get(handlers::list_recent_intakes))
+        .route("/rodents/batch", post(handlers::batch_get_rodents))
+        .route("/rodents/exists", post(handlers::check_rodents_exist))
+        .route("/rodents/facets", get(handlers::get_rodent_facets))
+        .route("/rodents/bulk-status", post(handlers::bulk_update_rodent_status))
+        .route("/rodents/merge", post(handlers::merge_rodents))
+        .route("/rodents/samples", delete(handlers::purge_sample_data))
+        .route("/rodents/import-bundle", post(handlers::import_rodent_bundle))
         .route("/rodents/:id", get(handlers::get_rodent))
         .route("/rodents/:id", put(handlers::update_rodent))
         .route("/rodents/:id", delete(handlers::delete_rodent))
         .route("/rodents/:id/status", put(handlers::update_rodent_status))
+        .route("/rodents/:id/clone", post(handlers::clone_rodent))
+        .route("/rodents/:id/export-bundle", get(handlers::export_rodent_bundle))
         .route("/rodents/:id/status-history", get(handlers::get_rodent_status_history))
+        // Volunteer assignment (admin only)
+        .route("/rodents/:id/assignment", put(handlers::assign_rodent))
+        // Enclosure routes
+        .route("/rodents/:id/move", post(handlers::move_rodent))
+        .route("/rodents/:id/enclosure-history", get(handlers::get_rodent_enclosure_history))
+        .route("/rodents/:id/notes", get(handlers::get_rodent_notes))
         // Image routes
-        .route("/rodents/:id/images", post(handlers::upload_rodent_image))
+        .route(
+            "/rodents/:id/images",
+            post(handlers::upload_rodent_image).layer(image_body_limit),
+        )
+        .route("/rodents/:id/images/order", put(handlers::reorder_rodent_images))
+        .route("/rodents/:id/images/download", get(handlers::download_rodent_images))
         .route("/rodents/:rodent_id/images/:image_id", delete(handlers::delete_rodent_image))
         .route("/rodents/:rodent_id/images/:image_id/primary", put(handlers::set_primary_image))
         // Medical record routes
+        .route("/medical-records", get(handlers::list_all_medical_records))
+        .route("/medical-records/search", get(handlers::search_medical_records))
+        .route("/rodents/medical-records/bulk", post(handlers::bulk_create_medical_records))
         .route("/rodents/:rodent_id/medical-records", get(handlers::list_medical_records))
         .route("/rodents/:rodent_id/medical-records", post(handlers::create_medical_record))
+        .route("/rodents/:rodent_id/medical-records/export.pdf", get(handlers::export_medical_history_pdf))
         .route("/rodents/:rodent_id/medical-records/:record_id", get(handlers::get_medical_record))
         .route("/rodents/:rodent_id/medical-records/:record_id", put(handlers::update_medical_record))
         .route("/rodents/:rodent_id/medical-records/:record_id", delete(handlers::delete_medical_record))
@@ -1,33 +1,178 @@
+use std::collections::HashMap;
 use std::env;
 
+/// Complexity rules every new password must meet
+#[derive(Clone)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_digit: bool,
+    pub require_uppercase: bool,
+    pub require_symbol: bool,
+}
+
+impl PasswordPolicy {
+    /// Human-readable list of the rules `password` does not satisfy
+    pub fn unmet_requirements(&self, password: &str) -> Vec<String> {
+        let mut unmet = Vec::new();
+
+        if password.chars().count() < self.min_length {
+            unmet.push(format!("be at least {} characters long", self.min_length));
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            unmet.push("contain a digit".to_string());
+        }
+        if self.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+            unmet.push("contain an uppercase letter".to_string());
+        }
+        if self.require_symbol && password.chars().all(|c| c.is_alphanumeric()) {
+            unmet.push("contain a symbol".to_string());
+        }
+
+        unmet
+    }
+}
+
 #[derive(Clone)]
 pub struct Config {
     pub port: u16,
     pub database_url: String,
+    pub db_connect_retries: u32,
+    pub db_connect_retry_delay_ms: u64,
     pub jwt_secret: String,
     pub jwt_expiration_hours: i64,
+    pub jwt_expiration_hours_by_role: HashMap<String, i64>,
+    pub jwt_leeway_secs: u64,
     pub refresh_token_expiration_days: i64,
+    pub session_max_lifetime_days: i64,
+    pub max_body_size_mb: usize,
+    pub password_policy: PasswordPolicy,
+    pub production: bool,
+    pub seed_admin_username: String,
+    pub seed_admin_email: String,
+    pub seed_admin_password: Option<String>,
+    pub seed_admin_dev_password: bool,
+    pub max_page_limit: i64,
+    pub clamp_page_limit: bool,
+    pub slow_query_threshold_ms: u64,
 }
 
 impl Config {
     pub fn from_env() -> Self {
-        Self {
+        let config = Self {
             port: env::var("PORT")
                 .unwrap_or_else(|_| "8001".to_string())
                 .parse()
                 .expect("PORT must be a number"),
             database_url: env::var("DATABASE_URL")
                 .expect("DATABASE_URL must be set"),
+            db_connect_retries: env::var("DB_CONNECT_RETRIES")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .expect("DB_CONNECT_RETRIES must be a number"),
+            db_connect_retry_delay_ms: env::var("DB_CONNECT_RETRY_DELAY_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .expect("DB_CONNECT_RETRY_DELAY_MS must be a number"),
             jwt_secret: env::var("JWT_SECRET")
                 .unwrap_or_else(|_| "your-super-secret-jwt-key-change-in-production".to_string()),
             jwt_expiration_hours: env::var("JWT_EXPIRATION_HOURS")
                 .unwrap_or_else(|_| "24".to_string())
                 .parse()
                 .expect("JWT_EXPIRATION_HOURS must be a number"),
+            jwt_expiration_hours_by_role: ["admin", "caretaker", "veterinarian", "volunteer"]
+                .iter()
+                .filter_map(|role| {
+                    let var = format!("JWT_EXPIRATION_HOURS_{}", role.to_uppercase());
+                    env::var(&var).ok().map(|value| {
+                        let hours = value
+                            .parse()
+                            .unwrap_or_else(|_| panic!("{} must be a number", var));
+                        (role.to_string(), hours)
+                    })
+                })
+                .collect(),
+            jwt_leeway_secs: env::var("JWT_LEEWAY_SECS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .expect("JWT_LEEWAY_SECS must be a number"),
             refresh_token_expiration_days: env::var("REFRESH_TOKEN_EXPIRATION_DAYS")
                 .unwrap_or_else(|_| "7".to_string())
                 .parse()
                 .expect("REFRESH_TOKEN_EXPIRATION_DAYS must be a number"),
+            session_max_lifetime_days: env::var("SESSION_MAX_LIFETIME_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .expect("SESSION_MAX_LIFETIME_DAYS must be a number"),
+            max_body_size_mb: env::var("MAX_BODY_SIZE_MB")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .expect("MAX_BODY_SIZE_MB must be a number"),
+            password_policy: PasswordPolicy {
+                min_length: env::var("PASSWORD_MIN_LENGTH")
+                    .unwrap_or_else(|_| "8".to_string())
+                    .parse()
+                    .expect("PASSWORD_MIN_LENGTH must be a number"),
+                require_digit: env::var("PASSWORD_REQUIRE_DIGIT")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .expect("PASSWORD_REQUIRE_DIGIT must be true or false"),
+                require_uppercase: env::var("PASSWORD_REQUIRE_UPPERCASE")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .expect("PASSWORD_REQUIRE_UPPERCASE must be true or false"),
+                require_symbol: env::var("PASSWORD_REQUIRE_SYMBOL")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .expect("PASSWORD_REQUIRE_SYMBOL must be true or false"),
+            },
+            production: env::var("APP_ENV")
+                .map(|app_env| app_env.eq_ignore_ascii_case("production"))
+                .unwrap_or(false),
+            seed_admin_username: env::var("SEED_ADMIN_USERNAME")
+                .unwrap_or_else(|_| "admin".to_string()),
+            seed_admin_email: env::var("SEED_ADMIN_EMAIL")
+                .unwrap_or_else(|_| "admin@rodentcare.org".to_string()),
+            seed_admin_password: env::var("SEED_ADMIN_PASSWORD")
+                .ok()
+                .filter(|password| !password.is_empty()),
+            seed_admin_dev_password: env::var("SEED_ADMIN_DEV_PASSWORD")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .expect("SEED_ADMIN_DEV_PASSWORD must be true or false"),
+            max_page_limit: env::var("MAX_PAGE_LIMIT")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .expect("MAX_PAGE_LIMIT must be a number"),
+            clamp_page_limit: env::var("CLAMP_PAGE_LIMIT")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .expect("CLAMP_PAGE_LIMIT must be true or false"),
+            slow_query_threshold_ms: env::var("SLOW_QUERY_THRESHOLD_MS")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .expect("SLOW_QUERY_THRESHOLD_MS must be a number"),
+        };
+
+        // Never ship the well-known dev admin password to production
+        if config.production && config.seed_admin_dev_password {
+            panic!("SEED_ADMIN_DEV_PASSWORD must not be enabled when APP_ENV=production");
         }
+
+        config
+    }
+
+    /// Checks for the tokens this service issues, allowing for clock skew between services
+    pub fn jwt_validation(&self) -> jsonwebtoken::Validation {
+        let mut validation = jsonwebtoken::Validation::default();
+        validation.leeway = self.jwt_leeway_secs;
+        validation
+    }
+
+    /// Access token lifetime for a role, falling back to the global default
+    pub fn jwt_expiration_hours_for(&self, role: &str) -> i64 {
+        self.jwt_expiration_hours_by_role
+            .get(role)
+            .copied()
+            .unwrap_or(self.jwt_expiration_hours)
     }
 }
@@ -1,4 +1,14 @@
 use sqlx::{postgres::PgPoolOptions, PgPool};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config::Config;
+
+/// Longest wait between startup connection attempts
+const MAX_CONNECT_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Seed admin password used only when SEED_ADMIN_DEV_PASSWORD=true
+const DEV_ADMIN_PASSWORD: &str = "admin123";
 
 #[derive(Clone)]
 pub struct Database {
@@ -6,16 +16,37 @@ pub struct Database {
 }
 
 impl Database {
-    pub async fn new(database_url: &str) -> Result<Self, sqlx::Error> {
-        let pool = PgPoolOptions::new()
-            .max_connections(10)
-            .connect(database_url)
-            .await?;
-
-        Ok(Self { pool })
+    /// Connect to Postgres, retrying up to `DB_CONNECT_RETRIES` times with a
+    /// doubling delay so a database that starts after the service is tolerated
+    pub async fn new(config: &Config) -> Result<Self, sqlx::Error> {
+        let mut delay = Duration::from_millis(config.db_connect_retry_delay_ms);
+        let mut attempt = 0;
+
+        loop {
+            match PgPoolOptions::new()
+                .max_connections(10)
+                .connect(&config.database_url)
+                .await
+            {
+                Ok(pool) => return Ok(Self { pool }),
+                Err(e) if attempt < config.db_connect_retries => {
+                    attempt += 1;
+                    tracing::warn!(
+                        "Failed to connect to database: {}. Retrying in {:?} (attempt {}/{})",
+                        e,
+                        delay,
+                        attempt,
+                        config.db_connect_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(MAX_CONNECT_RETRY_DELAY);
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 
-    pub async fn run_migrations(&self) -> Result<(), sqlx::Error> {
+    pub async fn run_migrations(&self, config: &Config) -> Result<(), sqlx::Error> {
         // Create extension for UUID generation
         sqlx::query("CREATE EXTENSION IF NOT EXISTS \"uuid-ossp\"")
             .execute(&self.pool)
@@ -74,13 +105,21 @@ impl Database {
                 token VARCHAR(255) UNIQUE NOT NULL,
                 expires_at TIMESTAMPTZ NOT NULL,
                 created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
-                revoked BOOLEAN NOT NULL DEFAULT FALSE
+                revoked BOOLEAN NOT NULL DEFAULT FALSE,
+                session_start TIMESTAMPTZ NOT NULL DEFAULT NOW()
             )
             "#,
         )
         .execute(&self.pool)
         .await?;
 
+        // Tokens issued before sessions had a start time begin their session now
+        sqlx::query(
+            "ALTER TABLE refresh_tokens ADD COLUMN IF NOT EXISTS session_start TIMESTAMPTZ NOT NULL DEFAULT NOW()",
+        )
+        .execute(&self.pool)
+        .await?;
+
         // Create activity logs table
         sqlx::query(
             r#"
@@ -97,6 +136,31 @@ impl Database {
         .execute(&self.pool)
         .await?;
 
+        // Create pending approval notifications table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS pending_notifications (
+                id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+                user_id UUID UNIQUE NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                resolved_at TIMESTAMPTZ
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Backfill notifications for users registered before the table existed
+        sqlx::query(
+            r#"
+            INSERT INTO pending_notifications (user_id)
+            SELECT id FROM users WHERE status = 'pending'
+            ON CONFLICT (user_id) DO NOTHING
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
         // Create indexes (each in separate query)
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_refresh_tokens_user_id ON refresh_tokens(user_id)")
             .execute(&self.pool)
@@ -110,7 +174,7 @@ impl Database {
             .execute(&self.pool)
             .await?;
 
-        // Create default admin user if not exists
+        // Create the initial admin user if none exists
         let admin_exists: (bool,) = sqlx::query_as(
             "SELECT EXISTS(SELECT 1 FROM users WHERE role = 'admin')",
         )
@@ -118,25 +182,56 @@ impl Database {
         .await?;
 
         if !admin_exists.0 {
-            // Password: admin123 (hashed with bcrypt)
-            let password_hash = bcrypt::hash("admin123", bcrypt::DEFAULT_COST)
-                .expect("Failed to hash password");
-            
-            sqlx::query(
-                r#"
-                INSERT INTO users (username, email, password_hash, role, status)
-                VALUES ('admin', 'admin@rodentcare.org', $1, 'admin', 'active')
-                ON CONFLICT (username) DO NOTHING
-                "#,
-            )
-            .bind(&password_hash)
-            .execute(&self.pool)
-            .await?;
-
-            tracing::info!("Created default admin user (username: admin, password: admin123)");
+            self.seed_admin(config).await?;
         }
 
         tracing::info!("Database migrations completed successfully");
         Ok(())
     }
+
+    /// Seed the first admin account. The password comes from SEED_ADMIN_PASSWORD,
+    /// the dev default when SEED_ADMIN_DEV_PASSWORD is set (never in production),
+    /// or is randomly generated. It is never written to the logs.
+    async fn seed_admin(&self, config: &Config) -> Result<(), sqlx::Error> {
+        let (password, source) = match &config.seed_admin_password {
+            Some(password) => (password.clone(), "configured"),
+            None if config.seed_admin_dev_password => (DEV_ADMIN_PASSWORD.to_string(), "development default"),
+            None => (Uuid::new_v4().simple().to_string(), "randomly generated"),
+        };
+
+        let password_hash = bcrypt::hash(&password, bcrypt::DEFAULT_COST)
+            .expect("Failed to hash password");
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO users (username, email, password_hash, role, status)
+            VALUES ($1, $2, $3, 'admin', 'active')
+            ON CONFLICT (username) DO NOTHING
+            "#,
+        )
+        .bind(&config.seed_admin_username)
+        .bind(&config.seed_admin_email)
+        .bind(&password_hash)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            tracing::warn!(
+                "Skipped seeding the admin: user '{}' already exists",
+                config.seed_admin_username
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            "Created admin user '{}' with a {} password",
+            config.seed_admin_username,
+            source
+        );
+        if config.seed_admin_dev_password && config.seed_admin_password.is_none() {
+            tracing::warn!("The admin password is the well-known development default; do not use this outside development");
+        }
+
+        Ok(())
+    }
 }
@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -23,6 +23,9 @@ pub enum AppError {
     #[error("Token expired")]
     TokenExpired,
     
+    #[error("Session expired, please log in again")]
+    SessionExpired,
+    
     #[error("Access denied")]
     AccessDenied,
     
@@ -32,6 +35,9 @@ pub enum AppError {
     #[error("Account is inactive")]
     AccountInactive,
     
+    #[error("User is not awaiting approval")]
+    UserNotPending,
+    
     #[error("Validation error: {0}")]
     ValidationError(String),
     
@@ -40,6 +46,31 @@ pub enum AppError {
     
     #[error("Internal server error")]
     InternalError,
+
+    #[error("Request body too large")]
+    PayloadTooLarge,
+}
+
+impl AppError {
+    /// e.g. INVALID_CREDENTIALS, so clients needn't match on the message
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::InvalidCredentials => "INVALID_CREDENTIALS",
+            AppError::UserNotFound => "USER_NOT_FOUND",
+            AppError::UserAlreadyExists => "USER_ALREADY_EXISTS",
+            AppError::InvalidToken => "INVALID_TOKEN",
+            AppError::TokenExpired => "TOKEN_EXPIRED",
+            AppError::SessionExpired => "SESSION_EXPIRED",
+            AppError::AccessDenied => "ACCESS_DENIED",
+            AppError::AccountPendingApproval => "ACCOUNT_PENDING_APPROVAL",
+            AppError::AccountInactive => "ACCOUNT_INACTIVE",
+            AppError::UserNotPending => "USER_NOT_PENDING",
+            AppError::ValidationError(_) => "VALIDATION_ERROR",
+            AppError::DatabaseError(_) => "DATABASE_ERROR",
+            AppError::InternalError => "INTERNAL_ERROR",
+            AppError::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+        }
+    }
 }
 
 impl IntoResponse for AppError {
@@ -50,22 +81,40 @@ impl IntoResponse for AppError {
             AppError::UserAlreadyExists => (StatusCode::CONFLICT, self.to_string()),
             AppError::InvalidToken => (StatusCode::UNAUTHORIZED, self.to_string()),
             AppError::TokenExpired => (StatusCode::UNAUTHORIZED, self.to_string()),
+            AppError::SessionExpired => (StatusCode::UNAUTHORIZED, self.to_string()),
             AppError::AccessDenied => (StatusCode::FORBIDDEN, self.to_string()),
             AppError::AccountPendingApproval => (StatusCode::FORBIDDEN, self.to_string()),
             AppError::AccountInactive => (StatusCode::FORBIDDEN, self.to_string()),
+            AppError::UserNotPending => (StatusCode::CONFLICT, self.to_string()),
             AppError::ValidationError(_) => (StatusCode::BAD_REQUEST, self.to_string()),
             AppError::DatabaseError(e) => {
                 tracing::error!("Database error: {:?}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Database error".to_string())
             }
             AppError::InternalError => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+            AppError::PayloadTooLarge => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
         };
 
         let body = Json(json!({
             "success": false,
             "error": error_message,
+            "code": self.code(),
         }));
 
         (status, body).into_response()
     }
 }
+
+/// Replace axum's plain-text 413 (from `DefaultBodyLimit`) with the JSON error envelope
+pub async fn json_payload_too_large(response: Response) -> Response {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE && !is_json {
+        return AppError::PayloadTooLarge.into_response();
+    }
+
+    response
+}
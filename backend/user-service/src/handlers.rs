@@ -1,10 +1,13 @@
 use axum::{
+    body::Body,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
-use chrono::{Duration, Utc};
-use jsonwebtoken::{encode, decode, EncodingKey, DecodingKey, Header, Validation};
+use chrono::{DateTime, Duration, Utc};
+use futures::StreamExt;
+use jsonwebtoken::{encode, decode, EncodingKey, DecodingKey, Header};
 use serde::Deserialize;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -13,6 +16,7 @@ use validator::Validate;
 use crate::{
     error::AppError,
     models::*,
+    timing::QueryTimer,
     AppState,
 };
 
@@ -35,7 +39,7 @@ async fn extract_claims_from_header(
     let claims = jsonwebtoken::decode::<Claims>(
         token,
         &DecodingKey::from_secret(state.config.jwt_secret.as_bytes()),
-        &Validation::default(),
+        &state.config.jwt_validation(),
     )
     .map_err(|e| match e.kind() {
         jsonwebtoken::errors::ErrorKind::ExpiredSignature => AppError::TokenExpired,
@@ -46,19 +50,50 @@ async fn extract_claims_from_header(
     Ok(claims)
 }
 
+// Helper function to check a new password against the configured policy
+fn validate_password(config: &crate::config::Config, password: &str) -> Result<(), AppError> {
+    let unmet = config.password_policy.unmet_requirements(password);
+    if unmet.is_empty() {
+        return Ok(());
+    }
+
+    Err(AppError::ValidationError(format!(
+        "Password must {}",
+        unmet.join(", ")
+    )))
+}
+
+// Helper function to resolve a page size against the configured maximum:
+// over-limit requests are rejected, or clamped when CLAMP_PAGE_LIMIT is set
+fn page_limit(config: &crate::config::Config, requested: Option<i64>, default: i64) -> Result<i64, AppError> {
+    let limit = requested.unwrap_or(default.min(config.max_page_limit));
+    if limit <= config.max_page_limit {
+        return Ok(limit);
+    }
+    if config.clamp_page_limit {
+        return Ok(config.max_page_limit);
+    }
+    Err(AppError::ValidationError(format!(
+        "limit must be at most {}",
+        config.max_page_limit
+    )))
+}
+
 // Helper function to generate JWT tokens
 fn generate_tokens(
     user: &User,
     config: &crate::config::Config,
 ) -> Result<(String, String, i64), AppError> {
     let now = Utc::now();
-    let expires_in = config.jwt_expiration_hours * 3600; // in seconds
+    let role = user.role.to_string();
+    let expiration_hours = config.jwt_expiration_hours_for(&role);
+    let expires_in = expiration_hours * 3600; // in seconds
     
     let access_claims = Claims {
         sub: user.id.to_string(),
         username: user.username.clone(),
-        role: user.role.to_string(),
-        exp: (now + Duration::hours(config.jwt_expiration_hours)).timestamp() as usize,
+        role,
+        exp: (now + Duration::hours(expiration_hours)).timestamp() as usize,
         iat: now.timestamp() as usize,
     };
 
@@ -81,6 +116,7 @@ pub async fn register(
 ) -> Result<(StatusCode, Json<MessageResponse>), AppError> {
     // Validate input
     payload.validate().map_err(|e| AppError::ValidationError(e.to_string()))?;
+    validate_password(&state.config, &payload.password)?;
 
     // Check if user can register as admin (only existing admin can create admin)
     if payload.role == UserRole::Admin {
@@ -107,19 +143,26 @@ pub async fn register(
         .map_err(|_| AppError::InternalError)?;
 
     // Insert user with pending status
-    sqlx::query(
+    let (user_id,): (Uuid,) = sqlx::query_as(
         r#"
         INSERT INTO users (username, email, password_hash, role, status)
         VALUES ($1, $2, $3, $4, 'pending')
+        RETURNING id
         "#,
     )
     .bind(&payload.username)
     .bind(&payload.email)
     .bind(&password_hash)
     .bind(&payload.role)
-    .execute(&state.db.pool)
+    .fetch_one(&state.db.pool)
     .await?;
 
+    // Queue an approval notification for admins
+    sqlx::query("INSERT INTO pending_notifications (user_id) VALUES ($1)")
+        .bind(user_id)
+        .execute(&state.db.pool)
+        .await?;
+
     tracing::info!("New user registered: {} with role {:?}", payload.username, payload.role);
 
     Ok((
@@ -217,6 +260,15 @@ pub async fn refresh_token(
     .await?
     .ok_or(AppError::InvalidToken)?;
 
+    // Rotation keeps a session alive only up to its absolute lifetime
+    if stored_token.session_start + Duration::days(state.config.session_max_lifetime_days) <= Utc::now() {
+        sqlx::query("UPDATE refresh_tokens SET revoked = TRUE WHERE id = $1")
+            .bind(stored_token.id)
+            .execute(&state.db.pool)
+            .await?;
+        return Err(AppError::SessionExpired);
+    }
+
     // Get user
     let user: User = sqlx::query_as(
         "SELECT * FROM users WHERE id = $1 AND status = 'active'",
@@ -235,17 +287,18 @@ pub async fn refresh_token(
     // Generate new tokens
     let (access_token, new_refresh_token, expires_in) = generate_tokens(&user, &state.config)?;
 
-    // Store new refresh token
+    // Store new refresh token in the same session
     let refresh_expires_at = Utc::now() + Duration::days(state.config.refresh_token_expiration_days);
     sqlx::query(
         r#"
-        INSERT INTO refresh_tokens (user_id, token, expires_at)
-        VALUES ($1, $2, $3)
+        INSERT INTO refresh_tokens (user_id, token, expires_at, session_start)
+        VALUES ($1, $2, $3, $4)
         "#,
     )
     .bind(&user.id)
     .bind(&new_refresh_token)
     .bind(&refresh_expires_at)
+    .bind(stored_token.session_start)
     .execute(&state.db.pool)
     .await?;
 
@@ -295,36 +348,47 @@ pub async fn validate_token(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<ValidateTokenRequest>,
 ) -> Result<Json<TokenValidationResponse>, AppError> {
+    let invalid = || {
+        Json(TokenValidationResponse {
+            valid: false,
+            user_id: None,
+            username: None,
+            role: None,
+        })
+    };
+
     let claims = match decode::<Claims>(
         &payload.token,
         &DecodingKey::from_secret(state.config.jwt_secret.as_bytes()),
-        &Validation::default(),
+        &state.config.jwt_validation(),
     ) {
         Ok(data) => data.claims,
-        Err(_) => {
-            return Ok(Json(TokenValidationResponse {
-                valid: false,
-                user_id: None,
-                username: None,
-                role: None,
-            }));
-        }
+        Err(_) => return Ok(invalid()),
     };
 
-    let user_id = Uuid::parse_str(&claims.sub).ok();
+    // A signed token with a malformed subject or unknown role is still unusable;
+    // reject it here instead of letting it through without a role
     let role = match claims.role.as_str() {
-        "admin" => Some(UserRole::Admin),
-        "caretaker" => Some(UserRole::Caretaker),
-        "veterinarian" => Some(UserRole::Veterinarian),
-        "volunteer" => Some(UserRole::Volunteer),
-        _ => None,
+        "admin" => UserRole::Admin,
+        "caretaker" => UserRole::Caretaker,
+        "veterinarian" => UserRole::Veterinarian,
+        "volunteer" => UserRole::Volunteer,
+        other => {
+            tracing::warn!("Rejecting token for {} with unknown role {:?}", claims.username, other);
+            return Ok(invalid());
+        }
+    };
+
+    let Ok(user_id) = Uuid::parse_str(&claims.sub) else {
+        tracing::warn!("Rejecting token for {} with malformed subject", claims.username);
+        return Ok(invalid());
     };
 
     Ok(Json(TokenValidationResponse {
         valid: true,
-        user_id,
+        user_id: Some(user_id),
         username: Some(claims.username),
-        role,
+        role: Some(role),
     }))
 }
 
@@ -361,8 +425,9 @@ pub async fn list_users(
     State(state): State<Arc<AppState>>,
     Query(query): Query<ListUsersQuery>,
 ) -> Result<Json<UsersListResponse>, AppError> {
+    let _timer = QueryTimer::start(&state.config, "list_users");
     let page = query.page.unwrap_or(1).max(1);
-    let limit = query.limit.unwrap_or(20).min(100);
+    let limit = page_limit(&state.config, query.limit, 20)?;
     let offset = (page - 1) * limit;
 
     let mut sql = String::from("SELECT * FROM users WHERE 1=1");
@@ -391,7 +456,7 @@ pub async fn list_users(
     Ok(Json(UsersListResponse {
         success: true,
         users: users.into_iter().map(|u| u.into()).collect(),
-        total: total.0,
+        pagination: Pagination::new(total.0, page, limit),
     }))
 }
 
@@ -411,6 +476,96 @@ pub async fn get_user(
     Ok(Json(user.into()))
 }
 
+// GET /api/users/pending/count (Admin only)
+pub async fn get_pending_count(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+) -> Result<Json<PendingCountResponse>, AppError> {
+    let claims = extract_claims_from_header(&state, &headers).await?;
+    if claims.role != "admin" {
+        return Err(AppError::AccessDenied);
+    }
+
+    let (count,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM pending_notifications WHERE resolved_at IS NULL",
+    )
+    .fetch_one(&state.db.pool)
+    .await?;
+
+    Ok(Json(PendingCountResponse {
+        success: true,
+        count,
+    }))
+}
+
+// POST /api/users (Admin only)
+pub async fn create_user(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<CreateUserRequest>,
+) -> Result<(StatusCode, Json<UserResponse>), AppError> {
+    let claims = extract_claims_from_header(&state, &headers).await?;
+    if claims.role != "admin" {
+        return Err(AppError::AccessDenied);
+    }
+    let admin_id = Uuid::parse_str(&claims.sub).map_err(|_| AppError::InvalidToken)?;
+
+    payload.validate().map_err(|e| AppError::ValidationError(e.to_string()))?;
+    validate_password(&state.config, &payload.password)?;
+
+    // Check if username or email already exists
+    let existing_user: Option<(Uuid,)> = sqlx::query_as(
+        "SELECT id FROM users WHERE username = $1 OR email = $2",
+    )
+    .bind(&payload.username)
+    .bind(&payload.email)
+    .fetch_optional(&state.db.pool)
+    .await?;
+
+    if existing_user.is_some() {
+        return Err(AppError::UserAlreadyExists);
+    }
+
+    // Hash password
+    let password_hash = bcrypt::hash(&payload.password, bcrypt::DEFAULT_COST)
+        .map_err(|_| AppError::InternalError)?;
+
+    // Admin-created users skip the approval queue
+    let user: User = sqlx::query_as(
+        r#"
+        INSERT INTO users (username, email, password_hash, role, status)
+        VALUES ($1, $2, $3, $4, 'active')
+        RETURNING *
+        "#,
+    )
+    .bind(&payload.username)
+    .bind(&payload.email)
+    .bind(&password_hash)
+    .bind(&payload.role)
+    .fetch_one(&state.db.pool)
+    .await?;
+
+    // Log activity
+    sqlx::query(
+        r#"
+        INSERT INTO activity_logs (user_id, action, details)
+        VALUES ($1, 'admin_create_user', $2::jsonb)
+        "#,
+    )
+    .bind(admin_id)
+    .bind(serde_json::json!({
+        "target_user_id": user.id.to_string(),
+        "username": user.username,
+        "role": user.role.to_string()
+    }))
+    .execute(&state.db.pool)
+    .await?;
+
+    tracing::info!("User {} created by admin {} with role {}", user.username, claims.username, user.role);
+
+    Ok((StatusCode::CREATED, Json(user.into())))
+}
+
 // PUT /api/users/:id/role (Admin only)
 pub async fn update_user_role(
     State(state): State<Arc<AppState>>,
@@ -505,12 +660,122 @@ pub async fn update_user_status(
     .execute(&state.db.pool)
     .await?;
 
+    // Any decision on a pending account resolves its approval notification
+    if payload.status != UserStatus::Pending {
+        sqlx::query(
+            "UPDATE pending_notifications SET resolved_at = NOW() WHERE user_id = $1 AND resolved_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(&state.db.pool)
+        .await?;
+    }
+
     Ok(Json(MessageResponse {
         success: true,
         message: "User status updated successfully".to_string(),
     }))
 }
 
+// POST /api/users/:id/resend-approval-notification (Admin only)
+pub async fn resend_approval_notification(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+    headers: axum::http::HeaderMap,
+) -> Result<Json<MessageResponse>, AppError> {
+    let claims = extract_claims_from_header(&state, &headers).await?;
+    if claims.role != "admin" {
+        return Err(AppError::AccessDenied);
+    }
+    let admin_id = Uuid::parse_str(&claims.sub).map_err(|_| AppError::InvalidToken)?;
+
+    let (status,): (UserStatus,) = sqlx::query_as("SELECT status FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(&state.db.pool)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+
+    if status != UserStatus::Pending {
+        return Err(AppError::UserNotPending);
+    }
+
+    // Re-open the approval notification as if the user had just registered
+    sqlx::query(
+        r#"
+        INSERT INTO pending_notifications (user_id) VALUES ($1)
+        ON CONFLICT (user_id) DO UPDATE SET created_at = NOW(), resolved_at = NULL
+        "#,
+    )
+    .bind(user_id)
+    .execute(&state.db.pool)
+    .await?;
+
+    // Log activity
+    sqlx::query(
+        r#"
+        INSERT INTO activity_logs (user_id, action, details)
+        VALUES ($1, 'resend_approval_notification', $2::jsonb)
+        "#,
+    )
+    .bind(admin_id)
+    .bind(serde_json::json!({
+        "target_user_id": user_id.to_string()
+    }))
+    .execute(&state.db.pool)
+    .await?;
+
+    Ok(Json(MessageResponse {
+        success: true,
+        message: "Approval notification re-sent".to_string(),
+    }))
+}
+
+// POST /api/users/:id/force-logout (Admin only)
+pub async fn force_logout(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+    headers: axum::http::HeaderMap,
+) -> Result<Json<MessageResponse>, AppError> {
+    let claims = extract_claims_from_header(&state, &headers).await?;
+    if claims.role != "admin" {
+        return Err(AppError::AccessDenied);
+    }
+    let admin_id = Uuid::parse_str(&claims.sub).map_err(|_| AppError::InvalidToken)?;
+
+    let exists: Option<(Uuid,)> = sqlx::query_as("SELECT id FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(&state.db.pool)
+        .await?;
+    if exists.is_none() {
+        return Err(AppError::UserNotFound);
+    }
+
+    // Revoke all refresh tokens for the target user
+    let result = sqlx::query("UPDATE refresh_tokens SET revoked = TRUE WHERE user_id = $1 AND revoked = FALSE")
+        .bind(user_id)
+        .execute(&state.db.pool)
+        .await?;
+
+    // Log activity
+    sqlx::query(
+        r#"
+        INSERT INTO activity_logs (user_id, action, details)
+        VALUES ($1, 'admin_force_logout', $2::jsonb)
+        "#,
+    )
+    .bind(admin_id)
+    .bind(serde_json::json!({
+        "target_user_id": user_id.to_string(),
+        "revoked_tokens": result.rows_affected()
+    }))
+    .execute(&state.db.pool)
+    .await?;
+
+    Ok(Json(MessageResponse {
+        success: true,
+        message: format!("Revoked {} session(s)", result.rows_affected()),
+    }))
+}
+
 // DELETE /api/users/:id (Admin only)
 pub async fn delete_user(
     State(state): State<Arc<AppState>>,
@@ -562,8 +827,9 @@ pub async fn get_user_activity_logs(
     Path(user_id): Path<Uuid>,
     Query(query): Query<ListUsersQuery>,
 ) -> Result<Json<ActivityLogsResponse>, AppError> {
+    let _timer = QueryTimer::start(&state.config, "get_user_activity_logs");
     let page = query.page.unwrap_or(1).max(1);
-    let limit = query.limit.unwrap_or(50).min(100);
+    let limit = page_limit(&state.config, query.limit, 50)?;
     let offset = (page - 1) * limit;
 
     let logs: Vec<ActivityLog> = sqlx::query_as(
@@ -590,10 +856,114 @@ pub async fn get_user_activity_logs(
     Ok(Json(ActivityLogsResponse {
         success: true,
         logs,
-        total: total.0,
+        pagination: Pagination::new(total.0, page, limit),
     }))
 }
 
+// Query parameters for exporting activity logs
+#[derive(Debug, Deserialize)]
+pub struct ActivityLogExportQuery {
+    pub from_date: Option<DateTime<Utc>>,
+    pub to_date: Option<DateTime<Utc>>,
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break.
+/// Values a spreadsheet would evaluate as a formula get a leading `'`.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn activity_log_csv_row(log: &ActivityLog) -> String {
+    let details = log.details.as_ref().map(|d| d.to_string()).unwrap_or_default();
+    format!(
+        "{},{},{},{},{}\n",
+        log.id,
+        csv_field(&log.action),
+        csv_field(&details),
+        csv_field(log.ip_address.as_deref().unwrap_or("")),
+        log.created_at.to_rfc3339(),
+    )
+}
+
+// GET /api/users/:id/activity-logs/export.csv (Admin only)
+pub async fn export_user_activity_logs_csv(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+    headers: axum::http::HeaderMap,
+    Query(query): Query<ActivityLogExportQuery>,
+) -> Result<Response, AppError> {
+    let claims = extract_claims_from_header(&state, &headers).await?;
+    if claims.role != "admin" {
+        return Err(AppError::AccessDenied);
+    }
+
+    if let (Some(from), Some(to)) = (query.from_date, query.to_date) {
+        if from > to {
+            return Err(AppError::ValidationError("from_date must be before to_date".to_string()));
+        }
+    }
+
+    let (user_exists,): (bool,) = sqlx::query_as("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)")
+        .bind(user_id)
+        .fetch_one(&state.db.pool)
+        .await?;
+    if !user_exists {
+        return Err(AppError::UserNotFound);
+    }
+
+    // Rows are written out as they come off the query instead of buffering the export
+    let pool = state.db.pool.clone();
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<String, sqlx::Error>>(64);
+    tokio::spawn(async move {
+        let mut rows = sqlx::query_as::<_, ActivityLog>(
+            r#"
+            SELECT * FROM activity_logs
+            WHERE user_id = $1
+              AND ($2::timestamptz IS NULL OR created_at >= $2)
+              AND ($3::timestamptz IS NULL OR created_at <= $3)
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(query.from_date)
+        .bind(query.to_date)
+        .fetch(&pool);
+
+        if tx.send(Ok("id,action,details,ip_address,created_at\n".to_string())).await.is_err() {
+            return;
+        }
+        while let Some(row) = rows.next().await {
+            let failed = row.is_err();
+            if tx.send(row.map(|log| activity_log_csv_row(&log))).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    let body = futures::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|chunk| (chunk, rx)) });
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"activity-logs-{}.csv\"", user_id),
+            ),
+        ],
+        Body::from_stream(body),
+    )
+        .into_response())
+}
+
 // PUT /api/users/me - Update current user's profile
 pub async fn update_profile(
     State(state): State<Arc<AppState>>,
@@ -615,6 +985,8 @@ pub async fn update_profile(
 
     // Update password if provided
     if let Some(ref password) = payload.password {
+        validate_password(&state.config, password)?;
+
         let password_hash = bcrypt::hash(password, bcrypt::DEFAULT_COST)
             .map_err(|_| AppError::InternalError)?;
 
@@ -5,8 +5,9 @@ mod handlers;
 mod models;
 mod middleware;
 mod routes;
+mod timing;
 
-use axum::Router;
+use axum::{extract::DefaultBodyLimit, Router};
 use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
@@ -22,12 +23,17 @@ pub struct AppState {
 
 #[tokio::main]
 async fn main() {
-    // Initialize tracing
+    // Initialize tracing (LOG_FORMAT=json for machine-readable output)
+    let json_logs = std::env::var("LOG_FORMAT")
+        .map(|format| format.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+
     tracing_subscriber::registry()
         .with(tracing_subscriber::EnvFilter::new(
             std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
         ))
-        .with(tracing_subscriber::fmt::layer())
+        .with(json_logs.then(|| tracing_subscriber::fmt::layer().json()))
+        .with((!json_logs).then(tracing_subscriber::fmt::layer))
         .init();
 
     // Load configuration
@@ -37,12 +43,12 @@ async fn main() {
     tracing::info!("Starting User Service on port {}", config.port);
 
     // Initialize database
-    let db = Database::new(&config.database_url)
+    let db = Database::new(&config)
         .await
         .expect("Failed to connect to database");
 
     // Run migrations
-    db.run_migrations()
+    db.run_migrations(&config)
         .await
         .expect("Failed to run migrations");
 
@@ -58,6 +64,8 @@ async fn main() {
     // Build router
     let app = Router::new()
         .nest("/api", routes::create_routes())
+        .layer(DefaultBodyLimit::max(config.max_body_size_mb * 1024 * 1024))
+        .layer(axum::middleware::map_response(error::json_payload_too_large))
         .layer(cors)
         .layer(TraceLayer::new_for_http())
         .with_state(state);
@@ -4,7 +4,7 @@ use axum::{
     middleware::Next,
     response::Response,
 };
-use jsonwebtoken::{decode, DecodingKey, Validation};
+use jsonwebtoken::{decode, DecodingKey};
 use std::sync::Arc;
 
 use crate::{error::AppError, models::Claims, AppState};
@@ -27,7 +27,7 @@ pub async fn auth_middleware(
     let claims = decode::<Claims>(
         token,
         &DecodingKey::from_secret(state.config.jwt_secret.as_bytes()),
-        &Validation::default(),
+        &state.config.jwt_validation(),
     )
     .map_err(|e| {
         tracing::debug!("Token validation failed: {:?}", e);
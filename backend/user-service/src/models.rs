@@ -55,6 +55,7 @@ pub struct RefreshToken {
     pub expires_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub revoked: bool,
+    pub session_start: DateTime<Utc>, // login time, carried over on every rotation
 }
 
 #[derive(Debug, Clone, FromRow, Serialize)]
@@ -75,8 +76,17 @@ pub struct RegisterRequest {
     pub username: String,
     #[validate(email(message = "Invalid email format"))]
     pub email: String,
-    #[validate(length(min = 6, message = "Password must be at least 6 characters"))]
-    pub password: String,
+    pub password: String, // checked against the configured password policy
+    pub role: UserRole,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateUserRequest {
+    #[validate(length(min = 3, max = 50, message = "Username must be between 3 and 50 characters"))]
+    pub username: String,
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+    pub password: String, // checked against the configured password policy
     pub role: UserRole,
 }
 
@@ -103,8 +113,7 @@ pub struct UpdateUserStatusRequest {
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct UpdateProfileRequest {
-    #[validate(length(min = 6, message = "Password must be at least 6 characters"))]
-    pub password: Option<String>,
+    pub password: Option<String>, // checked against the configured password policy
 }
 
 #[derive(Debug, Deserialize)]
@@ -114,6 +123,32 @@ pub struct ValidateTokenRequest {
 
 // Response DTOs
 
+/// Paging metadata shared by list responses; flattened into the response body
+#[derive(Debug, Serialize)]
+pub struct Pagination {
+    pub total: i64,
+    pub page: i64,
+    pub limit: i64,
+    pub total_pages: i64,
+    pub has_next: bool,
+    pub has_prev: bool,
+}
+
+impl Pagination {
+    pub fn new(total: i64, page: i64, limit: i64) -> Self {
+        let limit = limit.max(1);
+        let total_pages = (total + limit - 1) / limit;
+        Self {
+            total,
+            page,
+            limit,
+            total_pages,
+            has_next: page < total_pages,
+            has_prev: page > 1,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct AuthResponse {
     pub success: bool,
@@ -151,7 +186,8 @@ impl From<User> for UserResponse {
 pub struct UsersListResponse {
     pub success: bool,
     pub users: Vec<UserResponse>,
-    pub total: i64,
+    #[serde(flatten)]
+    pub pagination: Pagination,
 }
 
 #[derive(Debug, Serialize)]
@@ -160,6 +196,12 @@ pub struct MessageResponse {
     pub message: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct PendingCountResponse {
+    pub success: bool,
+    pub count: i64,
+}
+
 #[derive(Debug, Serialize)]
 pub struct TokenValidationResponse {
     pub valid: bool,
@@ -172,7 +214,8 @@ pub struct TokenValidationResponse {
 pub struct ActivityLogsResponse {
     pub success: bool,
     pub logs: Vec<ActivityLog>,
-    pub total: i64,
+    #[serde(flatten)]
+    pub pagination: Pagination,
 }
 
 // JWT Claims
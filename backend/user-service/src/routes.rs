@@ -18,10 +18,14 @@ pub fn create_routes() -> Router<Arc<AppState>> {
         .route("/auth/logout", post(handlers::logout))
         .route("/users/me", get(handlers::get_current_user).put(handlers::update_profile))
         // Admin routes (role checked in handlers)
-        .route("/users", get(handlers::list_users))
+        .route("/users", get(handlers::list_users).post(handlers::create_user))
+        .route("/users/pending/count", get(handlers::get_pending_count))
         .route("/users/:id", get(handlers::get_user))
         .route("/users/:id/role", put(handlers::update_user_role))
         .route("/users/:id/status", put(handlers::update_user_status))
+        .route("/users/:id/force-logout", post(handlers::force_logout))
+        .route("/users/:id/resend-approval-notification", post(handlers::resend_approval_notification))
         .route("/users/:id", delete(handlers::delete_user))
         .route("/users/:id/activity-logs", get(handlers::get_user_activity_logs))
+        .route("/users/:id/activity-logs/export.csv", get(handlers::export_user_activity_logs_csv))
 }
@@ -0,0 +1,35 @@
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+
+/// Logs a warning when dropped if the handler it was started in ran past the
+/// configured slow query threshold. A threshold of 0 disables it.
+pub struct QueryTimer {
+    handler: &'static str,
+    threshold: Duration,
+    started: Instant,
+}
+
+impl QueryTimer {
+    pub fn start(config: &Config, handler: &'static str) -> Self {
+        Self {
+            handler,
+            threshold: Duration::from_millis(config.slow_query_threshold_ms),
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Drop for QueryTimer {
+    fn drop(&mut self) {
+        let elapsed = self.started.elapsed();
+        if !self.threshold.is_zero() && elapsed >= self.threshold {
+            tracing::warn!(
+                "Slow query in {}: took {} ms (threshold {} ms)",
+                self.handler,
+                elapsed.as_millis(),
+                self.threshold.as_millis()
+            );
+        }
+    }
+}
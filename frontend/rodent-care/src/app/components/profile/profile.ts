@@ -86,7 +86,7 @@ export class Profile implements OnInit, OnDestroy {
           '',
           [
             Validators.required,
-            Validators.minLength(6),
+            Validators.minLength(8),
           ],
         ],
         confirmPassword: ['', [Validators.required]],
@@ -254,7 +254,7 @@ export class Profile implements OnInit, OnDestroy {
     const control = this.passwordControl;
     if (control?.errors?.['required']) return 'Password is required';
     if (control?.errors?.['minlength'])
-      return 'Password must be at least 6 characters';
+      return 'Password must be at least 8 characters';
     return '';
   }
 
@@ -1,4 +1,5 @@
 import { ActivityType } from './ActivityType';
+import { ActivityAssistant } from './CreateActivityRequest';
 
 export interface ActivityResponse {
   id: string;
@@ -9,6 +10,7 @@ export interface ActivityResponse {
   notes: string | null;
   recorded_by: string;
   recorded_by_name: string;
+  assisted_by: ActivityAssistant[];
   recorded_at: string;
   created_at: string;
 }
@@ -17,8 +19,12 @@ export interface ActivityListResponse {
   success: boolean;
   activities: ActivityResponse[];
   total: number;
+  total_minutes: number;
   page: number;
   limit: number;
+  total_pages: number;
+  has_next: boolean;
+  has_prev: boolean;
 }
 
 export interface SingleActivityResponse {
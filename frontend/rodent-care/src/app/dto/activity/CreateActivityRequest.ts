@@ -1,9 +1,15 @@
 import { ActivityType } from './ActivityType';
 
+export interface ActivityAssistant {
+  user_id: string;
+  name: string;
+}
+
 export interface CreateActivityRequest {
   activity_type: ActivityType;
   duration_minutes: number;
   intensity: number | null;
   notes: string | null;
   recorded_at: string | null;
+  assisted_by?: ActivityAssistant[];
 }
@@ -19,6 +19,9 @@ export interface DailyRecordListResponse {
   total: number;
   page: number;
   limit: number;
+  total_pages: number;
+  has_next: boolean;
+  has_prev: boolean;
 }
 
 export interface SingleDailyRecordResponse {
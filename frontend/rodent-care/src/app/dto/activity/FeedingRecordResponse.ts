@@ -17,8 +17,12 @@ export interface FeedingRecordListResponse {
   success: boolean;
   feeding_records: FeedingRecordResponse[];
   total: number;
+  total_grams: number;
   page: number;
   limit: number;
+  total_pages: number;
+  has_next: boolean;
+  has_prev: boolean;
 }
 
 export interface SingleFeedingRecordResponse {
@@ -20,6 +20,7 @@ export interface DayOfWeekActivity {
 export interface RodentActivityStats {
   rodent_id: string;
   rodent_name: string;
+  species: string;
   total_minutes: number;
   session_count: number;
 }
@@ -3,6 +3,7 @@ export interface FoodTypeStats {
   total_grams: number;
   feeding_count: number;
   avg_quantity: number;
+  consumption_rate: number;
 }
 
 export interface HourlyFeeding {
@@ -14,6 +15,7 @@ export interface HourlyFeeding {
 export interface RodentFeedingStats {
   rodent_id: string;
   rodent_name: string;
+  species: string;
   total_grams: number;
   feeding_count: number;
 }
@@ -22,6 +24,8 @@ export interface FeedingAnalyticsResponse {
   success: boolean;
   total_food_grams: number;
   avg_daily_food: number;
+  total_water_grams: number;
+  avg_daily_water: number;
   by_food_type: FoodTypeStats[];
   feeding_by_hour: HourlyFeeding[];
   consumption_rate: number;
@@ -23,6 +23,9 @@ export interface MedicalRecordListResponse {
   total: number;
   page: number;
   limit: number;
+  total_pages: number;
+  has_next: boolean;
+  has_prev: boolean;
 }
 
 export interface SingleMedicalRecordResponse {
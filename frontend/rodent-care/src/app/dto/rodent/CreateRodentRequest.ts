@@ -12,4 +12,5 @@ export interface CreateRodentRequest {
   status: RodentStatus;
   notes?: string | null;
   intake_date?: string | null;
+  is_sample?: boolean;
 }
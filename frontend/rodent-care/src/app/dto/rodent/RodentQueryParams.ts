@@ -8,6 +8,7 @@ export interface RodentQueryParams {
   chip_id?: string;
   sort_by?: 'age' | 'intake_date' | 'name' | 'created_at';
   sort_order?: 'asc' | 'desc';
+  exclude_samples?: boolean;
   page?: number;
   limit?: number;
 }
@@ -14,6 +14,7 @@ export interface RodentResponse {
   chip_id: string | null;
   status: RodentStatus;
   notes: string | null;
+  is_sample: boolean;
   images: RodentImage[];
   intake_date: string;
   created_at: string;
@@ -26,6 +27,9 @@ export interface RodentListResponse {
   total: number;
   page: number;
   limit: number;
+  total_pages: number;
+  has_next: boolean;
+  has_prev: boolean;
 }
 
 export interface SingleRodentResponse {
@@ -9,4 +9,5 @@ export interface UpdateRodentRequest {
   date_of_birth_estimated?: boolean;
   chip_id?: string | null;
   notes?: string | null;
+  is_sample?: boolean;
 }